@@ -1,7 +1,7 @@
 use crate::{
     ndarray::NdArray,
     ndarray::{matrix::matmul_impl, shape::Shape},
-    DuResult,
+    DuError, DuResult,
 };
 use std::f32::consts::E;
 
@@ -11,10 +11,12 @@ use rayon::prelude::*;
 #[cfg(feature = "rayon")]
 pub fn relu(inp: &NdArray<f32>) -> NdArray<f32> {
     let mut out = inp.clone();
-    out.par_iter_rows_mut().for_each(|row| {
-        for v in row {
-            *v = v.max(0.0);
-        }
+    crate::thread_pool::install(|| {
+        out.par_iter_rows_mut().for_each(|row| {
+            for v in row {
+                *v = v.max(0.0);
+            }
+        })
     });
     out
 }
@@ -28,10 +30,12 @@ pub fn relu(inp: &NdArray<f32>) -> NdArray<f32> {
 #[cfg(feature = "rayon")]
 pub fn drelu_dz(inputs: &NdArray<f32>, dvalues: &NdArray<f32>) -> NdArray<f32> {
     let mut res = dvalues.clone();
-    res.iter_rows_mut()
-        .zip(inputs.iter_rows())
-        .par_bridge()
-        .for_each(|(dx, dz)| _drelu(dx, dz));
+    crate::thread_pool::install(|| {
+        res.iter_rows_mut()
+            .zip(inputs.iter_rows())
+            .par_bridge()
+            .for_each(|(dx, dz)| _drelu(dx, dz))
+    });
     res
 }
 
@@ -103,31 +107,55 @@ pub fn softmax(inp: &NdArray<f32>) -> DuResult<NdArray<f32>> {
 pub fn dsoftmax(output: &NdArray<f32>, dvalues: &NdArray<f32>) -> DuResult<NdArray<f32>> {
     let mut res = NdArray::new(dvalues.shape().clone());
 
-    let collen = output.shape().last();
+    crate::thread_pool::install(|| {
+        res.as_mut_slice()
+            .par_chunks_mut(dvalues.shape().last().max(1) as usize)
+            .enumerate()
+            .try_for_each(|(i, row)| dsoftmax_row(output, dvalues, i, row))
+    })?;
+
+    Ok(res)
+}
+
+/// Softmax backwards pass, calculating gradient
+#[cfg(not(feature = "rayon"))]
+pub fn dsoftmax(output: &NdArray<f32>, dvalues: &NdArray<f32>) -> DuResult<NdArray<f32>> {
+    let mut res = NdArray::new(dvalues.shape().clone());
 
     res.as_mut_slice()
-        .par_chunks_mut(dvalues.shape().last() as usize)
+        .chunks_mut(dvalues.shape().last().max(1) as usize)
         .enumerate()
-        .try_for_each(|(i, row)| {
-            let output = output.get_row(&[i as u32]).unwrap();
-            let dvalues = dvalues.get_row(&[i as u32]).unwrap();
+        .try_for_each(|(i, row)| dsoftmax_row(output, dvalues, i, row))?;
 
-            let mut jacobian_matrix = NdArray::new([collen, collen]);
-            let mut dotcache = NdArray::new([collen, collen]);
-            diagflat(output, &mut jacobian_matrix);
-            matmul_impl([collen, 1, collen], output, output, dotcache.as_mut_slice())?;
+    Ok(res)
+}
 
-            jacobian_matrix = jacobian_matrix.sub(&dotcache)?;
+/// Gradient of the `i`-th row, written to `row`
+fn dsoftmax_row(
+    output: &NdArray<f32>,
+    dvalues: &NdArray<f32>,
+    i: usize,
+    row: &mut [f32],
+) -> Result<(), crate::ndarray::NdArrayError> {
+    // scalars are treated as vectors of 1 element
+    let collen = output.shape().last().max(1);
 
-            matmul_impl(
-                [collen, collen, 1],
-                jacobian_matrix.as_slice(),
-                dvalues,
-                row,
-            )
-        })?;
+    let output = output.get_row(&[i as u32]).unwrap();
+    let dvalues = dvalues.get_row(&[i as u32]).unwrap();
 
-    Ok(res)
+    let mut jacobian_matrix = NdArray::new([collen, collen]);
+    let mut dotcache = NdArray::new([collen, collen]);
+    diagflat(output, &mut jacobian_matrix);
+    matmul_impl([collen, 1, collen], output, output, dotcache.as_mut_slice())?;
+
+    jacobian_matrix = jacobian_matrix.sub(&dotcache)?;
+
+    matmul_impl(
+        [collen, collen, 1],
+        jacobian_matrix.as_slice(),
+        dvalues,
+        row,
+    )
 }
 
 fn diagflat(output: &[f32], mat: &mut NdArray<f32>) {
@@ -160,3 +188,109 @@ pub fn dsigmoid(output: &NdArray<f32>, dvalues: &NdArray<f32>) -> DuResult<NdArr
     let v = dvalues.mul(&(NdArray::new_scalar(1.).sub(output)?))?;
     Ok(v.mul(output)?)
 }
+
+/// Approximation used by [gelu] and [dgelu]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeluApproximation {
+    /// `x * Φ(x)` using the error function
+    None,
+    /// `0.5 * x * (1 + tanh(sqrt(2/π) * (x + 0.044715 * x^3)))`
+    Tanh,
+}
+
+const GELU_TANH_COEFF: f64 = 0.044715;
+// sqrt(2/π)
+const SQRT_2_OVER_PI: f64 = 0.797_884_560_802_865_4;
+
+/// Error function, Abramowitz and Stegun 7.1.26. The absolute error is below 1.5e-7.
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let res = 1.0 - poly * (-x * x).exp();
+    res.copysign(x)
+}
+
+fn gelu_scalar(x: f64, approximate: GeluApproximation) -> f64 {
+    match approximate {
+        GeluApproximation::None => 0.5 * x * (1.0 + erf(x / std::f64::consts::SQRT_2)),
+        GeluApproximation::Tanh => {
+            0.5 * x * (1.0 + (SQRT_2_OVER_PI * (x + GELU_TANH_COEFF * x * x * x)).tanh())
+        }
+    }
+}
+
+fn dgelu_scalar(x: f64, approximate: GeluApproximation) -> f64 {
+    match approximate {
+        GeluApproximation::None => {
+            let cdf = 0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2));
+            let pdf = (-0.5 * x * x).exp() / (2.0 * std::f64::consts::PI).sqrt();
+            cdf + x * pdf
+        }
+        GeluApproximation::Tanh => {
+            let t = (SQRT_2_OVER_PI * (x + GELU_TANH_COEFF * x * x * x)).tanh();
+            let du = SQRT_2_OVER_PI * (1.0 + 3.0 * GELU_TANH_COEFF * x * x);
+            0.5 * (1.0 + t) + 0.5 * x * (1.0 - t * t) * du
+        }
+    }
+}
+
+/// Element-wise activation that a layer can apply in the same pass as its bias, see
+/// [DenseLayer::with_activation](crate::layer::dense_layer::DenseLayer::with_activation)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activation {
+    Relu,
+    Sigmoid,
+    Gelu(GeluApproximation),
+}
+
+impl Activation {
+    /// Value of the activation at `z`
+    pub fn apply(self, z: f32) -> f32 {
+        match self {
+            Activation::Relu => z.max(0.0),
+            Activation::Sigmoid => 1.0 / (1.0 + E.powf(-z)),
+            Activation::Gelu(approximate) => gelu_scalar(z as f64, approximate) as f32,
+        }
+    }
+
+    /// Derivative of the activation at `z`
+    pub fn derivative(self, z: f32) -> f32 {
+        match self {
+            Activation::Relu => (z > 0.0) as i32 as f32,
+            Activation::Sigmoid => {
+                let s = self.apply(z);
+                s * (1.0 - s)
+            }
+            Activation::Gelu(approximate) => dgelu_scalar(z as f64, approximate) as f32,
+        }
+    }
+}
+
+/// Gaussian Error Linear Unit, applied elementwise. Computed in f64 internally.
+pub fn gelu(inp: &NdArray<f32>, approximate: GeluApproximation) -> NdArray<f32> {
+    inp.map(|x| gelu_scalar(*x as f64, approximate) as f32)
+}
+
+/// GELU derivative
+///
+/// dvalues * gelu'(inputs)
+pub fn dgelu(
+    inputs: &NdArray<f32>,
+    dvalues: &NdArray<f32>,
+    approximate: GeluApproximation,
+) -> DuResult<NdArray<f32>> {
+    if inputs.shape() != dvalues.shape() {
+        return Err(DuError::MismatchedShapes(
+            inputs.shape().clone(),
+            dvalues.shape().clone(),
+        ));
+    }
+    let mut res = dvalues.clone();
+    for (dx, x) in res.as_mut_slice().iter_mut().zip(inputs.as_slice().iter()) {
+        *dx *= dgelu_scalar(*x as f64, approximate) as f32;
+    }
+    Ok(res)
+}
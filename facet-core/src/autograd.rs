@@ -0,0 +1,453 @@
+//! Reverse-mode automatic differentiation
+//!
+//! A [Tape] records the operations applied to its variables in order. [Tape::backward] walks the
+//! recording in reverse, computing the gradient of an output with respect to every variable it
+//! depends on.
+//!
+//! ```
+//! use facet_core::autograd::Tape;
+//! use facet_core::prelude::*;
+//!
+//! let mut tape = Tape::new();
+//! let x = tape.variable(NdArray::new_vector(smallvec![1.0, 2.0]));
+//! let y = tape.mul(x, x).unwrap();
+//! let y = tape.sum(y);
+//!
+//! let grads = tape.backward(y).unwrap();
+//!
+//! assert_eq!(tape.value(y).as_slice(), &[5.0]);
+//! assert_eq!(grads.get(x).unwrap().as_slice(), &[2.0, 4.0]);
+//! ```
+use crate::{
+    activation,
+    ndarray::{shape::Shape, NdArray, NdArrayError},
+    DuError,
+};
+
+/// Handle of a value recorded on a [Tape]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Var(usize);
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Leaf,
+    Add(Var, Var),
+    Sub(Var, Var),
+    Mul(Var, Var),
+    Div(Var, Var),
+    MatMul(Var, Var),
+    Sum(Var),
+    SumAxis(Var),
+    Mean(Var),
+    Reshape(Var),
+    Exp(Var),
+    Log(Var),
+    Relu(Var),
+    Softmax(Var),
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    value: NdArray<f32>,
+    op: Op,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AutogradError {
+    #[error("Failed to compute `{0}` {1}")]
+    OpFail(&'static str, DuError),
+    #[error("Expected an output with a single value, got shape {0:?}")]
+    NotScalar(Shape),
+}
+
+impl AutogradError {
+    fn op(name: &'static str) -> impl FnOnce(NdArrayError) -> Self {
+        move |err| AutogradError::OpFail(name, DuError::ArrayError(err))
+    }
+}
+
+/// Records operations on variables for [backward](Tape::backward)
+#[derive(Debug, Clone, Default)]
+pub struct Tape {
+    nodes: Vec<Node>,
+}
+
+/// Gradients of an output with respect to the variables of a [Tape]
+#[derive(Debug, Clone)]
+pub struct Gradients {
+    grads: Vec<Option<NdArray<f32>>>,
+}
+
+impl Gradients {
+    /// The gradient of `var`, `None` if the output does not depend on it
+    pub fn get(&self, var: Var) -> Option<&NdArray<f32>> {
+        self.grads.get(var.0).and_then(|g| g.as_ref())
+    }
+}
+
+impl Tape {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of values recorded
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Record a leaf variable, e.g. an input or a trainable parameter
+    pub fn variable(&mut self, value: NdArray<f32>) -> Var {
+        self.push(value, Op::Leaf)
+    }
+
+    /// # Panics
+    ///
+    /// If `var` was recorded on another tape
+    pub fn value(&self, var: Var) -> &NdArray<f32> {
+        &self.nodes[var.0].value
+    }
+
+    /// Element-wise sum, broadcasting like [NdArray::add]
+    pub fn add(&mut self, a: Var, b: Var) -> Result<Var, AutogradError> {
+        let value = self
+            .value(a)
+            .add(self.value(b))
+            .map_err(AutogradError::op("add"))?;
+        Ok(self.push(value, Op::Add(a, b)))
+    }
+
+    /// Element-wise difference, broadcasting like [NdArray::sub]
+    pub fn sub(&mut self, a: Var, b: Var) -> Result<Var, AutogradError> {
+        let value = self
+            .value(a)
+            .sub(self.value(b))
+            .map_err(AutogradError::op("sub"))?;
+        Ok(self.push(value, Op::Sub(a, b)))
+    }
+
+    /// Element-wise product, broadcasting like [NdArray::mul]
+    pub fn mul(&mut self, a: Var, b: Var) -> Result<Var, AutogradError> {
+        let value = self
+            .value(a)
+            .mul(self.value(b))
+            .map_err(AutogradError::op("mul"))?;
+        Ok(self.push(value, Op::Mul(a, b)))
+    }
+
+    /// Element-wise quotient, broadcasting like [NdArray::div]
+    pub fn div(&mut self, a: Var, b: Var) -> Result<Var, AutogradError> {
+        let value = self
+            .value(a)
+            .div(self.value(b))
+            .map_err(AutogradError::op("div"))?;
+        Ok(self.push(value, Op::Div(a, b)))
+    }
+
+    /// Matrix product of two matrices
+    pub fn matmul(&mut self, a: Var, b: Var) -> Result<Var, AutogradError> {
+        for var in [a, b] {
+            if !matches!(self.value(var).shape(), Shape::Matrix(_)) {
+                return Err(AutogradError::op("matmul")(NdArrayError::UnsupportedShape(
+                    self.value(var).shape().clone(),
+                )));
+            }
+        }
+        let mut value = NdArray::new(0);
+        self.value(a)
+            .matmul_f32(self.value(b), &mut value)
+            .map_err(AutogradError::op("matmul"))?;
+        Ok(self.push(value, Op::MatMul(a, b)))
+    }
+
+    /// Sum of all elements, returning a scalar
+    pub fn sum(&mut self, a: Var) -> Var {
+        let total = self.value(a).as_slice().iter().sum();
+        self.push(NdArray::new_scalar(total), Op::Sum(a))
+    }
+
+    /// Sum along `axis`, keeping it with length 1 so the result broadcasts against `a`
+    pub fn sum_axis(&mut self, a: Var, axis: u32) -> Result<Var, AutogradError> {
+        let value = self
+            .value(a)
+            .map_axis(axis, |lane| vec![lane.iter().sum()])
+            .map_err(AutogradError::op("sum_axis"))?;
+        Ok(self.push(value, Op::SumAxis(a)))
+    }
+
+    /// Mean of all elements, returning a scalar
+    pub fn mean(&mut self, a: Var) -> Var {
+        let value = self.value(a);
+        let mean = value.as_slice().iter().sum::<f32>() / value.len().max(1) as f32;
+        self.push(NdArray::new_scalar(mean), Op::Mean(a))
+    }
+
+    /// The values of `a` with a new shape holding the same number of elements
+    pub fn reshape(&mut self, a: Var, shape: impl Into<Shape>) -> Result<Var, AutogradError> {
+        let shape = shape.into();
+        let mut value = self.value(a).clone();
+        if shape.span() != value.len() {
+            return Err(AutogradError::op("reshape")(NdArrayError::BadInput(
+                format!("Can not reshape {} values into {:?}", value.len(), shape),
+            )));
+        }
+        value.reshape(shape);
+        Ok(self.push(value, Op::Reshape(a)))
+    }
+
+    pub fn exp(&mut self, a: Var) -> Var {
+        let value = self.value(a).map(|x| x.exp());
+        self.push(value, Op::Exp(a))
+    }
+
+    /// Natural logarithm
+    pub fn ln(&mut self, a: Var) -> Var {
+        let value = self.value(a).map(|x| x.ln());
+        self.push(value, Op::Log(a))
+    }
+
+    pub fn relu(&mut self, a: Var) -> Var {
+        let value = activation::relu(self.value(a));
+        self.push(value, Op::Relu(a))
+    }
+
+    /// Softmax of each row, see [activation::softmax]
+    pub fn softmax(&mut self, a: Var) -> Result<Var, AutogradError> {
+        let value = activation::softmax(self.value(a))
+            .map_err(|err| AutogradError::OpFail("softmax", err))?;
+        Ok(self.push(value, Op::Softmax(a)))
+    }
+
+    /// Compute the gradients of `output` with respect to the values it was computed from.
+    ///
+    /// `output` must hold a single value, e.g. a loss reduced by [sum](Tape::sum).
+    pub fn backward(&self, output: Var) -> Result<Gradients, AutogradError> {
+        let seed = self.value(output);
+        if seed.len() != 1 {
+            return Err(AutogradError::NotScalar(seed.shape().clone()));
+        }
+
+        let mut grads = vec![None; self.nodes.len()];
+        grads[output.0] = Some(seed.map(|_| 1.0));
+
+        // nodes are recorded after their operands, so reverse order visits every node after all
+        // of its consumers
+        for i in (0..=output.0).rev() {
+            let grad = match &grads[i] {
+                Some(grad) => grad.clone(),
+                None => continue,
+            };
+            match self.nodes[i].op {
+                Op::Leaf => {}
+                Op::Add(a, b) => {
+                    accumulate(&mut grads, a, unbroadcast(&grad, self.value(a).shape()))?;
+                    accumulate(&mut grads, b, unbroadcast(&grad, self.value(b).shape()))?;
+                }
+                Op::Sub(a, b) => {
+                    accumulate(&mut grads, a, unbroadcast(&grad, self.value(a).shape()))?;
+                    let db = unbroadcast(&grad, self.value(b).shape()).map(|x| -x);
+                    accumulate(&mut grads, b, db)?;
+                }
+                Op::Mul(a, b) => {
+                    let da = grad.mul(self.value(b)).map_err(AutogradError::op("mul"))?;
+                    let db = grad.mul(self.value(a)).map_err(AutogradError::op("mul"))?;
+                    accumulate(&mut grads, a, unbroadcast(&da, self.value(a).shape()))?;
+                    accumulate(&mut grads, b, unbroadcast(&db, self.value(b).shape()))?;
+                }
+                Op::Div(a, b) => {
+                    // c = a / b  =>  da = dc / b, db = -dc a / b^2 = -dc c / b
+                    let da = grad.div(self.value(b)).map_err(AutogradError::op("div"))?;
+                    let db = da
+                        .mul(&self.nodes[i].value)
+                        .map_err(AutogradError::op("mul"))?
+                        .map(|x| -x);
+                    accumulate(&mut grads, a, unbroadcast(&da, self.value(a).shape()))?;
+                    accumulate(&mut grads, b, unbroadcast(&db, self.value(b).shape()))?;
+                }
+                Op::MatMul(a, b) => {
+                    // c = a b  =>  da = dc bT, db = aT dc
+                    let mut da = NdArray::new(0);
+                    let mut db = NdArray::new(0);
+                    grad.matmul_f32(&self.value(b).clone().transpose(), &mut da)
+                        .map_err(AutogradError::op("matmul"))?;
+                    self.value(a)
+                        .clone()
+                        .transpose()
+                        .matmul_f32(&grad, &mut db)
+                        .map_err(AutogradError::op("matmul"))?;
+                    accumulate(&mut grads, a, da)?;
+                    accumulate(&mut grads, b, db)?;
+                }
+                Op::Sum(a) => {
+                    let g = grad.as_slice()[0];
+                    accumulate(&mut grads, a, self.value(a).map(|_| g))?;
+                }
+                Op::SumAxis(a) => {
+                    // every element of the lane contributed to the sum once
+                    let da = self
+                        .value(a)
+                        .broadcast_with(&grad, |_, g| g)
+                        .map_err(AutogradError::op("sum_axis"))?;
+                    accumulate(&mut grads, a, da)?;
+                }
+                Op::Mean(a) => {
+                    let g = grad.as_slice()[0] / self.value(a).len().max(1) as f32;
+                    accumulate(&mut grads, a, self.value(a).map(|_| g))?;
+                }
+                Op::Reshape(a) => {
+                    let mut da = grad;
+                    da.reshape(self.value(a).shape().clone());
+                    accumulate(&mut grads, a, da)?;
+                }
+                Op::Exp(a) => {
+                    let da = grad
+                        .mul(&self.nodes[i].value)
+                        .map_err(AutogradError::op("mul"))?;
+                    accumulate(&mut grads, a, da)?;
+                }
+                Op::Log(a) => {
+                    let da = grad.div(self.value(a)).map_err(AutogradError::op("div"))?;
+                    accumulate(&mut grads, a, da)?;
+                }
+                Op::Relu(a) => {
+                    accumulate(&mut grads, a, activation::drelu_dz(self.value(a), &grad))?;
+                }
+                Op::Softmax(a) => {
+                    // y = softmax(x)  =>  dx = y * (dy - (y . dy)) for each row
+                    let mut dx = grad;
+                    for (dx, y) in dx.iter_rows_mut().zip(self.nodes[i].value.iter_rows()) {
+                        let dot = dx.iter().zip(y.iter()).map(|(dy, y)| dy * y).sum::<f32>();
+                        for (dx, y) in dx.iter_mut().zip(y.iter()) {
+                            *dx = y * (*dx - dot);
+                        }
+                    }
+                    accumulate(&mut grads, a, dx)?;
+                }
+            }
+        }
+
+        Ok(Gradients { grads })
+    }
+
+    fn push(&mut self, value: NdArray<f32>, op: Op) -> Var {
+        self.nodes.push(Node { value, op });
+        Var(self.nodes.len() - 1)
+    }
+}
+
+/// Add `grad` to the gradient of `var`
+fn accumulate(
+    grads: &mut [Option<NdArray<f32>>],
+    var: Var,
+    grad: NdArray<f32>,
+) -> Result<(), AutogradError> {
+    let acc = &mut grads[var.0];
+    *acc = match acc.take() {
+        Some(acc) => Some(acc.add(&grad).map_err(AutogradError::op("add"))?),
+        None => Some(grad),
+    };
+    Ok(())
+}
+
+/// Sum `grad` over the axes an operand of `shape` was broadcast along.
+///
+/// The gradient of each element of the operand is the sum of the elements of `grad` it was
+/// broadcast to.
+fn unbroadcast(grad: &NdArray<f32>, shape: &Shape) -> NdArray<f32> {
+    let mut res = NdArray::new_default(shape.clone());
+    let index = NdArray::new_with_values(shape.clone(), (0..res.len()).collect()).unwrap();
+    // the operand broadcast against its own gradient can not fail
+    let targets = index.broadcast_with(grad, |i, g| (i, g)).unwrap();
+    let values = res.as_mut_slice();
+    for (i, g) in targets.as_slice() {
+        values[*i] += g;
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smallvec::smallvec;
+
+    #[test]
+    fn test_broadcast_column_gradient() {
+        let mut tape = Tape::new();
+        let x = tape.variable(
+            NdArray::new_with_values([2, 3], smallvec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap(),
+        );
+        let c = tape.variable(NdArray::new_with_values([2, 1], smallvec![1.0, 2.0]).unwrap());
+
+        let y = tape.mul(x, c).unwrap();
+        let y = tape.sum(y);
+        let grads = tape.backward(y).unwrap();
+
+        // dc is the sum of x over each row
+        assert_eq!(grads.get(c).unwrap().as_slice(), &[6.0, 15.0]);
+        assert_eq!(
+            grads.get(x).unwrap().as_slice(),
+            &[1.0, 1.0, 1.0, 2.0, 2.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn test_broadcast_add_gradient() {
+        let mut tape = Tape::new();
+        let x =
+            tape.variable(NdArray::new_with_values([2, 2], smallvec![1.0, 2.0, 3.0, 4.0]).unwrap());
+        let b = tape.variable(NdArray::new_vector(smallvec![1.0, -1.0]));
+
+        let y = tape.add(x, b).unwrap();
+        let y = tape.mul(y, x).unwrap();
+        let y = tape.sum(y);
+        let grads = tape.backward(y).unwrap();
+
+        // y = sum((x + b) * x)  =>  dx = 2x + b, db = sum of x over the rows
+        assert_eq!(grads.get(x).unwrap().as_slice(), &[3.0, 3.0, 7.0, 7.0]);
+        assert_eq!(grads.get(b).unwrap().as_slice(), &[4.0, 6.0]);
+    }
+
+    #[test]
+    fn test_cross_entropy_gradient() {
+        let mut tape = Tape::new();
+        let logits =
+            tape.variable(NdArray::new_with_values([2, 2], smallvec![1.0, 1.0, 0.0, 2.0]).unwrap());
+        let target =
+            tape.variable(NdArray::new_with_values([2, 2], smallvec![1.0, 0.0, 0.0, 1.0]).unwrap());
+
+        // softmax written out of differentiable ops, so its gradient is composed by the tape
+        let e = tape.exp(logits);
+        let total = tape.sum_axis(e, 1).unwrap();
+        let p = tape.div(e, total).unwrap();
+        let logp = tape.ln(p);
+        let loss = tape.mul(logp, target).unwrap();
+        let loss = tape.reshape(loss, 4).unwrap();
+        let loss = tape.mean(loss);
+        let grads = tape.backward(loss).unwrap();
+
+        // d mean(-sum(t log softmax(x))) / dx = (softmax(x) - t) / 4, negated here
+        let p = tape.value(p).as_slice();
+        let expected = [
+            (1.0 - p[0]) / 4.0,
+            -p[1] / 4.0,
+            -p[2] / 4.0,
+            (1.0 - p[3]) / 4.0,
+        ];
+        for (g, e) in grads.get(logits).unwrap().as_slice().iter().zip(&expected) {
+            assert!((g - e).abs() < 1e-6, "{} != {}", g, e);
+        }
+        assert_eq!(grads.get(target).unwrap().shape(), &Shape::Matrix([2, 2]));
+    }
+
+    #[test]
+    fn test_backward_requires_single_value() {
+        let mut tape = Tape::new();
+        let x = tape.variable(NdArray::new_vector(smallvec![1.0, 2.0]));
+        let y = tape.relu(x);
+
+        assert!(matches!(tape.backward(y), Err(AutogradError::NotScalar(_))));
+    }
+}
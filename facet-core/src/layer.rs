@@ -1 +1,87 @@
+pub mod checkpoint;
+pub mod conv2d;
 pub mod dense_layer;
+pub mod embedding;
+pub mod parameter;
+pub mod relu;
+pub mod sigmoid;
+pub mod softmax;
+pub mod upsample;
+
+pub use parameter::Parameter;
+
+use crate::ndarray::NdArray;
+
+/// Common interface of the layers, so networks can be built and trained from Rust.
+///
+/// A layer memoizes what its `backward` needs in `forward`, so `backward` computes the gradients
+/// of the last `forward`.
+pub trait Module {
+    type Error: std::error::Error;
+
+    /// Run the forward pass, returning the output of the layer
+    fn forward(&mut self, inputs: NdArray<f32>) -> Result<NdArray<f32>, Self::Error>;
+    /// Run the backward pass, returning the gradient of the inputs of the layer
+    fn backward(&mut self, dvalues: NdArray<f32>) -> Result<NdArray<f32>, Self::Error>;
+    /// Trainable parameters of the layer
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loss::{CategoricalCrossEntropy, Loss};
+
+    #[test]
+    fn test_train_step_without_python() {
+        let mut dense = dense_layer::DenseLayer::new(2, 2).with_training(None, None, None, None);
+        dense.weights.value =
+            NdArray::new_with_values([2, 2], smallvec::smallvec![0.25, -1.0, 0.0, 2.0]).unwrap();
+        dense.biases.value = NdArray::new_vector(smallvec::smallvec![0.0, 0.0]);
+        let mut relu = relu::Relu::new();
+
+        let inputs = NdArray::new_with_values([1, 2], smallvec::smallvec![2.0, 1.0]).unwrap();
+        let targets = NdArray::new_with_values([1, 2], smallvec::smallvec![1.0, 0.0]).unwrap();
+
+        let hidden = Module::forward(&mut dense, inputs).unwrap();
+        let output = Module::forward(&mut relu, hidden).unwrap();
+        assert_eq!(output.as_slice(), &[0.5, 0.0]);
+
+        let loss = CategoricalCrossEntropy.loss(&output, &targets).unwrap();
+        assert_eq!(loss.shape().as_slice(), &[1]);
+
+        let dloss = CategoricalCrossEntropy.backward(&output, &targets).unwrap();
+        let dhidden = Module::backward(&mut relu, dloss).unwrap();
+        let dinputs = Module::backward(&mut dense, dhidden).unwrap();
+        assert_eq!(dinputs.shape().as_slice(), &[1, 2]);
+
+        let params = dense.parameters_mut();
+        assert_eq!(params.len(), 2);
+        assert_eq!(params[0].grad.as_slice(), &[-4.0, 0.0, -2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_activation_modules() {
+        let inputs = NdArray::new_with_values([1, 2], smallvec::smallvec![0.0, 2.0]).unwrap();
+        let targets = NdArray::new_with_values([1, 2], smallvec::smallvec![1.0, 0.0]).unwrap();
+
+        let mut sigmoid = sigmoid::Sigmoid::new();
+        assert!(Module::backward(&mut sigmoid, targets.clone()).is_err());
+        let output = Module::forward(&mut sigmoid, inputs.clone()).unwrap();
+        assert_eq!(output.as_slice()[0], 0.5);
+        let dinputs = Module::backward(&mut sigmoid, targets.clone()).unwrap();
+        assert_eq!(dinputs.as_slice(), &[0.25, 0.0]);
+
+        // softmax followed by cross entropy has the gradient `output - targets`
+        let mut softmax = softmax::Softmax::new();
+        let output = Module::forward(&mut softmax, inputs).unwrap();
+        let dloss = CategoricalCrossEntropy.backward(&output, &targets).unwrap();
+        let dinputs = Module::backward(&mut softmax, dloss).unwrap();
+        let expected = output.sub(&targets).unwrap();
+        for (x, y) in dinputs.as_slice().iter().zip(expected.as_slice()) {
+            assert!((x - y).abs() < 1e-5, "{} {}", x, y);
+        }
+    }
+}
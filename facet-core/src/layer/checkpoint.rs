@@ -0,0 +1,182 @@
+//! Persist the trainable parameters of layers, e.g. to checkpoint and resume training
+//!
+//! The format is a small binary container, all integers and values are little-endian:
+//!
+//! - the magic bytes `FACETW`, followed by the format version as a `u16`
+//! - the number of arrays as a `u32`
+//! - for each array the number of dimensions as a `u32`, the length of each dimension as a `u32`
+//!   and the `f32` values in row-major order
+//!
+use std::io::{Read, Write};
+
+use super::Parameter;
+use crate::ndarray::{shape::Shape, LeBytes, NdArray, NdArrayError};
+
+const MAGIC: &[u8; 6] = b"FACETW";
+const VERSION: u16 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CheckpointError {
+    #[error("Failed to read or write the weights {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not a weights file, or a weights file of an unsupported version")]
+    BadHeader,
+    #[error("Failed to rebuild an array {0}")]
+    ArrayError(NdArrayError),
+    #[error("Expected {expected} parameter arrays, the weights hold {actual}")]
+    CountMismatch { expected: usize, actual: usize },
+    #[error("Expected a parameter of shape {expected:?}, got {actual:?}")]
+    ShapeMismatch { expected: Shape, actual: Shape },
+}
+
+fn read_u32(input: &mut impl Read) -> Result<u32, CheckpointError> {
+    let mut bytes = [0; 4];
+    input.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Write `values` to `out`
+///
+/// ```
+/// use facet_core::layer::checkpoint::{read_weights, write_weights};
+/// use facet_core::ndarray::NdArray;
+///
+/// let w = NdArray::new_with_values([2, 2], vec![1.0, 2.0, 3.0, 4.0].into()).unwrap();
+/// let b = NdArray::new_vector(vec![0.5, -0.5]);
+///
+/// let mut buffer = Vec::new();
+/// write_weights(&mut buffer, &[&w, &b]).unwrap();
+/// let values = read_weights(buffer.as_slice()).unwrap();
+///
+/// assert_eq!(values, vec![w, b]);
+/// ```
+pub fn write_weights<W: Write>(
+    mut out: W,
+    values: &[&NdArray<f32>],
+) -> Result<(), CheckpointError> {
+    let mut header = Vec::with_capacity(16);
+    header.extend_from_slice(MAGIC);
+    VERSION.write_le(&mut header);
+    (values.len() as u32).write_le(&mut header);
+    out.write_all(&header)?;
+    for value in values {
+        let dims = value.shape().as_slice();
+        let mut header = Vec::with_capacity(4 * (dims.len() + 1));
+        (dims.len() as u32).write_le(&mut header);
+        for d in dims {
+            d.write_le(&mut header);
+        }
+        out.write_all(&header)?;
+        out.write_all(&value.as_bytes())?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Read the arrays written by [write_weights], in the order they were written
+pub fn read_weights<R: Read>(mut input: R) -> Result<Vec<NdArray<f32>>, CheckpointError> {
+    let mut header = [0; 8];
+    input.read_exact(&mut header)?;
+    if &header[..6] != MAGIC || u16::read_le(&header[6..]) != VERSION {
+        return Err(CheckpointError::BadHeader);
+    }
+    let count = read_u32(&mut input)?;
+    // the counts are not trusted for allocations, a corrupt file fails on its missing bytes
+    let mut res = Vec::new();
+    for _ in 0..count {
+        let ndims = read_u32(&mut input)?;
+        let dims = (0..ndims)
+            .map(|_| read_u32(&mut input))
+            .collect::<Result<Vec<_>, _>>()?;
+        let shape = match dims.as_slice() {
+            [n] => Shape::Vector([*n]),
+            dims => Shape::from(dims),
+        };
+        let len = shape.checked_span().ok_or_else(|| {
+            CheckpointError::ArrayError(NdArrayError::ShapeOverflow(shape.clone()))
+        })?;
+        let mut bytes = Vec::new();
+        (&mut input)
+            .take(len as u64 * f32::SIZE as u64)
+            .read_to_end(&mut bytes)?;
+        res.push(NdArray::from_bytes(shape, &bytes).map_err(CheckpointError::ArrayError)?);
+    }
+    Ok(res)
+}
+
+/// Replace the values of `params` with the next values of `values`, in order. Pass `&mut iter`
+/// to assign the rest of the values to further parameters.
+///
+/// Fails without changing any parameter if `values` runs out or a shape does not match.
+pub fn assign_parameters(
+    params: Vec<&mut Parameter>,
+    values: impl Iterator<Item = NdArray<f32>>,
+) -> Result<(), CheckpointError> {
+    let expected = params.len();
+    let values: Vec<_> = values.take(expected).collect();
+    if values.len() != expected {
+        return Err(CheckpointError::CountMismatch {
+            expected,
+            actual: values.len(),
+        });
+    }
+    for (param, value) in params.iter().zip(&values) {
+        if param.value.shape() != value.shape() {
+            return Err(CheckpointError::ShapeMismatch {
+                expected: param.value.shape().clone(),
+                actual: value.shape().clone(),
+            });
+        }
+    }
+    for (param, value) in params.into_iter().zip(values) {
+        param.value = value;
+    }
+    Ok(())
+}
+
+/// Replace the values of `params` with `values`, which must hold exactly one value per parameter.
+///
+/// Fails without changing any parameter if the counts or a shape do not match.
+pub fn load_parameters(
+    params: Vec<&mut Parameter>,
+    values: Vec<NdArray<f32>>,
+) -> Result<(), CheckpointError> {
+    if params.len() != values.len() {
+        return Err(CheckpointError::CountMismatch {
+            expected: params.len(),
+            actual: values.len(),
+        });
+    }
+    assign_parameters(params, values.into_iter())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mismatching_weights_are_rejected() {
+        let mut w =
+            Parameter::new(NdArray::new_with_values([1, 2], vec![1.0, 2.0].into()).unwrap());
+        let mut b = Parameter::new(NdArray::new_vector(vec![3.0]));
+
+        let mut buffer = Vec::new();
+        write_weights(&mut buffer, &[&w.value, &w.value]).unwrap();
+        let values = read_weights(buffer.as_slice()).unwrap();
+
+        let res = assign_parameters(vec![&mut w, &mut b], &mut values.into_iter());
+        assert!(matches!(res, Err(CheckpointError::ShapeMismatch { .. })));
+        assert_eq!(b.value.as_slice(), &[3.0]);
+
+        let values = vec![b.value.clone(), b.value.clone()];
+        let res = load_parameters(vec![&mut b], values);
+        assert!(matches!(res, Err(CheckpointError::CountMismatch { .. })));
+
+        buffer.truncate(buffer.len() - 1);
+        assert!(read_weights(buffer.as_slice()).is_err());
+        assert!(matches!(
+            read_weights(&b"not weights"[..]),
+            Err(CheckpointError::BadHeader)
+        ));
+    }
+}
@@ -0,0 +1,488 @@
+use super::{Module, Parameter};
+use crate::ndarray::{shape::Shape, NdArray, NdArrayError};
+use rand::Rng;
+
+/// 2D convolution of `[N, C, H, W]` inputs, producing `[N, out_channels, OH, OW]` outputs
+///
+/// The patches of the inputs are unrolled into the rows of a matrix ([im2col]), so the
+/// convolution becomes a single matrix multiplication with the filters.
+#[derive(Clone)]
+pub struct Conv2d {
+    /// `[out_channels, in_channels, kernel_size, kernel_size]` filters
+    pub weights: Parameter,
+    /// `[out_channels]`
+    pub biases: Parameter,
+    pub stride: u32,
+    pub padding: Padding,
+    pub output: NdArray<f32>,
+    pub dinputs: Option<NdArray<f32>>,
+
+    // memoization for training purposes
+    pub columns: Option<NdArray<f32>>,
+    pub input_shape: Option<Shape>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Conv2dError {
+    #[error("Expected [N, C, H, W] inputs, got shape {0:?}")]
+    UnsupportedRank(Shape),
+    #[error("Expected [N, {channels}, H, W] inputs, got shape {shape:?}")]
+    UnsupportedShape { channels: u32, shape: Shape },
+    #[error("The stride must be positive")]
+    ZeroStride,
+    #[error("Padding the {height}x{width} input overflows")]
+    PaddingOverflow { height: u32, width: u32 },
+    #[error("The {kernel}x{kernel} kernel does not fit into the padded {height}x{width} input")]
+    KernelTooLarge {
+        kernel: u32,
+        height: u32,
+        width: u32,
+    },
+    #[error("Failed to perform matrix multiplication {0}")]
+    MatMulFail(NdArrayError),
+    #[error("Failed to add the biases {0}")]
+    BiasFail(NdArrayError),
+    #[error("Failed to reorder the axes {0}")]
+    TransposeFail(NdArrayError),
+    #[error("Expected gradients of shape {expected:?}, got {actual:?}")]
+    GradientMismatch { expected: Shape, actual: Shape },
+    #[error("No inputs available. Perhaps you forgot to call `forward`?")]
+    NoInputs,
+}
+
+/// Zeros added around the spatial axes of the inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    /// The same number of zeros on every side
+    Fixed(u32),
+    /// Pad so the outputs have `ceil(H / stride) x ceil(W / stride)` positions, keeping the
+    /// spatial size for stride 1. An odd total goes to the bottom and right, like TensorFlow.
+    Same,
+    /// No padding
+    Valid,
+}
+
+impl Padding {
+    /// `(before, after)` zeros of an axis of length `size`, `None` on overflow
+    fn resolve(self, size: u32, kernel: u32, stride: u32) -> Option<(u32, u32)> {
+        match self {
+            Padding::Fixed(p) => Some((p, p)),
+            Padding::Valid => Some((0, 0)),
+            Padding::Same => {
+                // ceil(size / stride) - 1, the last output position
+                let last = size.saturating_sub(1) / stride;
+                let span = last.checked_mul(stride)?.checked_add(kernel)?;
+                let total = span.saturating_sub(size);
+                Some((total / 2, total - total / 2))
+            }
+        }
+    }
+}
+
+impl From<u32> for Padding {
+    fn from(padding: u32) -> Self {
+        Padding::Fixed(padding)
+    }
+}
+
+/// Geometry of a convolution, `[n, c, h, w]` inputs and `[oh, ow]` outputs
+#[derive(Debug, Clone, Copy)]
+struct Geometry {
+    input: [usize; 4],
+    output: [usize; 2],
+    kernel: usize,
+    stride: usize,
+    /// zeros before the `[h, w]` axes, i.e. at the top and on the left
+    padding: [usize; 2],
+}
+
+impl Geometry {
+    fn new(shape: &Shape, kernel: u32, stride: u32, padding: Padding) -> Result<Self, Conv2dError> {
+        let (n, c, h, w) = match shape.as_slice() {
+            [n, c, h, w] => (*n, *c, *h, *w),
+            _ => return Err(Conv2dError::UnsupportedRank(shape.clone())),
+        };
+        if stride == 0 {
+            return Err(Conv2dError::ZeroStride);
+        }
+        let padded = |size: u32| {
+            let (before, after) = padding.resolve(size, kernel, stride)?;
+            let padded = size.checked_add(before)?.checked_add(after)?;
+            Some((before, padded))
+        };
+        let ((top, ph), (left, pw)) = match (padded(h), padded(w)) {
+            (Some(h), Some(w)) => (h, w),
+            _ => {
+                return Err(Conv2dError::PaddingOverflow {
+                    height: h,
+                    width: w,
+                })
+            }
+        };
+        if kernel == 0 || kernel > ph || kernel > pw {
+            return Err(Conv2dError::KernelTooLarge {
+                kernel,
+                height: ph,
+                width: pw,
+            });
+        }
+        Ok(Self {
+            input: [n as usize, c as usize, h as usize, w as usize],
+            output: [
+                ((ph - kernel) / stride + 1) as usize,
+                ((pw - kernel) / stride + 1) as usize,
+            ],
+            kernel: kernel as usize,
+            stride: stride as usize,
+            padding: [top as usize, left as usize],
+        })
+    }
+
+    /// Index of the input element under kernel position `[ky, kx]` of output `[oy, ox]`, `None`
+    /// in the padding
+    fn source(&self, [oy, ox]: [usize; 2], [ky, kx]: [usize; 2]) -> Option<[usize; 2]> {
+        let y = (oy * self.stride + ky).checked_sub(self.padding[0])?;
+        let x = (ox * self.stride + kx).checked_sub(self.padding[1])?;
+        if y < self.input[2] && x < self.input[3] {
+            Some([y, x])
+        } else {
+            None
+        }
+    }
+
+    /// Visit every `(column index, input index)` pair of the unrolled patches
+    fn for_each_patch(&self, mut f: impl FnMut(usize, usize)) {
+        let [n, c, h, w] = self.input;
+        let [oh, ow] = self.output;
+        let k = self.kernel;
+        let width = c * k * k;
+        for b in 0..n {
+            for oy in 0..oh {
+                for ox in 0..ow {
+                    let row = (b * oh + oy) * ow + ox;
+                    for ch in 0..c {
+                        for ky in 0..k {
+                            for kx in 0..k {
+                                if let Some([y, x]) = self.source([oy, ox], [ky, kx]) {
+                                    let col = (ch * k + ky) * k + kx;
+                                    f(row * width + col, ((b * c + ch) * h + y) * w + x);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Unroll the `kernel x kernel` patches of `[N, C, H, W]` inputs into the rows of a
+/// `[N * OH * OW, C * kernel * kernel]` matrix. Padding reads as zeros.
+///
+/// ```
+/// use facet_core::layer::conv2d::{im2col, Padding};
+/// use facet_core::ndarray::NdArray;
+///
+/// let x = NdArray::new_with_values(&[1, 1, 2, 3][..], (1..=6).map(|x| x as f32).collect()).unwrap();
+///
+/// let cols = im2col(&x, 2, 1, Padding::Valid).unwrap();
+///
+/// assert_eq!(cols.shape().as_slice(), &[2, 4]);
+/// assert_eq!(cols.as_slice(), &[1.0, 2.0, 4.0, 5.0, 2.0, 3.0, 5.0, 6.0]);
+/// ```
+pub fn im2col(
+    inputs: &NdArray<f32>,
+    kernel: u32,
+    stride: u32,
+    padding: Padding,
+) -> Result<NdArray<f32>, Conv2dError> {
+    let geometry = Geometry::new(inputs.shape(), kernel, stride, padding)?;
+    Ok(unroll(inputs, &geometry))
+}
+
+fn unroll(inputs: &NdArray<f32>, geometry: &Geometry) -> NdArray<f32> {
+    let [n, c, ..] = geometry.input;
+    let [oh, ow] = geometry.output;
+    let k = geometry.kernel;
+    let [rows, cols] = [n * oh * ow, c * k * k];
+    let mut res = NdArray::new_default([rows as u32, cols as u32]);
+    let values = inputs.as_slice();
+    let out = res.as_mut_slice();
+    geometry.for_each_patch(|i, j| out[i] = values[j]);
+    res
+}
+
+/// Inverse of [unroll], summing the gradients of the patches that overlap
+fn fold(columns: &NdArray<f32>, geometry: &Geometry) -> NdArray<f32> {
+    let [n, c, h, w] = geometry.input;
+    let shape = [n as u32, c as u32, h as u32, w as u32];
+    let mut res = NdArray::new_default(&shape[..]);
+    let values = columns.as_slice();
+    let out = res.as_mut_slice();
+    geometry.for_each_patch(|i, j| out[j] += values[i]);
+    res
+}
+
+impl Conv2d {
+    pub fn new(
+        in_channels: u32,
+        out_channels: u32,
+        kernel_size: u32,
+        stride: u32,
+        padding: Padding,
+    ) -> Self {
+        let shape = [out_channels, in_channels, kernel_size, kernel_size];
+        let span = shape.iter().map(|x| *x as usize).product::<usize>();
+        let weights = NdArray::new_with_values(
+            &shape[..],
+            (0..span)
+                .map(|_| rand::thread_rng().gen_range(-1., 1.))
+                .collect(),
+        )
+        .unwrap();
+
+        let biases = NdArray::new_with_values(
+            out_channels,
+            (0..out_channels as usize)
+                .map(|_| rand::thread_rng().gen_range(-1., 1.))
+                .collect(),
+        )
+        .unwrap();
+
+        Self {
+            weights: Parameter::new(weights),
+            biases: Parameter::new(biases),
+            stride: stride.max(1),
+            padding,
+            output: Default::default(),
+            dinputs: None,
+            columns: None,
+            input_shape: None,
+        }
+    }
+
+    pub fn out_channels(&self) -> u32 {
+        self.weights.value.shape().as_slice()[0]
+    }
+
+    pub fn in_channels(&self) -> u32 {
+        self.weights.value.shape().as_slice()[1]
+    }
+
+    pub fn kernel_size(&self) -> u32 {
+        self.weights.value.shape().last()
+    }
+
+    /// `[out_channels, in_channels * kernel_size * kernel_size]` view of the filters
+    fn filter_matrix(&self) -> NdArray<f32> {
+        let mut filters = self.weights.value.clone();
+        let o = self.out_channels();
+        filters.reshape([o, filters.len() as u32 / o]);
+        filters
+    }
+
+    fn geometry(&self, shape: &Shape) -> Result<Geometry, Conv2dError> {
+        let geometry = Geometry::new(shape, self.kernel_size(), self.stride, self.padding)?;
+        if geometry.input[1] != self.in_channels() as usize {
+            return Err(Conv2dError::UnsupportedShape {
+                channels: self.in_channels(),
+                shape: shape.clone(),
+            });
+        }
+        Ok(geometry)
+    }
+
+    pub fn forward(&mut self, inputs: NdArray<f32>) -> Result<(), Conv2dError> {
+        let geometry = self.geometry(inputs.shape())?;
+        let [n, ..] = geometry.input;
+        let [oh, ow] = geometry.output;
+        let o = self.out_channels();
+
+        let columns = unroll(&inputs, &geometry);
+        let mut out = NdArray::default();
+        columns
+            .matmul_f32(&self.filter_matrix().transpose(), &mut out)
+            .map_err(Conv2dError::MatMulFail)?;
+        crate::broadcast_add_axis(&mut out, &self.biases.value, 1)
+            .map_err(Conv2dError::BiasFail)?;
+
+        // [N * OH * OW, O] -> [N, O, OH, OW]
+        out.reshape(&[n as u32, (oh * ow) as u32, o][..]);
+        let mut out = out
+            .transpose_axes(&[0, 2, 1])
+            .map_err(Conv2dError::TransposeFail)?;
+        out.reshape(&[n as u32, o, oh as u32, ow as u32][..]);
+
+        self.output = out;
+        self.columns = Some(columns);
+        self.input_shape = Some(inputs.shape().clone());
+        Ok(())
+    }
+
+    /// Consumes the last unrolled inputs replacing them with `None`.
+    pub fn backward(&mut self, dvalues: NdArray<f32>) -> Result<(), Conv2dError> {
+        let columns = self.columns.take().ok_or(Conv2dError::NoInputs)?;
+        let shape = self.input_shape.take().ok_or(Conv2dError::NoInputs)?;
+        if dvalues.shape() != self.output.shape() {
+            return Err(Conv2dError::GradientMismatch {
+                expected: self.output.shape().clone(),
+                actual: dvalues.shape().clone(),
+            });
+        }
+        let geometry = self.geometry(&shape)?;
+        let [n, ..] = geometry.input;
+        let [oh, ow] = geometry.output;
+        let o = self.out_channels();
+
+        // [N, O, OH, OW] -> [N * OH * OW, O], the layout of the forward matmul
+        let mut grads = dvalues;
+        grads.reshape(&[n as u32, o, (oh * ow) as u32][..]);
+        let mut grads = grads
+            .transpose_axes(&[0, 2, 1])
+            .map_err(Conv2dError::TransposeFail)?;
+        grads.reshape([(n * oh * ow) as u32, o]);
+
+        let grads_t = grads.clone().transpose();
+        let mut dweights = NdArray::default();
+        grads_t
+            .matmul_f32(&columns, &mut dweights)
+            .map_err(Conv2dError::MatMulFail)?;
+        dweights.reshape(self.weights.value.shape().clone());
+        self.weights.grad = dweights;
+        self.biases.grad = crate::sum(&grads_t);
+
+        let mut dcolumns = NdArray::default();
+        grads
+            .matmul_f32(&self.filter_matrix(), &mut dcolumns)
+            .map_err(Conv2dError::MatMulFail)?;
+        self.dinputs = Some(fold(&dcolumns, &geometry));
+        Ok(())
+    }
+}
+
+impl Module for Conv2d {
+    type Error = Conv2dError;
+
+    fn forward(&mut self, inputs: NdArray<f32>) -> Result<NdArray<f32>, Self::Error> {
+        Conv2d::forward(self, inputs)?;
+        Ok(self.output.clone())
+    }
+
+    fn backward(&mut self, dvalues: NdArray<f32>) -> Result<NdArray<f32>, Self::Error> {
+        Conv2d::backward(self, dvalues)?;
+        Ok(self.dinputs.clone().unwrap())
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        vec![&mut self.weights, &mut self.biases]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conv2d_matches_direct_convolution() {
+        let mut layer = Conv2d::new(2, 3, 3, 2, Padding::Fixed(1));
+        let inputs = NdArray::new_with_values(
+            &[2, 2, 5, 4][..],
+            (0..80).map(|x| ((x * 7) % 11) as f32 - 5.0).collect(),
+        )
+        .unwrap();
+
+        layer.forward(inputs.clone()).unwrap();
+        assert_eq!(layer.output.shape().as_slice(), &[2, 3, 3, 2]);
+
+        let x = |b: usize, c: usize, y: i64, x: i64| -> f32 {
+            if y < 0 || x < 0 || y >= 5 || x >= 4 {
+                return 0.0;
+            }
+            inputs.as_slice()[((b * 2 + c) * 5 + y as usize) * 4 + x as usize]
+        };
+        let w = layer.weights.value.as_slice();
+        let b = layer.biases.value.as_slice();
+        for (i, out) in layer.output.as_slice().iter().enumerate() {
+            let (batch, o, oy, ox) = (i / 18, i / 6 % 3, i / 2 % 3, i % 2);
+            let mut expected = b[o];
+            for c in 0..2 {
+                for ky in 0..3 {
+                    for kx in 0..3 {
+                        let (y, xx) = ((oy * 2 + ky) as i64 - 1, (ox * 2 + kx) as i64 - 1);
+                        expected += w[((o * 2 + c) * 3 + ky) * 3 + kx] * x(batch, c, y, xx);
+                    }
+                }
+            }
+            assert!((out - expected).abs() < 1e-4, "{} {} {}", i, out, expected);
+        }
+    }
+
+    #[test]
+    fn test_conv2d_gradients() {
+        let mut layer = Conv2d::new(1, 1, 2, 1, Padding::Valid);
+        layer.weights.value =
+            NdArray::new_with_values(&[1, 1, 2, 2][..], smallvec::smallvec![1.0, 2.0, 3.0, 4.0])
+                .unwrap();
+        let inputs =
+            NdArray::new_with_values(&[1, 1, 2, 3][..], (1..=6).map(|x| x as f32).collect())
+                .unwrap();
+
+        layer.forward(inputs).unwrap();
+        let dvalues =
+            NdArray::new_with_values(&[1, 1, 1, 2][..], smallvec::smallvec![1.0, 1.0]).unwrap();
+        layer.backward(dvalues).unwrap();
+
+        // each weight sees the sum of the inputs under it in both positions
+        assert_eq!(layer.weights.grad.as_slice(), &[3.0, 5.0, 9.0, 11.0]);
+        assert_eq!(layer.biases.grad.as_slice(), &[2.0]);
+        assert_eq!(
+            layer.dinputs.unwrap().as_slice(),
+            &[1.0, 3.0, 2.0, 3.0, 7.0, 4.0]
+        );
+    }
+
+    #[test]
+    fn test_conv2d_rejects_bad_geometry() {
+        let mut layer = Conv2d::new(2, 1, 3, 1, Padding::Fixed(u32::MAX));
+
+        let err = layer
+            .forward(NdArray::new_default(&[2, 5, 5][..]))
+            .unwrap_err();
+        assert!(matches!(err, Conv2dError::UnsupportedRank(_)));
+        assert_eq!(
+            err.to_string(),
+            "Expected [N, C, H, W] inputs, got shape Tensor([2, 5, 5])"
+        );
+
+        let err = layer
+            .forward(NdArray::new_default(&[1, 2, 5, 5][..]))
+            .unwrap_err();
+        assert!(matches!(err, Conv2dError::PaddingOverflow { .. }));
+
+        let inputs = NdArray::new_default(&[1, 1, 5, 5][..]);
+        assert!(matches!(
+            im2col(&inputs, 3, 0, Padding::Valid),
+            Err(Conv2dError::ZeroStride)
+        ));
+    }
+
+    #[test]
+    fn test_same_padding() {
+        let inputs = NdArray::new_default(&[1, 1, 5, 5][..]);
+        let mut layer = Conv2d::new(1, 1, 3, 1, Padding::Same);
+        layer.forward(inputs.clone()).unwrap();
+        assert_eq!(layer.output.shape().as_slice(), &[1, 1, 5, 5]);
+
+        // the odd zero of an even kernel goes to the bottom and right
+        let geometry = Geometry::new(inputs.shape(), 2, 1, Padding::Same).unwrap();
+        assert_eq!(geometry.output, [5, 5]);
+        assert_eq!(geometry.padding, [0, 0]);
+        let geometry = Geometry::new(inputs.shape(), 4, 1, Padding::Same).unwrap();
+        assert_eq!(geometry.padding, [1, 1]);
+
+        let geometry = Geometry::new(inputs.shape(), 3, 2, Padding::Same).unwrap();
+        assert_eq!(geometry.output, [3, 3]);
+        let geometry = Geometry::new(inputs.shape(), 3, 2, Padding::Valid).unwrap();
+        assert_eq!(geometry.output, [2, 2]);
+    }
+}
@@ -1,3 +1,5 @@
+use super::{Module, Parameter};
+use crate::activation::Activation;
 use crate::ndarray::{NdArray, NdArrayError};
 use rand::Rng;
 
@@ -7,9 +9,11 @@ use rayon::prelude::*;
 #[derive(Clone)]
 pub struct DenseLayer {
     // core attributes
-    pub weights: NdArray<f32>,
-    pub biases: NdArray<f32>,
+    pub weights: Parameter,
+    pub biases: Parameter,
     pub output: NdArray<f32>,
+    /// Applied to the output in the same pass as the biases
+    pub activation: Option<Activation>,
 
     pub training: Option<Box<DenseLayerTraining>>,
 }
@@ -19,9 +23,9 @@ pub struct DenseLayer {
 pub struct DenseLayerTraining {
     // memoization for training purposes
     pub inputs: NdArray<f32>,
+    /// Output before the activation, if the layer has one
+    pub preactivation: NdArray<f32>,
     // training data
-    pub dweights: NdArray<f32>,
-    pub dbiases: NdArray<f32>,
     pub dinputs: NdArray<f32>,
     // hyperparameters
     pub weight_regularizer_l1: Option<f32>,
@@ -34,6 +38,10 @@ pub struct DenseLayerTraining {
 pub enum DenseLayerError {
     #[error("Failed to perform matrix multiplication {0}")]
     MatMulFail(NdArrayError),
+    #[error("Failed to add the biases {0}")]
+    BiasFail(NdArrayError),
+    #[error("Failed to back propagate the activation {0}")]
+    ActivationFail(NdArrayError),
     #[error("No inputs available. Perhaps you forgot to call `forward`?")]
     NoInputs,
 }
@@ -57,9 +65,10 @@ impl DenseLayer {
         .unwrap();
 
         Self {
-            weights,
-            biases,
+            weights: Parameter::new(weights),
+            biases: Parameter::new(biases),
             output: Default::default(),
+            activation: None,
             training: None,
         }
     }
@@ -81,6 +90,13 @@ impl DenseLayer {
         self
     }
 
+    /// Apply `activation` to the output. The activation is fused with adding the biases, saving
+    /// a pass over the output compared to a separate activation layer.
+    pub fn with_activation(mut self, activation: Activation) -> Self {
+        self.activation = Some(activation);
+        self
+    }
+
     pub fn forward(&mut self, inputs: NdArray<f32>) -> Result<(), DenseLayerError> {
         assert!(
             matches!(inputs.shape(), crate::prelude::Shape::Matrix(_)),
@@ -89,17 +105,14 @@ impl DenseLayer {
         self.output.reshape(0);
 
         inputs
-            .matmul_f32(&self.weights, &mut self.output)
+            .matmul_f32(&self.weights.value, &mut self.output)
             .map_err(DenseLayerError::MatMulFail)?;
 
-        assert_eq!(self.output.shape().last(), self.biases.shape().last());
-
-        let biases = self.biases.as_slice();
-        self.output.iter_rows_mut().for_each(|col| {
-            col.iter_mut()
-                .zip(biases.iter())
-                .for_each(|(out, bias)| *out += bias)
-        });
+        match self.activation {
+            Some(activation) => self.add_biases_activated(activation)?,
+            None => crate::broadcast_add_axis(&mut self.output, &self.biases.value, 1)
+                .map_err(DenseLayerError::BiasFail)?,
+        }
 
         if let Some(ref mut t) = self.training {
             t.inputs = inputs;
@@ -119,38 +132,105 @@ impl DenseLayer {
         // we know that training is some at this point
         let training = self.training.as_mut().unwrap();
 
+        let mut dvalues = dvalues;
+        if let Some(activation) = self.activation {
+            let z = std::mem::take(&mut training.preactivation);
+            if z.shape() != dvalues.shape() {
+                return Err(DenseLayerError::ActivationFail(
+                    NdArrayError::ShapeMismatch {
+                        expected: z.shape().clone(),
+                        actual: dvalues.shape().clone(),
+                    },
+                ));
+            }
+            for (dv, z) in dvalues.as_mut_slice().iter_mut().zip(z.as_slice()) {
+                *dv *= activation.derivative(*z);
+            }
+        }
+
         let inputs_t = inputs.transpose();
         inputs_t
-            .matmul_f32(&dvalues, &mut training.dweights)
+            .matmul_f32(&dvalues, &mut self.weights.grad)
             .map_err(DenseLayerError::MatMulFail)?;
 
         let s = dvalues.clone().transpose();
-        training.dbiases = crate::sum(&s);
+        self.biases.grad = crate::sum(&s);
 
         // Regularization
         if let Some(l1) = training.weight_regularizer_l1 {
-            regularize_l1(l1, &mut training.dweights, &self.weights);
+            regularize_l1(l1, &mut self.weights.grad, &self.weights.value);
         }
         if let Some(l2) = training.weight_regularizer_l2 {
-            regularize_l2(l2, &mut training.dweights, &self.weights);
+            regularize_l2(l2, &mut self.weights.grad, &self.weights.value);
         }
 
         if let Some(l1) = training.bias_regularizer_l1 {
-            regularize_l1(l1, &mut training.dbiases, &self.biases);
+            regularize_l1(l1, &mut self.biases.grad, &self.biases.value);
         }
         if let Some(l2) = training.bias_regularizer_l2 {
-            regularize_l2(l2, &mut training.dbiases, &self.biases);
+            regularize_l2(l2, &mut self.biases.grad, &self.biases.value);
         }
 
         // Gradients
         dvalues
-            .matmul_f32(&self.weights.clone().transpose(), &mut training.dinputs)
+            .matmul_f32(
+                &self.weights.value.clone().transpose(),
+                &mut training.dinputs,
+            )
             .map_err(DenseLayerError::MatMulFail)?;
 
         Ok(())
     }
 }
 
+impl DenseLayer {
+    /// Add the biases and apply `activation` in a single pass over the output, caching the
+    /// values before the activation for `backward`
+    fn add_biases_activated(&mut self, activation: Activation) -> Result<(), DenseLayerError> {
+        let biases = self.biases.value.as_slice();
+        let width = self.output.shape().last() as usize;
+        if biases.len() != width {
+            return Err(DenseLayerError::BiasFail(NdArrayError::DimensionMismatch {
+                expected: width,
+                actual: biases.len(),
+            }));
+        }
+
+        let output = &mut self.output;
+        let mut preactivation = self.training.as_mut().map(|t| {
+            t.preactivation = output.clone();
+            t.preactivation.as_mut_slice()
+        });
+        for (i, v) in output.as_mut_slice().iter_mut().enumerate() {
+            let z = *v + biases[i % width];
+            if let Some(cache) = preactivation.as_mut() {
+                cache[i] = z;
+            }
+            *v = activation.apply(z);
+        }
+        Ok(())
+    }
+}
+
+impl Module for DenseLayer {
+    type Error = DenseLayerError;
+
+    fn forward(&mut self, inputs: NdArray<f32>) -> Result<NdArray<f32>, Self::Error> {
+        DenseLayer::forward(self, inputs)?;
+        Ok(self.output.clone())
+    }
+
+    /// Requires the layer to be set up [with_training](DenseLayer::with_training)
+    fn backward(&mut self, dvalues: NdArray<f32>) -> Result<NdArray<f32>, Self::Error> {
+        DenseLayer::backward(self, dvalues)?;
+        Ok(self.training.as_ref().unwrap().dinputs.clone())
+    }
+
+    fn parameters_mut(&mut self) -> Vec<&mut Parameter> {
+        vec![&mut self.weights, &mut self.biases]
+    }
+}
+
 fn regularize_l1(l1: f32, to_regulate: &mut NdArray<f32>, inp: &NdArray<f32>) {
     let mut d_l1 = NdArray::new_with_values(
         inp.shape().clone(),
@@ -168,7 +248,7 @@ fn regularize_l1(l1: f32, to_regulate: &mut NdArray<f32>, inp: &NdArray<f32>) {
 
     #[cfg(feature = "rayon")]
     {
-        d_l1.as_mut_slice().par_iter_mut().for_each(|x| *x *= l1);
+        crate::thread_pool::install(|| d_l1.as_mut_slice().par_iter_mut().for_each(|x| *x *= l1));
     }
     #[cfg(not(feature = "rayon"))]
     {
@@ -0,0 +1,160 @@
+use super::Parameter;
+use crate::ndarray::{NdArray, NdArrayError};
+use rand::Rng;
+
+/// Lookup table mapping integer indices to rows of a trainable weight matrix
+#[derive(Clone)]
+pub struct Embedding {
+    /// `[num_embeddings, embedding_dim]` matrix, one row per index
+    pub weights: Parameter,
+    /// Scale the looked up rows to unit L2 norm
+    pub normalize: bool,
+    pub output: NdArray<f32>,
+
+    // memoization for training purposes
+    pub inputs: Option<NdArray<i64>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EmbeddingError {
+    #[error("Failed to look up the embeddings {0}")]
+    LookupFail(NdArrayError),
+    #[error("Failed to normalize the embeddings {0}")]
+    NormalizeFail(NdArrayError),
+    #[error("Expected gradients for {expected} values, got {actual}")]
+    GradientMismatch { expected: usize, actual: usize },
+    #[error("No inputs available. Perhaps you forgot to call `forward`?")]
+    NoInputs,
+}
+
+impl Embedding {
+    pub fn new(num_embeddings: u32, embedding_dim: u32, normalize: bool) -> Self {
+        let weights = NdArray::new_with_values(
+            [num_embeddings, embedding_dim],
+            (0..num_embeddings as usize * embedding_dim as usize)
+                .map(|_| rand::thread_rng().gen_range(-1., 1.))
+                .collect(),
+        )
+        .unwrap();
+
+        Self {
+            weights: Parameter::new(weights),
+            normalize,
+            output: Default::default(),
+            inputs: None,
+        }
+    }
+
+    pub fn embedding_dim(&self) -> u32 {
+        self.weights.value.shape().last()
+    }
+
+    /// Look up the rows of `indices`. The output has the shape of `indices` with the embedding
+    /// dimension appended.
+    pub fn forward(&mut self, indices: NdArray<i64>) -> Result<(), EmbeddingError> {
+        let mut rows = self.lookup(&indices)?;
+        if self.normalize {
+            rows = crate::normalize_f32(&rows, 1, 2.0).map_err(EmbeddingError::NormalizeFail)?;
+        }
+
+        let mut shape = indices.shape().as_slice().to_vec();
+        shape.push(self.embedding_dim());
+        rows.reshape(shape);
+
+        self.output = rows;
+        self.inputs = Some(indices);
+        Ok(())
+    }
+
+    /// Accumulate `dvalues` into the gradient of the rows used in the last `forward`. Rows
+    /// looked up multiple times receive the sum of their gradients.
+    ///
+    /// Consumes the last `inputs` replacing it with `None`.
+    pub fn backward(&mut self, dvalues: NdArray<f32>) -> Result<(), EmbeddingError> {
+        let indices = self.inputs.take().ok_or(EmbeddingError::NoInputs)?;
+        let dim = self.embedding_dim() as usize;
+        let expected = indices.len() * dim;
+        if dvalues.len() != expected {
+            return Err(EmbeddingError::GradientMismatch {
+                expected,
+                actual: dvalues.len(),
+            });
+        }
+
+        let mut grads = dvalues;
+        grads.reshape([indices.len() as u32, dim as u32]);
+        if self.normalize {
+            // y = x / |x|  =>  dx = (dy - y * (y . dy)) / |x|
+            let rows = self.lookup(&indices)?;
+            for (x, dy) in rows.iter_rows().zip(grads.iter_rows_mut()) {
+                let norm = x.iter().map(|x| x * x).sum::<f32>().sqrt();
+                if norm < f32::EPSILON {
+                    dy.iter_mut().for_each(|dy| *dy /= f32::EPSILON);
+                    continue;
+                }
+                let dot = x.iter().zip(dy.iter()).map(|(x, dy)| x * dy).sum::<f32>() / norm;
+                for (dy, x) in dy.iter_mut().zip(x.iter()) {
+                    *dy = (*dy - x / norm * dot) / norm;
+                }
+            }
+        }
+
+        self.weights.zero_grad();
+        self.weights
+            .grad
+            .scatter_add(0, &row_index(&indices, dim), &grads)
+            .map_err(EmbeddingError::LookupFail)?;
+
+        Ok(())
+    }
+
+    /// Gather the rows of `indices` into a `[indices.len(), embedding_dim]` matrix
+    fn lookup(&self, indices: &NdArray<i64>) -> Result<NdArray<f32>, EmbeddingError> {
+        let index = row_index(indices, self.embedding_dim() as usize);
+        self.weights
+            .value
+            .gather(0, &index)
+            .map_err(EmbeddingError::LookupFail)
+    }
+}
+
+/// Repeat each index over a row, selecting whole rows in `gather` and `scatter_add` along axis 0
+fn row_index(indices: &NdArray<i64>, dim: usize) -> NdArray<i64> {
+    NdArray::new_with_values(
+        [indices.len() as u32, dim as u32],
+        indices
+            .as_slice()
+            .iter()
+            .flat_map(|i| std::iter::repeat_n(*i, dim))
+            .collect(),
+    )
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backward_accumulates_repeated_rows() {
+        let mut layer = Embedding::new(3, 2, false);
+        let indices = NdArray::new_with_values(3, smallvec::smallvec![1, 0, 1]).unwrap();
+
+        layer.forward(indices).unwrap();
+        assert_eq!(layer.output.shape().as_slice(), &[3, 2]);
+        assert_eq!(
+            &layer.output.as_slice()[..2],
+            &layer.weights.value.as_slice()[2..4]
+        );
+
+        let dvalues =
+            NdArray::new_with_values([3, 2], smallvec::smallvec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0])
+                .unwrap();
+        layer.backward(dvalues).unwrap();
+
+        assert_eq!(
+            layer.weights.grad.as_slice(),
+            &[3.0, 4.0, 6.0, 8.0, 0.0, 0.0]
+        );
+    }
+}
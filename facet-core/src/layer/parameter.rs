@@ -0,0 +1,41 @@
+use crate::ndarray::NdArray;
+
+/// A trainable value and the gradient of the loss with respect to it
+#[derive(Clone, Default)]
+pub struct Parameter {
+    pub value: NdArray<f32>,
+    pub grad: NdArray<f32>,
+}
+
+impl Parameter {
+    /// Initializes the gradient to zeros with the shape of `value`
+    pub fn new(value: NdArray<f32>) -> Self {
+        let grad = NdArray::new_default(value.shape().clone());
+        Self { value, grad }
+    }
+
+    /// Reset the gradient to zeros with the shape of the value
+    pub fn zero_grad(&mut self) {
+        self.grad = NdArray::new_default(self.value.shape().clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_grad() {
+        let value = NdArray::new_with_values([2, 3], (0..6).map(|x| x as f32).collect()).unwrap();
+        let mut p = Parameter::new(value);
+
+        assert_eq!(p.grad.shape(), p.value.shape());
+        assert_eq!(p.grad.as_slice(), &[0.0; 6]);
+
+        p.grad.as_mut_slice().iter_mut().for_each(|x| *x = 1.0);
+        p.zero_grad();
+
+        assert_eq!(p.grad.shape(), p.value.shape());
+        assert_eq!(p.grad.as_slice(), &[0.0; 6]);
+    }
+}
@@ -0,0 +1,50 @@
+use super::Module;
+use crate::{activation, ndarray::NdArray};
+
+/// Applies ReLU to its inputs
+#[derive(Clone, Default)]
+pub struct Relu {
+    pub output: NdArray<f32>,
+    pub dinputs: Option<NdArray<f32>>,
+
+    // memoization for training purposes
+    pub inputs: Option<NdArray<f32>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReluError {
+    #[error("No inputs available. Perhaps you forgot to call `forward`?")]
+    NoInputs,
+}
+
+impl Relu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn forward(&mut self, inputs: NdArray<f32>) {
+        self.output = activation::relu(&inputs);
+        self.inputs = Some(inputs);
+    }
+
+    /// Consumes the last `inputs` replacing it with `None`.
+    pub fn backward(&mut self, dvalues: NdArray<f32>) -> Result<(), ReluError> {
+        let inputs = self.inputs.take().ok_or(ReluError::NoInputs)?;
+        self.dinputs = Some(activation::drelu_dz(&inputs, &dvalues));
+        Ok(())
+    }
+}
+
+impl Module for Relu {
+    type Error = ReluError;
+
+    fn forward(&mut self, inputs: NdArray<f32>) -> Result<NdArray<f32>, Self::Error> {
+        Relu::forward(self, inputs);
+        Ok(self.output.clone())
+    }
+
+    fn backward(&mut self, dvalues: NdArray<f32>) -> Result<NdArray<f32>, Self::Error> {
+        Relu::backward(self, dvalues)?;
+        Ok(self.dinputs.clone().unwrap())
+    }
+}
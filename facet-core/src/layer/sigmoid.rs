@@ -0,0 +1,56 @@
+use super::Module;
+use crate::{activation, ndarray::NdArray, DuError};
+
+/// Applies the sigmoid function to its inputs
+#[derive(Clone, Default)]
+pub struct Sigmoid {
+    pub output: NdArray<f32>,
+    pub dinputs: Option<NdArray<f32>>,
+
+    // memoization for training purposes
+    has_output: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SigmoidError {
+    #[error("No output available. Perhaps you forgot to call `forward`?")]
+    NoOutput,
+    #[error("Failed to compute the gradient {0}")]
+    Gradient(DuError),
+}
+
+impl Sigmoid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn forward(&mut self, inputs: NdArray<f32>) {
+        activation::sigmoid(&inputs, &mut self.output).expect("sigmoid can not fail");
+        self.has_output = true;
+    }
+
+    /// Consumes the last output, a second call fails until the next `forward`.
+    pub fn backward(&mut self, dvalues: NdArray<f32>) -> Result<(), SigmoidError> {
+        if !std::mem::take(&mut self.has_output) {
+            return Err(SigmoidError::NoOutput);
+        }
+        let dinputs =
+            activation::dsigmoid(&self.output, &dvalues).map_err(SigmoidError::Gradient)?;
+        self.dinputs = Some(dinputs);
+        Ok(())
+    }
+}
+
+impl Module for Sigmoid {
+    type Error = SigmoidError;
+
+    fn forward(&mut self, inputs: NdArray<f32>) -> Result<NdArray<f32>, Self::Error> {
+        Sigmoid::forward(self, inputs);
+        Ok(self.output.clone())
+    }
+
+    fn backward(&mut self, dvalues: NdArray<f32>) -> Result<NdArray<f32>, Self::Error> {
+        Sigmoid::backward(self, dvalues)?;
+        Ok(self.dinputs.clone().unwrap())
+    }
+}
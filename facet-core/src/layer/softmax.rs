@@ -0,0 +1,57 @@
+use super::Module;
+use crate::{activation, ndarray::NdArray, DuError};
+
+/// Applies softmax to each row of its inputs
+#[derive(Clone, Default)]
+pub struct Softmax {
+    pub output: NdArray<f32>,
+    pub dinputs: Option<NdArray<f32>>,
+
+    // memoization for training purposes
+    has_output: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SoftmaxError {
+    #[error("No output available. Perhaps you forgot to call `forward`?")]
+    NoOutput,
+    #[error("Failed to compute softmax {0}")]
+    Compute(DuError),
+}
+
+impl Softmax {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn forward(&mut self, inputs: NdArray<f32>) -> Result<(), SoftmaxError> {
+        self.output = activation::softmax(&inputs).map_err(SoftmaxError::Compute)?;
+        self.has_output = true;
+        Ok(())
+    }
+
+    /// Consumes the last output, a second call fails until the next `forward`.
+    pub fn backward(&mut self, dvalues: NdArray<f32>) -> Result<(), SoftmaxError> {
+        if !std::mem::take(&mut self.has_output) {
+            return Err(SoftmaxError::NoOutput);
+        }
+        let dinputs =
+            activation::dsoftmax(&self.output, &dvalues).map_err(SoftmaxError::Compute)?;
+        self.dinputs = Some(dinputs);
+        Ok(())
+    }
+}
+
+impl Module for Softmax {
+    type Error = SoftmaxError;
+
+    fn forward(&mut self, inputs: NdArray<f32>) -> Result<NdArray<f32>, Self::Error> {
+        Softmax::forward(self, inputs)?;
+        Ok(self.output.clone())
+    }
+
+    fn backward(&mut self, dvalues: NdArray<f32>) -> Result<NdArray<f32>, Self::Error> {
+        Softmax::backward(self, dvalues)?;
+        Ok(self.dinputs.clone().unwrap())
+    }
+}
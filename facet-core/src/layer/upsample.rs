@@ -0,0 +1,136 @@
+use super::Module;
+use crate::ndarray::{shape::Shape, NdArray, NdArrayError};
+
+/// Enlarges the last two axes, e.g. `H` and `W` of `[N, C, H, W]` inputs, by an integer factor
+/// using nearest-neighbor replication
+#[derive(Clone)]
+pub struct Upsample {
+    pub scale_factor: u32,
+    pub output: NdArray<f32>,
+    pub dinputs: Option<NdArray<f32>>,
+
+    // memoization for training purposes
+    pub input_shape: Option<Shape>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpsampleError {
+    #[error("Expected inputs with at least 2 dimensions, got shape {0:?}")]
+    UnsupportedShape(Shape),
+    #[error("Failed to replicate the inputs {0}")]
+    RepeatFail(NdArrayError),
+    #[error("Expected gradients of shape {expected:?}, got {actual:?}")]
+    GradientMismatch { expected: Shape, actual: Shape },
+    #[error("No inputs available. Perhaps you forgot to call `forward`?")]
+    NoInputs,
+}
+
+impl Upsample {
+    pub fn new(scale_factor: u32) -> Self {
+        Self {
+            scale_factor,
+            output: Default::default(),
+            dinputs: None,
+            input_shape: None,
+        }
+    }
+
+    /// Repeat every element `scale_factor` times along each of the last two axes
+    pub fn forward(&mut self, inputs: NdArray<f32>) -> Result<(), UpsampleError> {
+        let ndims = inputs.shape().as_slice().len();
+        if ndims < 2 {
+            return Err(UpsampleError::UnsupportedShape(inputs.shape().clone()));
+        }
+        let [h, w] = [ndims as u32 - 2, ndims as u32 - 1];
+        self.output = inputs
+            .repeat_axis(h, self.scale_factor)
+            .and_then(|rows| rows.repeat_axis(w, self.scale_factor))
+            .map_err(UpsampleError::RepeatFail)?;
+        self.input_shape = Some(inputs.shape().clone());
+        Ok(())
+    }
+
+    /// The gradient of each input is the sum of the gradients of the `scale_factor x
+    /// scale_factor` block it was replicated into.
+    ///
+    /// Consumes the last input shape replacing it with `None`.
+    pub fn backward(&mut self, dvalues: NdArray<f32>) -> Result<(), UpsampleError> {
+        let shape = self.input_shape.take().ok_or(UpsampleError::NoInputs)?;
+        if dvalues.shape() != self.output.shape() {
+            return Err(UpsampleError::GradientMismatch {
+                expected: self.output.shape().clone(),
+                actual: dvalues.shape().clone(),
+            });
+        }
+        let dims = shape.as_slice();
+        let [h, w] = [dims[dims.len() - 2] as usize, dims[dims.len() - 1] as usize];
+        let s = self.scale_factor as usize;
+
+        let mut dinputs = NdArray::new_default(shape.clone());
+        if h * w * s > 0 {
+            let grads = dvalues.as_slice();
+            for (plane, dplane) in dinputs
+                .as_mut_slice()
+                .chunks_exact_mut(h * w)
+                .zip(grads.chunks_exact(h * w * s * s))
+            {
+                for (y, row) in dplane.chunks_exact(w * s).enumerate() {
+                    for (x, g) in row.iter().enumerate() {
+                        plane[y / s * w + x / s] += g;
+                    }
+                }
+            }
+        }
+        self.dinputs = Some(dinputs);
+        Ok(())
+    }
+}
+
+impl Module for Upsample {
+    type Error = UpsampleError;
+
+    fn forward(&mut self, inputs: NdArray<f32>) -> Result<NdArray<f32>, Self::Error> {
+        Upsample::forward(self, inputs)?;
+        Ok(self.output.clone())
+    }
+
+    fn backward(&mut self, dvalues: NdArray<f32>) -> Result<NdArray<f32>, Self::Error> {
+        Upsample::backward(self, dvalues)?;
+        Ok(self.dinputs.clone().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smallvec::smallvec;
+
+    #[test]
+    fn test_nearest_upsample_and_gradient() {
+        let mut layer = Upsample::new(2);
+        let inputs =
+            NdArray::new_with_values(&[1, 1, 2, 2][..], smallvec![1.0, 2.0, 3.0, 4.0]).unwrap();
+
+        layer.forward(inputs).unwrap();
+        assert_eq!(layer.output.shape().as_slice(), &[1, 1, 4, 4]);
+        #[rustfmt::skip]
+        assert_eq!(
+            layer.output.as_slice(),
+            &[
+                1.0, 1.0, 2.0, 2.0,
+                1.0, 1.0, 2.0, 2.0,
+                3.0, 3.0, 4.0, 4.0,
+                3.0, 3.0, 4.0, 4.0,
+            ]
+        );
+
+        let dvalues =
+            NdArray::new_with_values(&[1, 1, 4, 4][..], (0..16).map(|x| x as f32).collect())
+                .unwrap();
+        layer.backward(dvalues).unwrap();
+        let dinputs = layer.dinputs.unwrap();
+        assert_eq!(dinputs.shape().as_slice(), &[1, 1, 2, 2]);
+        // 0+1+4+5, 2+3+6+7, 8+9+12+13, 10+11+14+15
+        assert_eq!(dinputs.as_slice(), &[10.0, 18.0, 42.0, 50.0]);
+    }
+}
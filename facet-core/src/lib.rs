@@ -1,15 +1,20 @@
 //!
 //! ## Features
 //!
-//! - `rayon`: Enables CPU parallelism via the rayon crate
+//! - `rayon`: Enables CPU parallelism via the rayon crate. Parallel operations run on the
+//!   crate-owned [thread_pool](crate::thread_pool).
 //!
 use ndarray::{shape::Shape, NdArrayError};
+use smallvec::SmallVec;
 
 pub mod activation;
+pub mod autograd;
 pub mod layer;
 pub mod loss;
 pub mod ndarray;
 pub mod prelude;
+#[cfg(feature = "rayon")]
+pub mod thread_pool;
 
 #[cfg(test)]
 mod tests;
@@ -50,6 +55,10 @@ impl SquareRoot for f64 {
     }
 }
 
+/// Sum the last axis, removing it from the shape.
+///
+/// Each row is summed sequentially from the first element to the last, on a single thread, so the
+/// result of floating point inputs is bit-reproducible across calls and thread pools.
 pub fn sum<'a, T>(inp: &ndarray::NdArray<T>) -> ndarray::NdArray<T>
 where
     T: 'a + std::iter::Sum + Copy,
@@ -69,6 +78,144 @@ where
     }
 }
 
+/// Same as [sum], but accumulates integers into `i128`, so sums of `i64` values can not overflow.
+///
+/// ```
+/// use facet_core::prelude::*;
+///
+/// let a = NdArray::new_vector(vec![i64::MAX, i64::MAX, 2]);
+///
+/// let s = sum_wide(&a);
+///
+/// assert_eq!(s.as_slice(), &[2 * i64::MAX as i128 + 2]);
+/// ```
+pub fn sum_wide<T>(inp: &ndarray::NdArray<T>) -> ndarray::NdArray<i128>
+where
+    T: Copy + Into<i128>,
+{
+    let res = inp
+        .iter_rows()
+        .map(|x| x.iter().map(|x| (*x).into()).sum())
+        .collect::<ndarray::Data<_>>();
+
+    let shape = inp.shape();
+    let shape = shape.as_slice();
+    if !shape.is_empty() {
+        ndarray::NdArray::new_with_values(&shape[..shape.len() - 1], res).unwrap()
+    } else {
+        // scalar
+        ndarray::NdArray::new_with_values(0, res).unwrap()
+    }
+}
+
+/// Product of all elements. The product of an empty array is 1, like numpy.
+///
+/// ```
+/// use facet_core::prelude::*;
+///
+/// let a = NdArray::new_vector(vec![1, 2, 3, 4]);
+/// assert_eq!(prod(&a), 24);
+///
+/// let empty = NdArray::<i64>::new_with_values([2, 0], smallvec![]).unwrap();
+/// assert_eq!(prod(&empty), 1);
+/// ```
+pub fn prod<T>(inp: &ndarray::NdArray<T>) -> T
+where
+    T: std::iter::Product + Copy,
+{
+    inp.as_slice().iter().copied().product()
+}
+
+/// Same as [prod], but accumulates integers into `i128`. Returns `None` if the product overflows
+/// even `i128`.
+///
+/// ```
+/// use facet_core::prelude::*;
+///
+/// let a = NdArray::new_vector(vec![i64::MAX, 2]);
+///
+/// assert_eq!(prod_wide(&a), Some(2 * i64::MAX as i128));
+/// ```
+pub fn prod_wide<T>(inp: &ndarray::NdArray<T>) -> Option<i128>
+where
+    T: Copy + Into<i128>,
+{
+    inp.as_slice()
+        .iter()
+        .try_fold(1i128, |acc, x| acc.checked_mul((*x).into()))
+}
+
+/// Product of each lane along `axis`, removing `axis` from the shape. Empty lanes return 1.
+pub fn prod_axis<T>(
+    inp: &ndarray::NdArray<T>,
+    axis: u32,
+) -> Result<ndarray::NdArray<T>, NdArrayError>
+where
+    T: std::iter::Product + Copy,
+{
+    reduce_axis(inp, axis, |lane| lane.product())
+}
+
+/// Same as [prod_axis], but accumulates integers into `i128`. Lanes whose product overflows even
+/// `i128` are `None`.
+///
+/// ```
+/// use facet_core::prelude::*;
+///
+/// let max = i64::MAX;
+/// let a = NdArray::new_with_values([2, 3], smallvec![max, 2, 1, max, max, max]).unwrap();
+///
+/// let p = prod_wide_axis(&a, 1).unwrap();
+///
+/// assert_eq!(p.as_slice(), &[Some(2 * i64::MAX as i128), None]);
+/// ```
+pub fn prod_wide_axis<T>(
+    inp: &ndarray::NdArray<T>,
+    axis: u32,
+) -> Result<ndarray::NdArray<Option<i128>>, NdArrayError>
+where
+    T: Copy + Into<i128>,
+{
+    reduce_axis(inp, axis, |mut lane| {
+        Iterator::try_fold(&mut lane, 1i128, |acc, x| acc.checked_mul(x.into()))
+    })
+}
+
+/// The shape of a reduction along `axis`: `shape` without `axis`
+fn remove_axis(shape: &Shape, axis: usize) -> Shape {
+    let mut shape: SmallVec<[u32; 4]> = shape.as_slice().into();
+    shape.remove(axis);
+    match shape.as_slice() {
+        [n] => Shape::Vector([*n]),
+        shape => Shape::from(shape),
+    }
+}
+
+/// Reduce each lane along `axis` with `f`, removing `axis` from the shape
+fn reduce_axis<T, R>(
+    inp: &ndarray::NdArray<T>,
+    axis: u32,
+    f: impl Fn(&mut dyn Iterator<Item = T>) -> R,
+) -> Result<ndarray::NdArray<R>, NdArrayError>
+where
+    T: Copy,
+{
+    let axis = inp.check_axis(axis)?;
+    let [outer, n, inner] = inp.split_at_axis(axis);
+
+    let values = inp.as_slice();
+    let mut res = ndarray::Data::with_capacity(outer * inner);
+    for o in 0..outer {
+        for i in 0..inner {
+            let mut lane = (0..n).map(|k| values[(o * n + k) * inner + i]);
+            res.push(f(&mut lane));
+        }
+    }
+
+    let shape = remove_axis(inp.shape(), axis);
+    ndarray::NdArray::new_with_values(shape, res)
+}
+
 /// Calculate the column-wise mean.
 ///
 /// Scalars will return themselves. While others will collapse the last column into a 1D vector.
@@ -327,6 +474,249 @@ where
     ndarray::NdArray::new_with_values(shape.clone(), res)
 }
 
+/// Calculate the covariance matrix of the rows of the array. Like numpy's `cov`.
+///
+/// Each row is a variable and each column an observation. The result is normalized by `N-1`,
+/// where `N` is the number of observations.
+///
+/// Vectors are treated as a single variable and return a scalar.
+///
+/// ```
+/// use facet_core::prelude::*;
+///
+/// let a = NdArray::new_with_values([2, 3], smallvec![0.0f32, 1.0, 2.0, 2.0, 1.0, 0.0]).unwrap();
+///
+/// let c = cov(&a).unwrap();
+///
+/// assert_eq!(c.shape(), &Shape::Matrix([2, 2]));
+/// assert_eq!(c.as_slice(), &[1.0, -1.0, -1.0, 1.0]);
+/// ```
+pub fn cov<T>(inp: &ndarray::NdArray<T>) -> Result<ndarray::NdArray<T>, NdArrayError>
+where
+    T: Copy
+        + Default
+        + std::iter::Sum
+        + std::ops::Add<Output = T>
+        + std::ops::AddAssign
+        + std::ops::Mul<Output = T>
+        + std::ops::Div<f32, Output = T>
+        + std::ops::Sub<T, Output = T>
+        + Send
+        + Sync,
+{
+    let [vars, n] = match inp.shape() {
+        Shape::Vector([n]) => [1, *n],
+        Shape::Matrix([m, n]) => [*m, *n],
+        _ => return Err(NdArrayError::UnsupportedShape(inp.shape().clone())),
+    };
+    if n < 2 {
+        return Err(NdArrayError::BadInput(format!(
+            "cov requires at least 2 observations, got {}",
+            n
+        )));
+    }
+
+    let mean = crate::mean(inp)?;
+    let mut centered = inp.clone();
+    for (row, m) in centered.iter_rows_mut().zip(mean.as_slice().iter()) {
+        row.iter_mut().for_each(|x| *x = *x - *m);
+    }
+    centered.reshape([vars, n]);
+
+    let mut res = ndarray::NdArray::new(0);
+    centered.matmul(&centered.clone().transpose(), &mut res)?;
+    let norm = (n - 1) as f32;
+    res.as_mut_slice().iter_mut().for_each(|x| *x = *x / norm);
+
+    if vars == 1 && matches!(inp.shape(), Shape::Vector(_)) {
+        res.reshape(0);
+    }
+    Ok(res)
+}
+
+/// Calculate the Pearson correlation coefficients of the rows of the array. Like numpy's
+/// `corrcoef`.
+///
+/// See [cov](crate::cov) for the layout of the input.
+///
+/// ```
+/// use facet_core::prelude::*;
+///
+/// let a = NdArray::new_with_values([2, 3], smallvec![0.0f32, 1.0, 2.0, 4.0, 2.0, 0.0]).unwrap();
+///
+/// let c = corrcoef(&a).unwrap();
+///
+/// assert_eq!(c.as_slice(), &[1.0, -1.0, -1.0, 1.0]);
+/// ```
+pub fn corrcoef<T>(inp: &ndarray::NdArray<T>) -> Result<ndarray::NdArray<T>, NdArrayError>
+where
+    T: Copy
+        + Default
+        + std::iter::Sum
+        + std::ops::Add<Output = T>
+        + std::ops::AddAssign
+        + std::ops::Mul<Output = T>
+        + std::ops::Div<f32, Output = T>
+        + std::ops::Div<T, Output = T>
+        + std::ops::Sub<T, Output = T>
+        + SquareRoot
+        + Send
+        + Sync,
+{
+    let mut res = cov(inp)?;
+    let n = res.shape().last().max(1) as usize;
+    let stds: Vec<T> = (0..n).map(|i| res.as_slice()[i * n + i].sqrt()).collect();
+    for (i, row) in res.iter_rows_mut().enumerate() {
+        for (j, x) in row.iter_mut().enumerate() {
+            *x = *x / (stds[i] * stds[j]);
+        }
+    }
+    Ok(res)
+}
+
+/// Metric used by [pairwise_distances_f32]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceMetric {
+    /// `sqrt(sum((x - y)^2))`
+    Euclidean,
+    /// `sum(|x - y|)`
+    Manhattan,
+}
+
+/// Calculate the distances between each row of `a` and each row of `b`, returning an
+/// `[n_a, n_b]` matrix.
+///
+/// Euclidean distances are computed as `sqrt(|x|^2 + |y|^2 - 2 x.y)` using a matrix
+/// multiplication, so they carry a small rounding error, e.g. the distance of a row to itself
+/// may not be exactly 0.
+///
+/// ```
+/// use facet_core::prelude::*;
+///
+/// let a = NdArray::new_with_values([2, 2], smallvec![0.0f32, 0.0, 3.0, 4.0]).unwrap();
+///
+/// let d = pairwise_distances_f32(&a, &a, DistanceMetric::Manhattan).unwrap();
+///
+/// assert_eq!(d.as_slice(), &[0.0, 7.0, 7.0, 0.0]);
+/// ```
+pub fn pairwise_distances_f32(
+    a: &ndarray::NdArray<f32>,
+    b: &ndarray::NdArray<f32>,
+    metric: DistanceMetric,
+) -> Result<ndarray::NdArray<f32>, NdArrayError> {
+    let (n, d) = match a.shape() {
+        Shape::Matrix([n, d]) => (*n, *d),
+        _ => return Err(NdArrayError::UnsupportedShape(a.shape().clone())),
+    };
+    let m = match b.shape() {
+        Shape::Matrix([m, e]) if *e == d => *m,
+        _ => {
+            return Err(NdArrayError::ShapeMismatch {
+                expected: Shape::Matrix([b.shape().as_slice().first().copied().unwrap_or(0), d]),
+                actual: b.shape().clone(),
+            })
+        }
+    };
+
+    match metric {
+        DistanceMetric::Euclidean => {
+            let mut res = ndarray::NdArray::new(0);
+            a.matmul_f32(&b.clone().transpose(), &mut res)?;
+            let sq_norm = |x: &ndarray::NdArray<f32>| -> Vec<f32> {
+                x.iter_rows()
+                    .map(|row| row.iter().map(|x| x * x).sum())
+                    .collect()
+            };
+            let (na, nb) = (sq_norm(a), sq_norm(b));
+            for (row, x) in res.iter_rows_mut().zip(na.iter()) {
+                for (dot, y) in row.iter_mut().zip(nb.iter()) {
+                    // clamp the rounding errors of near identical rows
+                    *dot = (x + y - 2.0 * *dot).max(0.0).sqrt();
+                }
+            }
+            Ok(res)
+        }
+        DistanceMetric::Manhattan => {
+            let mut res = ndarray::NdArray::new_default([n, m]);
+            for (row, x) in res.iter_rows_mut().zip(a.iter_rows()) {
+                for (dist, y) in row.iter_mut().zip(b.iter_rows()) {
+                    *dist = x.iter().zip(y.iter()).map(|(x, y)| (x - y).abs()).sum();
+                }
+            }
+            Ok(res)
+        }
+    }
+}
+
+/// Add `vec` to `out` along `axis` in place, broadcasting over the other axes. Does not allocate.
+///
+/// The length of `vec` must match the size of `axis` in `out`.
+///
+/// ```
+/// use facet_core::prelude::*;
+///
+/// let mut out = NdArray::new_with_values([2, 3], smallvec![1, 2, 3, 4, 5, 6]).unwrap();
+/// let vec = NdArray::new_vector(smallvec![10, 20]);
+///
+/// broadcast_add_axis(&mut out, &vec, 0).unwrap();
+///
+/// assert_eq!(out.as_slice(), &[11, 12, 13, 24, 25, 26]);
+/// ```
+pub fn broadcast_add_axis<T>(
+    out: &mut ndarray::NdArray<T>,
+    vec: &ndarray::NdArray<T>,
+    axis: usize,
+) -> Result<(), NdArrayError>
+where
+    T: Copy + std::ops::AddAssign,
+{
+    let axis = out.check_axis(axis as u32)?;
+    let [_, n, inner] = out.split_at_axis(axis);
+    if vec.len() != n {
+        return Err(NdArrayError::DimensionMismatch {
+            expected: n,
+            actual: vec.len(),
+        });
+    }
+    if n * inner == 0 {
+        return Ok(());
+    }
+    let vec = vec.as_slice();
+    for chunk in out.as_mut_slice().chunks_exact_mut(n * inner) {
+        for (slice, v) in chunk.chunks_exact_mut(inner).zip(vec.iter()) {
+            for x in slice {
+                *x += *v;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Add the `bias` vector to each row of `out` in place.
+///
+/// The length of `bias` must match the last dimension of `out`.
+///
+/// ```
+/// use facet_core::prelude::*;
+///
+/// let mut out = NdArray::new_with_values([2, 3], smallvec![1, 2, 3, 4, 5, 6]).unwrap();
+/// let bias = NdArray::new_vector(smallvec![10, 20, 30]);
+///
+/// add_bias(&mut out, &bias).unwrap();
+///
+/// assert_eq!(out.as_slice(), &[11, 22, 33, 14, 25, 36]);
+/// ```
+pub fn add_bias<T>(
+    out: &mut ndarray::NdArray<T>,
+    bias: &ndarray::NdArray<T>,
+) -> Result<(), NdArrayError>
+where
+    T: Copy + std::ops::AddAssign,
+{
+    let ndims = out.shape().as_slice().len();
+    broadcast_add_axis(out, bias, ndims.saturating_sub(1))
+}
+
 pub fn clip<T>(inp: &mut ndarray::NdArray<T>, min: T, max: T)
 where
     T: Copy + std::cmp::PartialOrd,
@@ -368,6 +758,201 @@ pub fn normalize_f32_vectors(inp: &ndarray::NdArray<f32>, out: &mut ndarray::NdA
     }
 }
 
+/// Scale each lane along `axis` to unit norm. Unlike [normalize_f32_vectors] the result is exact.
+///
+/// `ord` selects the norm: `sum(|x|^ord)^(1/ord)`, or the largest absolute value if `ord` is
+/// infinite. Lanes with a norm below `f32::EPSILON` are divided by `f32::EPSILON` instead, so
+/// all-zero lanes stay zero.
+///
+/// ```
+/// use facet_core::prelude::*;
+///
+/// let a = NdArray::new_with_values([2, 2], smallvec![3.0f32, 4.0, 0.0, 0.0]).unwrap();
+///
+/// let b = normalize_f32(&a, 1, 2.0).unwrap();
+///
+/// assert_eq!(b.as_slice(), &[0.6, 0.8, 0.0, 0.0]);
+/// ```
+pub fn normalize_f32(
+    inp: &ndarray::NdArray<f32>,
+    axis: u32,
+    ord: f32,
+) -> Result<ndarray::NdArray<f32>, NdArrayError> {
+    if ord.is_nan() || ord <= 0.0 {
+        return Err(NdArrayError::BadInput(format!(
+            "ord must be positive, got {}",
+            ord
+        )));
+    }
+    let axis = inp.check_axis(axis)?;
+    let [outer, n, inner] = inp.split_at_axis(axis);
+
+    let mut out = inp.clone();
+    let values = out.as_mut_slice();
+    for o in 0..outer {
+        for i in 0..inner {
+            let lane = (0..n).map(|k| (o * n + k) * inner + i);
+            let norm = if ord.is_infinite() {
+                lane.clone().map(|j| values[j].abs()).fold(0.0, f32::max)
+            } else {
+                lane.clone()
+                    .map(|j| values[j].abs().powf(ord))
+                    .sum::<f32>()
+                    .powf(1.0 / ord)
+            };
+            let norm = norm.max(f32::EPSILON);
+            lane.for_each(|j| values[j] /= norm);
+        }
+    }
+    Ok(out)
+}
+
+/// Scale down each lane along `axis` whose L2 norm exceeds `max_norm` to a norm of `max_norm`,
+/// in place. Lanes within the limit are left untouched. Used for max-norm constraints on weights.
+///
+/// ```
+/// use facet_core::prelude::*;
+///
+/// let mut a = NdArray::new_with_values([2, 2], smallvec![3.0f32, 4.0, 0.3, 0.4]).unwrap();
+///
+/// clamp_norm_f32(&mut a, 1, 1.0).unwrap();
+///
+/// assert_eq!(a.as_slice(), &[0.6, 0.8, 0.3, 0.4]);
+/// ```
+pub fn clamp_norm_f32(
+    inp: &mut ndarray::NdArray<f32>,
+    axis: u32,
+    max_norm: f32,
+) -> Result<(), NdArrayError> {
+    if max_norm.is_nan() || max_norm < 0.0 {
+        return Err(NdArrayError::BadInput(format!(
+            "max_norm must not be negative, got {}",
+            max_norm
+        )));
+    }
+    let axis = inp.check_axis(axis)?;
+    let [outer, n, inner] = inp.split_at_axis(axis);
+
+    let values = inp.as_mut_slice();
+    for o in 0..outer {
+        for i in 0..inner {
+            let lane = (0..n).map(|k| (o * n + k) * inner + i);
+            let norm = lane
+                .clone()
+                .map(|j| values[j] * values[j])
+                .sum::<f32>()
+                .sqrt();
+            if norm > max_norm {
+                let scale = max_norm / norm;
+                lane.for_each(|j| values[j] *= scale);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Calculate `ln(sum(exp(x)))` of each lane along `axis`, removing `axis` from the shape.
+///
+/// The maximum of each lane is subtracted before exponentiating, so large inputs do not overflow.
+///
+/// ```
+/// use facet_core::prelude::*;
+///
+/// let a = NdArray::new_vector(smallvec![1000.0f32, 1000.0]);
+///
+/// let b = logsumexp_f32(&a, 0).unwrap();
+///
+/// assert_eq!(b.shape(), &Shape::Scalar([0]));
+/// assert!((b.as_slice()[0] - (1000.0 + 2.0f32.ln())).abs() < 1e-3);
+/// ```
+pub fn logsumexp_f32(
+    inp: &ndarray::NdArray<f32>,
+    axis: u32,
+) -> Result<ndarray::NdArray<f32>, NdArrayError> {
+    let axis = inp.check_axis(axis)?;
+    let [outer, n, inner] = inp.split_at_axis(axis);
+    if n == 0 {
+        return Err(NdArrayError::BadInput(
+            "logsumexp of an empty lane is undefined".to_string(),
+        ));
+    }
+
+    let values = inp.as_slice();
+    let mut res = ndarray::Data::with_capacity(outer * inner);
+    for o in 0..outer {
+        for i in 0..inner {
+            let lane = (0..n).map(|k| values[(o * n + k) * inner + i]);
+            let max = lane.clone().fold(f32::NEG_INFINITY, f32::max);
+            if max.is_infinite() {
+                // all -inf, or an inf in the lane
+                res.push(max);
+                continue;
+            }
+            let s: f32 = lane.map(|x| (x - max).exp()).sum();
+            res.push(max + s.ln());
+        }
+    }
+
+    let shape = remove_axis(inp.shape(), axis);
+    ndarray::NdArray::new_with_values(shape, res)
+}
+
+/// Calculate the mean and the population variance of each lane along `axis` in a single pass,
+/// removing `axis` from the shape of both.
+///
+/// The values are read once, in memory order, updating the moments of every lane with Welford's
+/// algorithm. Use this over [mean] and [std_squared] for e.g. the batch statistics of
+/// normalization layers.
+///
+/// ```
+/// use facet_core::prelude::*;
+///
+/// let a = NdArray::new_with_values([3, 2], smallvec![1.0f32, 2.0, 3.0, 2.0, 5.0, 2.0]).unwrap();
+///
+/// let (mean, var) = mean_var_axis(&a, 0).unwrap();
+///
+/// assert_eq!(mean.as_slice(), &[3.0, 2.0]);
+/// assert_eq!(var.as_slice(), &[8.0 / 3.0, 0.0]);
+/// ```
+pub fn mean_var_axis(
+    inp: &ndarray::NdArray<f32>,
+    axis: u32,
+) -> Result<(ndarray::NdArray<f32>, ndarray::NdArray<f32>), NdArrayError> {
+    let axis = inp.check_axis(axis)?;
+    let [outer, n, inner] = inp.split_at_axis(axis);
+    if n == 0 {
+        return Err(NdArrayError::BadInput(
+            "mean and variance of an empty lane are undefined".to_string(),
+        ));
+    }
+
+    let values = inp.as_slice();
+    let mut means = ndarray::Data::with_capacity(outer * inner);
+    let mut vars = ndarray::Data::with_capacity(outer * inner);
+    let mut mean = vec![0.0f32; inner];
+    let mut m2 = vec![0.0f32; inner];
+    for o in 0..outer {
+        mean.iter_mut().for_each(|x| *x = 0.0);
+        m2.iter_mut().for_each(|x| *x = 0.0);
+        for k in 0..n {
+            let row = &values[(o * n + k) * inner..(o * n + k + 1) * inner];
+            for ((x, mean), m2) in row.iter().zip(mean.iter_mut()).zip(m2.iter_mut()) {
+                let d = x - *mean;
+                *mean += d / (k + 1) as f32;
+                *m2 += d * (x - *mean);
+            }
+        }
+        means.extend_from_slice(&mean);
+        vars.extend(m2.iter().map(|m2| m2 / n as f32));
+    }
+
+    let shape = remove_axis(inp.shape(), axis);
+    Ok((
+        ndarray::NdArray::new_with_values(shape.clone(), means)?,
+        ndarray::NdArray::new_with_values(shape, vars)?,
+    ))
+}
+
 #[inline]
 fn _fast_inv_sqrt_f32(mut y: f32) -> f32 {
     const THREE_HALVES: f32 = 1.5;
@@ -443,11 +1028,9 @@ where
     T: Copy + Default + std::ops::AddAssign,
     &'a T: std::ops::Mul<Output = T>,
 {
-    if matches!(inp.shape(), Shape::Scalar(_)) {
-        *out = inp.clone();
-    }
+    // a scalar is treated as a vector of 1 element, resulting in a scalar
     let shape_len = inp.shape().as_slice().len();
-    let out_shape = &inp.shape().as_slice()[..shape_len - 1];
+    let out_shape = &inp.shape().as_slice()[..shape_len.saturating_sub(1)];
     out.reshape(out_shape);
     for (i, vector) in inp.iter_rows().enumerate() {
         let mut vec_len = T::default();
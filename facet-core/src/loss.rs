@@ -41,3 +41,175 @@ pub fn categorical_cross_entropy(
     let res = NdArray::new_with_values(out.len() as u32, out)?;
     Ok(res)
 }
+
+fn check_shapes(predictions: &NdArray<f32>, targets: &NdArray<f32>) -> DuResult<()> {
+    if predictions.shape() != targets.shape() {
+        return Err(DuError::MismatchedShapes(
+            predictions.shape().clone(),
+            targets.shape().clone(),
+        ));
+    }
+    Ok(())
+}
+
+/// Number of samples and of outputs per sample, the rows and the length of the last axis
+fn samples_outputs(predictions: &NdArray<f32>) -> (f32, f32) {
+    let outputs = predictions.shape().last().max(1) as usize;
+    let samples = (predictions.shape().span() / outputs).max(1);
+    (samples as f32, outputs as f32)
+}
+
+/// Clamp a probability to prevent division by 0 and taking the log of 0
+fn clamp_probability(x: f32) -> f32 {
+    x.clamp(1e-7, 1.0 - 1e-7)
+}
+
+/// Mean of `f(prediction, target)` over each row
+fn mean_rows(
+    predictions: &NdArray<f32>,
+    targets: &NdArray<f32>,
+    f: impl Fn(f32, f32) -> f32,
+) -> DuResult<NdArray<f32>> {
+    check_shapes(predictions, targets)?;
+    let out: Data<f32> = predictions
+        .iter_rows()
+        .zip(targets.iter_rows())
+        .map(|(x, y)| {
+            let sum: f32 = x.iter().zip(y.iter()).map(|(x, y)| f(*x, *y)).sum();
+            sum / x.len().max(1) as f32
+        })
+        .collect();
+    let res = NdArray::new_with_values(out.len() as u32, out)?;
+    Ok(res)
+}
+
+/// Mean of the binary cross entropy of the outputs of each sample
+///
+/// Expect the predictions to be in the interval [0, 1], e.g. the output of `sigmoid`
+///
+/// ```
+/// use facet_core::loss::binary_cross_entropy;
+/// use facet_core::ndarray::NdArray;
+///
+/// let predictions = NdArray::new_with_values([1, 2], vec![0.5, 0.5].into()).unwrap();
+/// let targets = NdArray::new_with_values([1, 2], vec![1.0, 0.0].into()).unwrap();
+///
+/// let loss = binary_cross_entropy(&predictions, &targets).unwrap();
+/// assert!((loss.as_slice()[0] - 2.0f32.ln()).abs() < 1e-6);
+/// ```
+pub fn binary_cross_entropy(
+    predictions: &NdArray<f32>,
+    targets: &NdArray<f32>,
+) -> DuResult<NdArray<f32>> {
+    mean_rows(predictions, targets, |x, y| {
+        let x = clamp_probability(x);
+        -(y * x.ln() + (1.0 - y) * (1.0 - x).ln())
+    })
+}
+
+/// Mean of the squared errors of the outputs of each sample
+pub fn mean_squared_error(
+    predictions: &NdArray<f32>,
+    targets: &NdArray<f32>,
+) -> DuResult<NdArray<f32>> {
+    mean_rows(predictions, targets, |x, y| (y - x) * (y - x))
+}
+
+/// Common interface of the loss functions
+pub trait Loss {
+    /// Loss of each sample
+    fn loss(&self, predictions: &NdArray<f32>, targets: &NdArray<f32>) -> DuResult<NdArray<f32>>;
+    /// Gradient of the mean loss with respect to the predictions
+    fn backward(
+        &self,
+        predictions: &NdArray<f32>,
+        targets: &NdArray<f32>,
+    ) -> DuResult<NdArray<f32>>;
+}
+
+/// See [categorical_cross_entropy]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CategoricalCrossEntropy;
+
+impl Loss for CategoricalCrossEntropy {
+    fn loss(&self, predictions: &NdArray<f32>, targets: &NdArray<f32>) -> DuResult<NdArray<f32>> {
+        categorical_cross_entropy(predictions, targets)
+    }
+
+    fn backward(
+        &self,
+        predictions: &NdArray<f32>,
+        targets: &NdArray<f32>,
+    ) -> DuResult<NdArray<f32>> {
+        check_shapes(predictions, targets)?;
+        let (samples, _) = samples_outputs(predictions);
+        // clamp like the forward pass to prevent division by 0
+        let res = targets.zip_with(predictions, |y, x| -y / clamp_probability(x) / samples)?;
+        Ok(res)
+    }
+}
+
+/// See [binary_cross_entropy]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BinaryCrossEntropy;
+
+impl Loss for BinaryCrossEntropy {
+    fn loss(&self, predictions: &NdArray<f32>, targets: &NdArray<f32>) -> DuResult<NdArray<f32>> {
+        binary_cross_entropy(predictions, targets)
+    }
+
+    fn backward(
+        &self,
+        predictions: &NdArray<f32>,
+        targets: &NdArray<f32>,
+    ) -> DuResult<NdArray<f32>> {
+        check_shapes(predictions, targets)?;
+        let (samples, outputs) = samples_outputs(predictions);
+        let res = targets.zip_with(predictions, |y, x| {
+            let x = clamp_probability(x);
+            -(y / x - (1.0 - y) / (1.0 - x)) / outputs / samples
+        })?;
+        Ok(res)
+    }
+}
+
+/// See [mean_squared_error]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MeanSquaredError;
+
+impl Loss for MeanSquaredError {
+    fn loss(&self, predictions: &NdArray<f32>, targets: &NdArray<f32>) -> DuResult<NdArray<f32>> {
+        mean_squared_error(predictions, targets)
+    }
+
+    fn backward(
+        &self,
+        predictions: &NdArray<f32>,
+        targets: &NdArray<f32>,
+    ) -> DuResult<NdArray<f32>> {
+        check_shapes(predictions, targets)?;
+        let (samples, outputs) = samples_outputs(predictions);
+        let res = targets.zip_with(predictions, |y, x| -2.0 * (y - x) / outputs / samples)?;
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mse() {
+        let predictions =
+            NdArray::new_with_values([2, 2], vec![1.0, 2.0, 0.0, 0.0].into()).unwrap();
+        let targets = NdArray::new_with_values([2, 2], vec![1.0, 0.0, 1.0, 1.0].into()).unwrap();
+
+        let loss = MeanSquaredError.loss(&predictions, &targets).unwrap();
+        assert_eq!(loss.as_slice(), &[2.0, 1.0]);
+
+        let dloss = MeanSquaredError.backward(&predictions, &targets).unwrap();
+        assert_eq!(dloss.as_slice(), &[0.0, 1.0, -0.5, -0.5]);
+
+        assert!(MeanSquaredError.loss(&predictions, &loss).is_err());
+    }
+}
@@ -1,9 +1,16 @@
 pub mod column_iter;
 pub mod matrix;
 pub mod shape;
+pub mod view;
 
 mod arithmetic;
+mod buffer;
+mod bytes;
+mod indexing;
 mod scalar;
+pub use arithmetic::{Saturating, Wrapping};
+use buffer::Buffer;
+pub use bytes::LeBytes;
 use column_iter::{ColumnIter, ColumnIterMut};
 pub use scalar::*;
 use smallvec::SmallVec;
@@ -29,12 +36,20 @@ pub enum NdArrayError {
     ShapeMismatch { expected: Shape, actual: Shape },
     #[error("Binary operation between the given shapes is not supported. Shape A: {shape_a:?} Shape B: {shape_b:?}")]
     BinaryOpNotSupported { shape_a: Shape, shape_b: Shape },
+    #[error("Shapes {shape_a:?} and {shape_b:?} can not be broadcast together, trailing dimensions must be equal or 1")]
+    BroadcastMismatch { shape_a: Shape, shape_b: Shape },
+    #[error("Can not multiply matrices of shapes {shape_a:?} and {shape_b:?}, the contracted dimensions differ")]
+    MatmulShapeMismatch { shape_a: Shape, shape_b: Shape },
     #[error("Failed to convert value type into another. {0}")]
     ConversionError(String),
     #[error("Shape {0:?} is not supported for this operation")]
     UnsupportedShape(Shape),
     #[error("Invalid input given. {0}")]
     BadInput(String),
+    #[error("Index {index} is out of bounds for axis {axis} with size {size}")]
+    IndexOutOfBounds { index: i64, axis: usize, size: u32 },
+    #[error("Shape {0:?} spans more elements than can be addressed")]
+    ShapeOverflow(Shape),
 }
 
 pub type Data<T> = SmallVec<[T; 16]>;
@@ -45,7 +60,7 @@ pub type Stride = SmallVec<[usize; 4]>;
 pub struct NdArray<T> {
     shape: Shape,
     stride: Stride,
-    values: Data<T>,
+    values: Buffer<T>,
 }
 
 impl<T> Default for NdArray<T>
@@ -60,7 +75,7 @@ where
         Self {
             shape,
             stride,
-            values,
+            values: values.into(),
         }
     }
 }
@@ -87,7 +102,24 @@ where
     /// Ordinary inner product of vectors for 1-D arrays (without complex conjugation),
     /// in higher dimensions a sum product over the last axes.
     ///
-    /// Returns None if the shapes are invalid
+    /// Returns `None` if the shapes are incompatible, so a mismatch can be told apart from a
+    /// product that is legitimately zero. Compatible shapes are:
+    ///
+    /// - a scalar and any array
+    /// - vectors of equal length
+    /// - a vector and a matrix or tensor whose rows have the length of the vector
+    /// - matrices and tensors with equal row lengths and equal element counts
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let a = NdArray::new_vector(vec![1, 0]);
+    /// let b = NdArray::new_vector(vec![0, 1]);
+    /// let c = NdArray::new_vector(vec![1, 2, 3]);
+    ///
+    /// assert_eq!(a.inner(&b), Some(0));
+    /// assert_eq!(a.inner(&c), None);
+    /// ```
     pub fn inner(&'a self, other: &'a Self) -> Option<T> {
         match (&self.shape, &other.shape) {
             // scalar * scalar
@@ -120,53 +152,32 @@ where
             }
 
             // ordinary inner-product
-            (Shape::Vector(_), Shape::Vector(_)) => {
-                let val = self
-                    .values
-                    .iter()
-                    .zip(other.values.iter())
-                    .map(|(a, b)| *a * *b)
-                    .fold(Default::default(), |a: T, b| a + b);
-                return Some(val);
-            }
-
-            // sum over the rows, vector products
-            (Shape::Matrix([c, _]), Shape::Vector([n]))
-            | (Shape::Vector([n]), Shape::Matrix([c, _])) => {
-                if *c != *n {
+            (Shape::Vector([n]), Shape::Vector([m])) => {
+                if n != m {
                     return None;
                 }
             }
 
-            (Shape::Tensor(shape), Shape::Vector([n]))
-            | (Shape::Vector([n]), Shape::Tensor(shape)) => {
-                if *shape.last()? != *n {
+            // sum over the rows, vector products
+            (Shape::Matrix(_), Shape::Vector([n])) | (Shape::Tensor(_), Shape::Vector([n])) => {
+                if self.shape.last() != *n {
                     return None;
                 }
+                return Some(Self::sum_row_products(self, other));
             }
-
-            // Frobenius inner product
-            //
-            (Shape::Matrix([c1, r1]), Shape::Matrix([c2, r2])) => {
-                if c1 != c2 || r1 != r2 {
+            (Shape::Vector([n]), Shape::Matrix(_)) | (Shape::Vector([n]), Shape::Tensor(_)) => {
+                if other.shape.last() != *n {
                     return None;
                 }
+                return Some(Self::sum_row_products(other, self));
             }
 
-            // Frobenius inner product for nd arrays
-            //
-            (Shape::Matrix([c, r]), Shape::Tensor(s))
-            | (Shape::Tensor(s), Shape::Matrix([c, r])) => {
-                if s.last()? != r {
-                    return None;
-                }
-                let num_rows: u32 = s[..s.len() - 1].iter().product();
-                if num_rows != *c {
-                    return None;
-                }
-            }
-            (Shape::Tensor(sa), Shape::Tensor(sb)) => {
-                if sa.last()? != sb.last()? {
+            // Frobenius inner product, also for nd arrays
+            (Shape::Matrix(_), Shape::Matrix(_))
+            | (Shape::Matrix(_), Shape::Tensor(_))
+            | (Shape::Tensor(_), Shape::Matrix(_))
+            | (Shape::Tensor(_), Shape::Tensor(_)) => {
+                if self.shape.last() != other.shape.last() {
                     // row size mismatch
                     return None;
                 }
@@ -177,19 +188,49 @@ where
             }
         }
 
-        let res = other
-            .iter_rows()
-            .map(|col| {
-                // dot product result
-                self.values
-                    .iter()
-                    .zip(col.iter())
+        let res = self
+            .values
+            .iter()
+            .zip(other.values.iter())
+            .map(|(a, b)| *a * *b)
+            .fold(T::default(), |a, b| a + b);
+        Some(res)
+    }
+
+    /// Outer product of the flattened arrays, an `n` by `m` matrix where `n` and `m` are the
+    /// number of elements in `self` and `other`.
+    ///
+    /// ```
+    /// use facet_core::ndarray::{NdArray, shape::Shape};
+    ///
+    /// let a = NdArray::new_vector(vec![1, 2]);
+    /// let b = NdArray::new_vector(vec![1, 0, -1]);
+    ///
+    /// let c = a.outer(&b);
+    ///
+    /// assert_eq!(c.shape(), &Shape::Matrix([2, 3]));
+    /// assert_eq!(c.as_slice(), &[1, 0, -1, 2, 0, -2]);
+    /// ```
+    pub fn outer(&self, other: &Self) -> Self {
+        let values = self
+            .values
+            .iter()
+            .flat_map(|a| other.values.iter().map(move |b| *a * *b))
+            .collect();
+        let shape = Shape::Matrix([self.values.len() as u32, other.values.len() as u32]);
+        Self::new_with_values(shape, values).unwrap()
+    }
+
+    /// Sum of the dot products of each row of `arr` with `vector`
+    fn sum_row_products(arr: &Self, vector: &Self) -> T {
+        arr.iter_rows()
+            .map(|row| {
+                row.iter()
+                    .zip(vector.values.iter())
                     .map(|(a, b)| *a * *b)
                     .fold(T::default(), |a, b| a + b)
             })
-            // sum over the dot product result vector
-            .fold(T::default(), |a, b| a + b);
-        Some(res)
+            .fold(T::default(), |a, b| a + b)
     }
 }
 
@@ -211,15 +252,17 @@ impl<T> NdArray<T> {
 
     /// In the case of Tensors transpose the inner matrices
     /// e.g. Shape `[3, 4, 5]` becomes `[3, 5, 4]`
+    ///
+    /// Scalars and vectors are returned unchanged, like in numpy. Reshape a vector into a `[n, 1]`
+    /// matrix to get a column vector.
     pub fn transpose(self) -> Self
     where
         T: Send + Sync + Copy,
     {
         match &self.shape {
-            Shape::Scalar(_) => self,
-            Shape::Vector([n]) => Self::new_with_values([*n, 1], self.values).unwrap(),
+            Shape::Scalar(_) | Shape::Vector(_) => self,
             Shape::Matrix([m, n]) => {
-                let mut values = self.values.clone();
+                let mut values = Data::from_slice(&self.values);
                 matrix::transpose_mat([*m as usize, *n as usize], &self.values, &mut values);
                 Self::new_with_values(Shape::Matrix([*n, *m]), values).unwrap()
             }
@@ -257,7 +300,7 @@ impl<T> NdArray<T> {
         let shape = Shape::Scalar(Default::default());
         let stride = shape::stride_vec(1, shape.as_slice());
         Self {
-            values: [value][..].into(),
+            values: Data::from(&[value][..]).into(),
             stride,
             shape,
         }
@@ -268,7 +311,9 @@ impl<T> NdArray<T> {
     ) -> Result<Self, NdArrayError> {
         let shape = shape.into();
 
-        let len: usize = shape.span();
+        let len: usize = shape
+            .checked_span()
+            .ok_or_else(|| NdArrayError::ShapeOverflow(shape.clone()))?;
         if len != 0 && values.len() != len {
             return Err(NdArrayError::DimensionMismatch {
                 expected: len,
@@ -279,7 +324,7 @@ impl<T> NdArray<T> {
         let res = Self {
             stride: shape::stride_vec(1, shape.as_slice()),
             shape,
-            values,
+            values: values.into(),
         };
         Ok(res)
     }
@@ -290,14 +335,36 @@ impl<T> NdArray<T> {
         Self::new_with_values(values.len() as u32, values).unwrap()
     }
 
+    /// Panics if the span of `shape` overflows, see [try_new_default](NdArray::try_new_default)
     pub fn new_default<S: Into<Shape>>(shape: S) -> Self
+    where
+        T: Default,
+    {
+        Self::try_new_default(shape).unwrap()
+    }
+
+    /// Array of `shape` filled with Default'ed values
+    ///
+    /// ```
+    /// use facet_core::ndarray::{NdArray, NdArrayError};
+    ///
+    /// let a = NdArray::<f32>::try_new_default([2, 3]).unwrap();
+    /// assert_eq!(a.as_slice(), &[0.0; 6]);
+    ///
+    /// let res = NdArray::<f32>::try_new_default(&[u32::MAX; 4][..]);
+    /// assert!(matches!(res, Err(NdArrayError::ShapeOverflow(_))));
+    /// ```
+    pub fn try_new_default<S: Into<Shape>>(shape: S) -> Result<Self, NdArrayError>
     where
         T: Default,
     {
         let shape: Shape = shape.into();
-        let len: usize = shape.span().max(1);
+        let len: usize = shape
+            .checked_span()
+            .ok_or_else(|| NdArrayError::ShapeOverflow(shape.clone()))?
+            .max(1);
         let values = (0..len).map(|_| Default::default()).collect::<Data<T>>();
-        Self::new_with_values(shape, values).unwrap()
+        Self::new_with_values(shape, values)
     }
 
     /// Returns a Diagonal matrix where the values are the given default value and the rest of the
@@ -314,6 +381,64 @@ impl<T> NdArray<T> {
         res
     }
 
+    /// Return a copy with the elements above the `k`-th diagonal of the inner matrices set to
+    /// Default. Like numpy's `tril`.
+    ///
+    /// `k = 0` is the main diagonal, `k > 0` is above it, `k < 0` is below it.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let a = NdArray::new_with_values([3, 3], vec![1; 9].into()).unwrap();
+    ///
+    /// let b = a.tril(0).unwrap();
+    ///
+    /// assert_eq!(b.as_slice(), &[1, 0, 0, 1, 1, 0, 1, 1, 1]);
+    /// ```
+    pub fn tril(&self, k: i64) -> Result<Self, NdArrayError>
+    where
+        T: Default + Clone,
+    {
+        self.keep_triangle(|i, j| j - i <= k)
+    }
+
+    /// Return a copy with the elements below the `k`-th diagonal of the inner matrices set to
+    /// Default. Like numpy's `triu`.
+    ///
+    /// `k = 0` is the main diagonal, `k > 0` is above it, `k < 0` is below it.
+    pub fn triu(&self, k: i64) -> Result<Self, NdArrayError>
+    where
+        T: Default + Clone,
+    {
+        self.keep_triangle(|i, j| j - i >= k)
+    }
+
+    /// Keep the elements at `[i, j]` of the inner matrices where `keep(i, j)` holds
+    fn keep_triangle(&self, keep: impl Fn(i64, i64) -> bool) -> Result<Self, NdArrayError>
+    where
+        T: Default + Clone,
+    {
+        let [n, m] = self.shape.last_two().ok_or_else(|| {
+            NdArrayError::BadInput(format!(
+                "Expected a matrix or tensor, got shape {:?}",
+                self.shape
+            ))
+        })?;
+        let [n, m] = [n as usize, m as usize];
+        let mut res = self.clone();
+        if n * m == 0 {
+            return Ok(res);
+        }
+        for mat in res.as_mut_slice().chunks_exact_mut(n * m) {
+            for (idx, x) in mat.iter_mut().enumerate() {
+                if !keep((idx / m) as i64, (idx % m) as i64) {
+                    *x = T::default();
+                }
+            }
+        }
+        Ok(res)
+    }
+
     /// If invalid returns false and leaves this instance unchanged
     pub fn set_slice(&mut self, values: Data<T>) -> Result<&mut Self, NdArrayError> {
         if values.len() != self.values.len() {
@@ -323,7 +448,7 @@ impl<T> NdArray<T> {
             });
         }
 
-        self.values = values;
+        self.values = values.into();
 
         Ok(self)
     }
@@ -336,14 +461,24 @@ impl<T> NdArray<T> {
         &self.stride
     }
 
+    /// Whether the values are laid out densely in row-major order, so the value slice can be
+    /// handed to the raw kernels, e.g. [matmul_impl](matrix::matmul_impl), as is.
+    ///
+    /// Arrays own their values, so this only fails if the stride went out of sync with the shape.
+    pub fn is_contiguous(&self) -> bool {
+        self.values.len() == self.shape.span()
+            && self.stride == shape::stride_vec(1, self.shape.as_slice())
+    }
+
     /// Smaller shape span will result in the last items being 'cut'
     pub fn reshape(&mut self, new_shape: impl Into<Shape>) -> &mut Self
     where
-        T: Default,
+        T: Default + Clone,
     {
         let new_shape = new_shape.into();
         let new_len = new_shape.span();
         self.values.resize_with(new_len, Default::default);
+        self.stride = shape::stride_vec(1, new_shape.as_slice());
         self.shape = new_shape;
         self
     }
@@ -352,7 +487,11 @@ impl<T> NdArray<T> {
         &self.values
     }
 
-    pub fn as_mut_slice(&mut self) -> &mut [T] {
+    /// Copies the values first if they are shared with a clone of this array
+    pub fn as_mut_slice(&mut self) -> &mut [T]
+    where
+        T: Clone,
+    {
         &mut self.values
     }
 
@@ -373,7 +512,10 @@ impl<T> NdArray<T> {
     }
 
     /// Returns `None` on invalid index
-    pub fn get_mut(&mut self, index: &[u32]) -> Option<&mut T> {
+    pub fn get_mut(&mut self, index: &[u32]) -> Option<&mut T>
+    where
+        T: Clone,
+    {
         match &self.shape {
             Shape::Scalar(_) => self.values.get_mut(0),
             Shape::Vector(_) => self.values.get_mut(*index.get(0)? as usize),
@@ -432,7 +574,10 @@ impl<T> NdArray<T> {
         }
     }
 
-    pub fn get_row_mut(&mut self, index: &[u32]) -> Option<&mut [T]> {
+    pub fn get_row_mut(&mut self, index: &[u32]) -> Option<&mut [T]>
+    where
+        T: Clone,
+    {
         match &self.shape {
             Shape::Scalar(_) | Shape::Vector(_) => Some(&mut self.values),
             Shape::Matrix([n, m]) => {
@@ -464,7 +609,10 @@ impl<T> NdArray<T> {
         ColumnIter::new(&self.values, self.shape.last().max(1) as usize)
     }
 
-    pub fn iter_rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+    pub fn iter_rows_mut(&mut self) -> impl Iterator<Item = &mut [T]>
+    where
+        T: Clone,
+    {
         ColumnIterMut::new(&mut self.values, self.shape.last().max(1) as usize)
     }
 
@@ -482,7 +630,7 @@ impl<T> NdArray<T> {
         &mut self,
     ) -> impl rayon::prelude::ParallelIterator<Item = &mut [T]> + '_
     where
-        T: Sync + Send,
+        T: Sync + Send + Clone,
     {
         let rows = self.shape.last().max(1) as usize;
         self.values.as_mut_slice().par_chunks_mut(rows)
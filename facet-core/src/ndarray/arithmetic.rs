@@ -2,25 +2,30 @@
 //!
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
 
-use super::Data;
-use super::{column_iter::ColumnIterMut, shape::Shape, NdArray, NdArrayError};
+use super::{column_iter::ColumnIterMut, shape, shape::Shape, NdArray, NdArrayError};
+use super::{Data, Stride};
 #[cfg(feature = "rayon")]
 use rayon::prelude::*;
 
+/// Apply the binary function `$f` element-wise. Scalars, vectors and matrices combined with larger
+/// arrays take fast paths, everything else goes through [NdArray::broadcast_with].
 macro_rules! arithimpl {
-    ($opeq: tt, $op: tt, $lhs: ident, $rhs: ident) =>{
+    ($f: expr, $lhs: ident, $rhs: ident) => {
         match (&$lhs.shape, &$rhs.shape) {
             (Shape::Scalar(_), Shape::Scalar(_)) => Ok(NdArray::<T>::new_with_values(
-                    0,
-                    (0..1).map(|_| $lhs.values[0] $op $rhs.values[0]).collect::<Data<T>>(),
-            ).unwrap()),
+                0,
+                (0..1)
+                    .map(|_| $f($lhs.values[0], $rhs.values[0]))
+                    .collect::<Data<T>>(),
+            )
+            .unwrap()),
             // add the scalar to all elements
             (Shape::Scalar(_), Shape::Vector(_))
             | (Shape::Scalar(_), Shape::Matrix([_, _]))
             | (Shape::Scalar(_), Shape::Tensor(_)) => {
                 let mut res = $rhs.clone();
                 let val = $lhs.values[0];
-                res.values.iter_mut().for_each(|x| *x = val $op *x);
+                res.values.iter_mut().for_each(|x| *x = $f(val, *x));
                 Ok(res)
             }
             (Shape::Vector(_), Shape::Scalar(_))
@@ -28,7 +33,7 @@ macro_rules! arithimpl {
             | (Shape::Tensor(_), Shape::Scalar(_)) => {
                 let mut res = $lhs.clone();
                 let val = $rhs.values[0];
-                res.values.iter_mut().for_each(|x| *x $opeq val);
+                res.values.iter_mut().for_each(|x| *x = $f(*x, val));
                 Ok(res)
             }
 
@@ -37,88 +42,84 @@ macro_rules! arithimpl {
             | (a @ Shape::Tensor(_), b @ Shape::Tensor(_))
             | (a @ Shape::Vector(_), b @ Shape::Vector(_)) => {
                 if a != b {
-                    return Err(NdArrayError::ShapeMismatch {
-                        expected: a.clone(),
-                        actual: b.clone(),
-                    });
+                    return $lhs.broadcast_with($rhs, $f);
                 }
                 let values;
                 #[cfg(feature = "rayon")]
                 {
-                    values= $lhs
-                    .values
-                    .as_slice()
-                    .par_iter()
-                    .zip($rhs.values.par_iter())
-                    .map(|(a, b)| *a $op *b)
-                    .collect::<Vec<_>>().into();
+                    values = crate::thread_pool::install(|| {
+                        $lhs.values
+                            .as_slice()
+                            .par_iter()
+                            .zip($rhs.values.par_iter())
+                            .map(|(a, b)| $f(*a, *b))
+                            .collect::<Vec<_>>()
+                    })
+                    .into();
                 }
                 #[cfg(not(feature = "rayon"))]
                 {
-                    values= $lhs
-                    .values
-                    .iter()
-                    .zip($rhs.values.iter())
-                    .map(|(a, b)| *a $op *b)
-                    .collect();
-                }
-                let res = NdArray::<T>::new_with_values(
-                    $lhs.shape.clone(),
-                    values,
-                ).unwrap();
+                    values = $lhs
+                        .values
+                        .iter()
+                        .zip($rhs.values.iter())
+                        .map(|(a, b)| $f(*a, *b))
+                        .collect();
+                }
+                let res = NdArray::<T>::new_with_values($lhs.shape.clone(), values).unwrap();
                 Ok(res)
             }
 
             // add vector to each column
-            (Shape::Vector([l]), Shape::Matrix([_, _])) | (Shape::Vector([l]), Shape::Tensor(_)) => {
+            (Shape::Vector([l]), Shape::Matrix([_, _]))
+            | (Shape::Vector([l]), Shape::Tensor(_)) => {
                 let l = *l;
                 if $rhs.shape.last() != l {
-                    return Err(NdArrayError::DimensionMismatch {
-                        expected: l as usize,
-                        actual: $rhs.shape.last() as usize,
-                    });
+                    return $lhs.broadcast_with($rhs, $f);
                 }
                 let mut res = $rhs.clone();
-                #[cfg(feature="rayon")]
+                #[cfg(feature = "rayon")]
                 {
-                    res.par_iter_rows_mut().for_each(|col|{
-                        for (a, b) in col.iter_mut().zip($lhs.values.iter()) {
-                            *a $opeq *b;
-                        }
+                    crate::thread_pool::install(|| {
+                        res.par_iter_rows_mut().for_each(|col| {
+                            for (a, b) in col.iter_mut().zip($lhs.values.iter()) {
+                                *a = $f(*b, *a);
+                            }
+                        });
                     });
                 }
-                #[cfg(not(feature="rayon"))]
+                #[cfg(not(feature = "rayon"))]
                 {
                     for col in res.iter_rows_mut() {
                         for (a, b) in col.iter_mut().zip($lhs.values.iter()) {
-                            *a $opeq *b;
+                            *a = $f(*b, *a);
                         }
                     }
                 }
                 Ok(res)
             }
-            (Shape::Matrix([_, _]), Shape::Vector([l])) | (Shape::Tensor(_), Shape::Vector([l])) => {
+            (Shape::Matrix([_, _]), Shape::Vector([l]))
+            | (Shape::Tensor(_), Shape::Vector([l])) => {
                 let l = *l;
                 if $lhs.shape.last() != l {
-                    return Err(NdArrayError::DimensionMismatch {
-                        expected: l as usize,
-                        actual: $lhs.shape.last() as usize,
-                    });
+                    return $lhs.broadcast_with($rhs, $f);
                 }
                 let mut res = $lhs.clone();
-                #[cfg(feautre="rayon")]
+                #[cfg(feautre = "rayon")]
                 {
-                    res.par_iter_rows_mut().for_each(|col|{
-                        for (a, b) in col.iter_mut().zip($rhs.values.iter()) {
-                            *a $opeq *b;
-                        }
+                    crate::thread_pool::install(|| {
+                        res.par_iter_rows_mut().for_each(|col| {
+                            for (a, b) in col.iter_mut().zip($rhs.values.iter()) {
+                                *a = $f(*a, *b);
+                            }
+                        });
                     });
                 }
-                #[cfg(not(feautre="rayon"))]
+                #[cfg(not(feautre = "rayon"))]
                 {
                     for col in res.iter_rows_mut() {
                         for (a, b) in col.iter_mut().zip($rhs.values.iter()) {
-                            *a $opeq *b;
+                            *a = $f(*a, *b);
                         }
                     }
                 }
@@ -130,10 +131,7 @@ macro_rules! arithimpl {
                 let [n, m] = [*n, *m];
                 let [k, l] = shp.last_two().unwrap();
                 if n != k || m != l {
-                    return Err(NdArrayError::ShapeMismatch {
-                        expected: [n,m].into(),
-                        actual: shp.clone(),
-                    });
+                    return $lhs.broadcast_with($rhs, $f);
                 }
 
                 let mut res = $rhs.clone();
@@ -142,7 +140,7 @@ macro_rules! arithimpl {
                         .iter_mut()
                         .zip($lhs.values.iter())
                         .for_each(|(a, b)| {
-                            *a $opeq *b;
+                            *a = $f(*b, *a);
                         })
                 }
                 Ok(res)
@@ -151,22 +149,22 @@ macro_rules! arithimpl {
                 let [k, l] = shp.last_two().unwrap();
                 let [n, m] = [*n, *m];
                 if n != k || m != l {
-                    return Err(NdArrayError::ShapeMismatch {
-                        actual: [n,m].into(),
-                        expected: shp.clone(),
-                    });
+                    return $lhs.broadcast_with($rhs, $f);
                 }
 
                 let mut res = $lhs.clone();
                 for submat in ColumnIterMut::new(&mut res.values, k as usize * l as usize) {
-                    submat.iter_mut().zip($rhs.values.iter()).for_each(|(a, b)| {
-                        *a $opeq *b;
-                    })
+                    submat
+                        .iter_mut()
+                        .zip($rhs.values.iter())
+                        .for_each(|(a, b)| {
+                            *a = $f(*a, *b);
+                        })
                 }
                 Ok(res)
             }
         }
-    }
+    };
 }
 
 impl<'a, T> NdArray<T>
@@ -174,7 +172,7 @@ where
     T: Add<T, Output = T> + AddAssign + Copy + 'a + Send + Sync,
 {
     pub fn add(&self, rhs: &Self) -> Result<Self, NdArrayError> {
-        arithimpl!(+=, +, self, rhs)
+        arithimpl!(|a: T, b: T| a + b, self, rhs)
     }
 }
 
@@ -183,7 +181,7 @@ where
     T: Sub<T, Output = T> + SubAssign + Copy + 'a + Send + Sync,
 {
     pub fn sub(&self, rhs: &Self) -> Result<Self, NdArrayError> {
-        arithimpl!(-=, -, self, rhs)
+        arithimpl!(|a: T, b: T| a - b, self, rhs)
     }
 }
 
@@ -192,7 +190,7 @@ where
     T: Mul<T, Output = T> + MulAssign + Copy + 'a + Send + Sync,
 {
     pub fn mul(&self, rhs: &Self) -> Result<Self, NdArrayError> {
-        arithimpl!(*=, *, self, rhs)
+        arithimpl!(|a: T, b: T| a * b, self, rhs)
     }
 }
 
@@ -201,7 +199,252 @@ where
     T: Div<T, Output = T> + DivAssign + Copy + 'a + Send + Sync,
 {
     pub fn div(&self, rhs: &Self) -> Result<Self, NdArrayError> {
-        arithimpl!(/=, /, self, rhs)
+        arithimpl!(|a: T, b: T| a / b, self, rhs)
+    }
+}
+
+impl<T> NdArray<T>
+where
+    T: Copy,
+{
+    /// Apply `f` element-wise to `self` and `rhs` broadcast against each other, like numpy. See
+    /// [broadcast_shape](shape::broadcast_shape) for the rules.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let col = NdArray::new_with_values([2, 1], vec![10, 20].into()).unwrap();
+    /// let row = NdArray::new_vector(vec![1, 2, 3]);
+    ///
+    /// let res = col.broadcast_with(&row, |a, b| a + b).unwrap();
+    ///
+    /// assert_eq!(res.shape().as_slice(), &[2, 3]);
+    /// assert_eq!(res.as_slice(), &[11, 12, 13, 21, 22, 23]);
+    /// ```
+    pub fn broadcast_with<R: Copy, U>(
+        &self,
+        rhs: &NdArray<R>,
+        f: impl Fn(T, R) -> U,
+    ) -> Result<NdArray<U>, NdArrayError> {
+        let shape = shape::broadcast_shape(&self.shape, &rhs.shape).ok_or_else(|| {
+            NdArrayError::BroadcastMismatch {
+                shape_a: self.shape.clone(),
+                shape_b: rhs.shape.clone(),
+            }
+        })?;
+        let dims = shape.as_slice();
+        let ndims = dims.len();
+        // stride of each output axis in the operand, 0 along the broadcast axes
+        let strides = |operand: &Shape| -> Stride {
+            let own = operand.as_slice();
+            let offset = ndims - own.len();
+            let stride = shape::stride_vec(1, own);
+            (0..ndims)
+                .map(|i| match i.checked_sub(offset) {
+                    Some(j) if own[j] != 1 => stride[j],
+                    _ => 0,
+                })
+                .collect()
+        };
+        let (sa, sb) = (strides(&self.shape), strides(&rhs.shape));
+
+        let span = shape.span();
+        let mut values = Data::with_capacity(span);
+        let mut index: Stride = smallvec::smallvec![0; ndims];
+        let (mut a, mut b) = (0, 0);
+        for _ in 0..span {
+            values.push(f(self.values[a], rhs.values[b]));
+            for d in (0..ndims).rev() {
+                index[d] += 1;
+                a += sa[d];
+                b += sb[d];
+                if index[d] < dims[d] as usize {
+                    break;
+                }
+                a -= sa[d] * index[d];
+                b -= sb[d] * index[d];
+                index[d] = 0;
+            }
+        }
+        NdArray::new_with_values(shape, values)
+    }
+}
+
+impl<T> NdArray<T>
+where
+    T: Copy + Send + Sync,
+{
+    /// Apply the binary function `f` element-wise, broadcasting the same way as
+    /// [add](NdArray::add).
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let a = NdArray::new_vector(vec![-1i64, 5]);
+    /// let b = NdArray::new_scalar(3);
+    ///
+    /// let c = a.zip_with(&b, |a, b| a.rem_euclid(b)).unwrap();
+    ///
+    /// assert_eq!(c.as_slice(), &[2, 2]);
+    /// ```
+    pub fn zip_with<F>(&self, rhs: &Self, f: F) -> Result<Self, NdArrayError>
+    where
+        F: Fn(T, T) -> T + Send + Sync,
+    {
+        arithimpl!(f, self, rhs)
+    }
+}
+
+impl<T> NdArray<T>
+where
+    T: Sub<T, Output = T> + Copy,
+{
+    /// Calculate the `n`-th discrete difference along `axis`. Like numpy's `diff`.
+    ///
+    /// The size of `axis` shrinks by `n` in the output.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let a = NdArray::new_vector(vec![1, 2, 4, 7]);
+    ///
+    /// assert_eq!(a.diff(0, 1).unwrap().as_slice(), &[1, 2, 3]);
+    /// assert_eq!(a.diff(0, 2).unwrap().as_slice(), &[1, 1]);
+    /// ```
+    pub fn diff(&self, axis: u32, n: u32) -> Result<Self, NdArrayError> {
+        let axis = self.check_axis(axis)?;
+        let mut res = self.clone();
+        for _ in 0..n {
+            let [outer, size, inner] = res.split_at_axis(axis);
+            if size == 0 {
+                break;
+            }
+            let mut values = Data::with_capacity(outer * (size - 1) * inner);
+            for o in 0..outer {
+                let lane = &res.values[o * size * inner..(o + 1) * size * inner];
+                for (a, b) in lane.iter().zip(lane[inner..].iter()) {
+                    values.push(*b - *a);
+                }
+            }
+            let mut shape = res.shape.clone();
+            shape[axis] -= 1;
+            res = Self::new_with_values(shape, values)?;
+        }
+        Ok(res)
+    }
+}
+
+/// Arithmetic that clamps the result to the bounds of the type instead of overflowing
+pub trait Saturating: Sized {
+    fn saturating_add(self, rhs: Self) -> Self;
+    fn saturating_sub(self, rhs: Self) -> Self;
+    fn saturating_mul(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_saturating {
+    ($($ty: ty),*) => {
+        $(
+            impl Saturating for $ty {
+                fn saturating_add(self, rhs: Self) -> Self {
+                    <$ty>::saturating_add(self, rhs)
+                }
+                fn saturating_sub(self, rhs: Self) -> Self {
+                    <$ty>::saturating_sub(self, rhs)
+                }
+                fn saturating_mul(self, rhs: Self) -> Self {
+                    <$ty>::saturating_mul(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_saturating!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// Saturating variants of the arithmetic operations.
+///
+/// The regular operations follow Rust's integer semantics: overflow wraps around in release
+/// builds and panics in debug builds. Use the [Wrapping] variants, e.g. `add_wrap`, to wrap around
+/// in every build.
+///
+/// ```
+/// use facet_core::ndarray::NdArray;
+///
+/// let a = NdArray::new_vector(vec![i64::MAX, i64::MIN]);
+/// let b = NdArray::new_vector(vec![1, 1]);
+///
+/// assert_eq!(a.add_sat(&b).unwrap().as_slice(), &[i64::MAX, i64::MIN + 1]);
+/// assert_eq!(a.sub_sat(&b).unwrap().as_slice(), &[i64::MAX - 1, i64::MIN]);
+/// ```
+impl<T> NdArray<T>
+where
+    T: Saturating + Copy + Send + Sync,
+{
+    pub fn add_sat(&self, rhs: &Self) -> Result<Self, NdArrayError> {
+        arithimpl!(T::saturating_add, self, rhs)
+    }
+
+    pub fn sub_sat(&self, rhs: &Self) -> Result<Self, NdArrayError> {
+        arithimpl!(T::saturating_sub, self, rhs)
+    }
+
+    pub fn mul_sat(&self, rhs: &Self) -> Result<Self, NdArrayError> {
+        arithimpl!(T::saturating_mul, self, rhs)
+    }
+}
+
+/// Arithmetic that wraps around at the bounds of the type instead of overflowing
+pub trait Wrapping: Sized {
+    fn wrapping_add(self, rhs: Self) -> Self;
+    fn wrapping_sub(self, rhs: Self) -> Self;
+    fn wrapping_mul(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_wrapping {
+    ($($ty: ty),*) => {
+        $(
+            impl Wrapping for $ty {
+                fn wrapping_add(self, rhs: Self) -> Self {
+                    <$ty>::wrapping_add(self, rhs)
+                }
+                fn wrapping_sub(self, rhs: Self) -> Self {
+                    <$ty>::wrapping_sub(self, rhs)
+                }
+                fn wrapping_mul(self, rhs: Self) -> Self {
+                    <$ty>::wrapping_mul(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_wrapping!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// Wrapping variants of the arithmetic operations, overflow wraps around in every build.
+///
+/// ```
+/// use facet_core::ndarray::NdArray;
+///
+/// let a = NdArray::new_vector(vec![i64::MAX, i64::MIN]);
+/// let b = NdArray::new_vector(vec![1, 1]);
+///
+/// assert_eq!(a.add_wrap(&b).unwrap().as_slice(), &[i64::MIN, i64::MIN + 1]);
+/// assert_eq!(a.sub_wrap(&b).unwrap().as_slice(), &[i64::MAX - 1, i64::MAX]);
+/// ```
+impl<T> NdArray<T>
+where
+    T: Wrapping + Copy + Send + Sync,
+{
+    pub fn add_wrap(&self, rhs: &Self) -> Result<Self, NdArrayError> {
+        arithimpl!(T::wrapping_add, self, rhs)
+    }
+
+    pub fn sub_wrap(&self, rhs: &Self) -> Result<Self, NdArrayError> {
+        arithimpl!(T::wrapping_sub, self, rhs)
+    }
+
+    pub fn mul_wrap(&self, rhs: &Self) -> Result<Self, NdArrayError> {
+        arithimpl!(T::wrapping_mul, self, rhs)
     }
 }
 
@@ -244,4 +487,15 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_saturating_add_does_not_wrap() {
+        let a = NdArray::new_vector(vec![i64::MAX, 0, i64::MIN]);
+
+        let b = a.add_sat(&NdArray::new_scalar(1)).unwrap();
+        assert_eq!(b.as_slice(), &[i64::MAX, 1, i64::MIN + 1]);
+
+        let b = a.mul_sat(&NdArray::new_scalar(-2)).unwrap();
+        assert_eq!(b.as_slice(), &[i64::MIN, 0, i64::MAX]);
+    }
 }
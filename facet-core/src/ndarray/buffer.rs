@@ -0,0 +1,47 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+use super::Data;
+
+/// Copy-on-write storage of the values of an [NdArray](super::NdArray).
+///
+/// Cloning only bumps a reference count, the values are copied when a shared buffer is first
+/// mutated.
+#[derive(Debug)]
+pub struct Buffer<T>(Arc<Data<T>>);
+
+impl<T> Clone for Buffer<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> From<Data<T>> for Buffer<T> {
+    fn from(values: Data<T>) -> Self {
+        Self(Arc::new(values))
+    }
+}
+
+impl<T> Deref for Buffer<T> {
+    type Target = Data<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Clone> DerefMut for Buffer<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        Arc::make_mut(&mut self.0)
+    }
+}
+
+impl<T: PartialEq> PartialEq for Buffer<T> {
+    fn eq(&self, other: &Self) -> bool {
+        *self.0 == *other.0
+    }
+}
+
+impl<T: Eq> Eq for Buffer<T> {}
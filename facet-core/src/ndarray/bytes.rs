@@ -0,0 +1,103 @@
+use std::convert::TryInto;
+
+use super::{shape::Shape, NdArray, NdArrayError};
+
+/// Element types with a fixed size little-endian byte representation
+pub trait LeBytes: Sized {
+    /// Number of bytes of a single element
+    const SIZE: usize;
+
+    fn write_le(&self, out: &mut Vec<u8>);
+    /// `bytes` must be exactly `SIZE` long
+    fn read_le(bytes: &[u8]) -> Self;
+    /// Reverse the order of the bytes, converting between little and big-endian
+    fn swap_bytes(self) -> Self;
+}
+
+macro_rules! impl_le_bytes {
+    ($($ty: ty),*) => {
+        $(
+            impl LeBytes for $ty {
+                const SIZE: usize = std::mem::size_of::<$ty>();
+
+                fn write_le(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+                fn read_le(bytes: &[u8]) -> Self {
+                    <$ty>::from_le_bytes(bytes.try_into().unwrap())
+                }
+                fn swap_bytes(self) -> Self {
+                    let mut bytes = self.to_ne_bytes();
+                    bytes.reverse();
+                    <$ty>::from_ne_bytes(bytes)
+                }
+            }
+        )*
+    };
+}
+
+impl_le_bytes!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64);
+
+impl<T: LeBytes> NdArray<T> {
+    /// Raw little-endian bytes of the values in row-major order, regardless of the byte order of
+    /// the host.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let a = NdArray::new_vector(vec![1i32, 256]);
+    ///
+    /// assert_eq!(a.as_bytes(), vec![1, 0, 0, 0, 0, 1, 0, 0]);
+    /// ```
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(self.values.len() * T::SIZE);
+        for x in self.values.iter() {
+            x.write_le(&mut res);
+        }
+        res
+    }
+
+    /// Inverse of [`as_bytes`](Self::as_bytes). `bytes` must hold exactly the number of values
+    /// `shape` spans.
+    pub fn from_bytes<S: Into<Shape>>(shape: S, bytes: &[u8]) -> Result<Self, NdArrayError> {
+        let shape = shape.into();
+        let len = shape
+            .checked_span()
+            .ok_or_else(|| NdArrayError::ShapeOverflow(shape.clone()))?;
+        let size = len
+            .checked_mul(T::SIZE)
+            .ok_or_else(|| NdArrayError::ShapeOverflow(shape.clone()))?;
+        if bytes.len() != size {
+            return Err(NdArrayError::BadInput(format!(
+                "Shape {:?} of {} byte values requires {} bytes, got {}",
+                shape,
+                T::SIZE,
+                size,
+                bytes.len()
+            )));
+        }
+        let values = bytes.chunks_exact(T::SIZE).map(T::read_le).collect();
+        Self::new_with_values(shape, values)
+    }
+
+    /// Reverse the byte order of each value in place, e.g. to fix up values read from the raw
+    /// bytes of a big-endian system. Like numpy's `byteswap`.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let mut a = NdArray::new_vector(vec![1i32, 0x01020304]);
+    ///
+    /// a.byteswap();
+    ///
+    /// assert_eq!(a.as_slice(), &[0x01000000, 0x04030201]);
+    /// ```
+    pub fn byteswap(&mut self)
+    where
+        T: Copy,
+    {
+        for x in self.as_mut_slice() {
+            *x = x.swap_bytes();
+        }
+    }
+}
@@ -0,0 +1,804 @@
+//! Index based selection and assembly operations
+//!
+use rand::{seq::SliceRandom, Rng};
+use smallvec::SmallVec;
+
+use super::{
+    shape::{stride_vec, Shape},
+    Data, NdArray, NdArrayError,
+};
+
+impl<T> NdArray<T> {
+    /// Iterate over the values in row-major order, together with their multi-dimensional index.
+    ///
+    /// The index of a scalar is empty.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let a = NdArray::new_with_values([2, 2], vec![1, 2, 3, 4].into()).unwrap();
+    ///
+    /// let (index, value) = a.iter_indexed().nth(2).unwrap();
+    ///
+    /// assert_eq!(index.as_slice(), &[1, 0]);
+    /// assert_eq!(*value, 3);
+    /// ```
+    pub fn iter_indexed(&self) -> impl Iterator<Item = (SmallVec<[u32; 4]>, &T)> + '_ {
+        let shape = self.shape.as_slice();
+        let mut index: SmallVec<[u32; 4]> = smallvec::smallvec![0; shape.len()];
+        self.values.iter().map(move |x| {
+            let res = (index.clone(), x);
+            increment_index(&mut index, shape);
+            res
+        })
+    }
+
+    /// Replace each lane along `axis` by the output of `f`. Like numpy's `apply_along_axis`.
+    ///
+    /// `f` may change the length of the lanes, the length of its first output becomes the size
+    /// of `axis` in the result. Every call must return that many values.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let a = NdArray::new_with_values([2, 3], vec![1, 2, 3, 4, 5, 6].into()).unwrap();
+    ///
+    /// let b = a.map_axis(0, |lane| vec![lane[0] + lane[1]]).unwrap();
+    ///
+    /// assert_eq!(b.shape().as_slice(), &[1, 3]);
+    /// assert_eq!(b.as_slice(), &[5, 7, 9]);
+    /// ```
+    pub fn map_axis<U>(
+        &self,
+        axis: u32,
+        mut f: impl FnMut(&[T]) -> Vec<U>,
+    ) -> Result<NdArray<U>, NdArrayError>
+    where
+        T: Copy,
+    {
+        let axis = self.check_axis(axis)?;
+        let [outer, n, inner] = self.split_at_axis(axis);
+
+        let mut lane = Vec::with_capacity(n);
+        let mut outputs = Vec::with_capacity(outer * inner);
+        let mut len = None;
+        for o in 0..outer {
+            for i in 0..inner {
+                lane.clear();
+                lane.extend((0..n).map(|k| self.values[(o * n + k) * inner + i]));
+                let out = f(&lane);
+                let m = *len.get_or_insert(out.len());
+                if m == 0 || out.len() != m {
+                    return Err(NdArrayError::BadInput(format!(
+                        "Lanes must map to the same, non-zero number of values, expected {}, got {}",
+                        m,
+                        out.len()
+                    )));
+                }
+                outputs.push(out.into_iter());
+            }
+        }
+        let m = len.ok_or_else(|| {
+            NdArrayError::BadInput("Can not map the lanes of an empty array".to_string())
+        })?;
+
+        let mut values = Data::with_capacity(outer * m * inner);
+        for o in 0..outer {
+            for _ in 0..m {
+                for out in &mut outputs[o * inner..(o + 1) * inner] {
+                    values.extend(out.next());
+                }
+            }
+        }
+        let mut shape: SmallVec<[u32; 4]> = self.shape.as_slice().into();
+        shape[axis] = m as u32;
+        NdArray::new_with_values(shape.as_slice(), values)
+    }
+}
+
+impl<T> NdArray<T>
+where
+    T: Copy,
+{
+    /// Collect values along `axis` using the indices in `index`. Like PyTorch's `gather`.
+    ///
+    /// `index` must have the same number of dimensions as this array, the output has the shape
+    /// of `index`.
+    ///
+    /// For matrices and `axis = 1`: `out[i][j] = self[i][index[i][j]]`
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let a = NdArray::new_with_values([2, 3], vec![1, 2, 3, 4, 5, 6].into()).unwrap();
+    /// let index = NdArray::new_with_values([2, 2], vec![2, 0, 1, 1].into()).unwrap();
+    ///
+    /// let b = a.gather(1, &index).unwrap();
+    ///
+    /// assert_eq!(b.as_slice(), &[3, 1, 5, 5]);
+    /// ```
+    pub fn gather(&self, axis: u32, index: &NdArray<i64>) -> Result<Self, NdArrayError> {
+        let offsets = self.gather_offsets(axis, index)?;
+        let values = offsets.into_iter().map(|i| self.values[i]).collect();
+        Self::new_with_values(index.shape.clone(), values)
+    }
+
+    /// Select values along `axis` using the indices in `indices`. Like numpy's
+    /// `take_along_axis`.
+    ///
+    /// Same as [gather](NdArray::gather), except that negative indices count from the end of
+    /// `axis`.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let a = NdArray::new_with_values([2, 3], vec![1, 2, 3, 4, 5, 6].into()).unwrap();
+    /// let indices = NdArray::new_with_values([2, 1], vec![-1, 0].into()).unwrap();
+    ///
+    /// let b = a.take_along_axis(1, &indices).unwrap();
+    ///
+    /// assert_eq!(b.as_slice(), &[3, 4]);
+    /// ```
+    pub fn take_along_axis(&self, axis: u32, indices: &NdArray<i64>) -> Result<Self, NdArrayError> {
+        let n = self.shape.as_slice()[self.check_axis(axis)?] as i64;
+        let indices = indices.map(|i| if *i < 0 { i + n } else { *i });
+        self.gather(axis, &indices)
+    }
+
+    /// Inverse of [gather](NdArray::gather). Write the values of `src` into this array at the
+    /// positions selected by `index` along `axis`.
+    ///
+    /// `src` must have the same shape as `index`.
+    ///
+    /// If the same position is selected multiple times the last write wins.
+    pub fn scatter(
+        &mut self,
+        axis: u32,
+        index: &NdArray<i64>,
+        src: &Self,
+    ) -> Result<&mut Self, NdArrayError> {
+        if src.shape != index.shape {
+            return Err(NdArrayError::ShapeMismatch {
+                expected: index.shape.clone(),
+                actual: src.shape.clone(),
+            });
+        }
+        let offsets = self.gather_offsets(axis, index)?;
+        for (i, x) in offsets.into_iter().zip(src.values.iter()) {
+            self.values[i] = *x;
+        }
+        Ok(self)
+    }
+
+    /// Return a new array with the slices at `indices` along `axis` removed. Like numpy's
+    /// `delete`.
+    ///
+    /// Duplicate indices are ignored.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    /// use facet_core::ndarray::shape::Shape;
+    ///
+    /// let a = NdArray::new_with_values([2, 3], vec![1, 2, 3, 4, 5, 6].into()).unwrap();
+    ///
+    /// let b = a.delete(1, &[1]).unwrap();
+    ///
+    /// assert_eq!(b.shape(), &Shape::Matrix([2, 2]));
+    /// assert_eq!(b.as_slice(), &[1, 3, 4, 6]);
+    /// ```
+    pub fn delete(&self, axis: u32, indices: &[u32]) -> Result<Self, NdArrayError> {
+        let axis = self.check_axis(axis)?;
+        let [outer, n, inner] = self.split_at_axis(axis);
+        let mut deleted = vec![false; n];
+        for &i in indices {
+            if i as usize >= n {
+                return Err(NdArrayError::IndexOutOfBounds {
+                    index: i as i64,
+                    axis,
+                    size: n as u32,
+                });
+            }
+            deleted[i as usize] = true;
+        }
+        let remaining = deleted.iter().filter(|d| !**d).count();
+
+        let mut values = Data::with_capacity(outer * remaining * inner);
+        for o in 0..outer {
+            for k in (0..n).filter(|k| !deleted[*k]) {
+                let start = (o * n + k) * inner;
+                values.extend_from_slice(&self.values[start..start + inner]);
+            }
+        }
+
+        let mut shape = self.shape.clone();
+        shape[axis] = remaining as u32;
+        Self::new_with_values(shape, values)
+    }
+
+    /// Return a new array with `values` inserted before the given `indices` along `axis`. Like
+    /// numpy's `insert`.
+    ///
+    /// Indices refer to positions in the original array, an index equal to the size of the axis
+    /// appends to the end.
+    ///
+    /// `values` may be
+    /// - a single value, inserted at every position
+    /// - a single slice, having the shape of this array with `axis` removed, inserted at every
+    ///   index
+    /// - one slice per index, having the shape of this array with the size of `axis` replaced by
+    ///   the number of indices
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    /// use facet_core::ndarray::shape::Shape;
+    ///
+    /// let a = NdArray::new_with_values([2, 3], vec![1, 2, 3, 4, 5, 6].into()).unwrap();
+    /// let col = NdArray::new_vector(vec![7, 8]);
+    ///
+    /// let b = a.insert(1, &[0], &col).unwrap();
+    ///
+    /// assert_eq!(b.shape(), &Shape::Matrix([2, 4]));
+    /// assert_eq!(b.as_slice(), &[7, 1, 2, 3, 8, 4, 5, 6]);
+    /// ```
+    pub fn insert(&self, axis: u32, indices: &[u32], values: &Self) -> Result<Self, NdArrayError> {
+        let axis = self.check_axis(axis)?;
+        let [outer, n, inner] = self.split_at_axis(axis);
+        let m = indices.len();
+        for &i in indices {
+            if i as usize > n {
+                return Err(NdArrayError::IndexOutOfBounds {
+                    index: i as i64,
+                    axis,
+                    size: n as u32,
+                });
+            }
+        }
+        // (stride of a slab in `values`, stride of an index in `values`)
+        let (outer_stride, index_stride) = match values.len() {
+            1 => (0, 0),
+            l if l == outer * inner => (inner, 0),
+            l if l == outer * m * inner => (m * inner, inner),
+            l => {
+                return Err(NdArrayError::DimensionMismatch {
+                    expected: outer * m * inner,
+                    actual: l,
+                })
+            }
+        };
+        let inserted_value = |o: usize, j: usize, i: usize| {
+            if values.len() == 1 {
+                values.values[0]
+            } else {
+                values.values[o * outer_stride + j * index_stride + i]
+            }
+        };
+
+        // insert in the order of the indices, keeping the order of equal indices
+        let mut order: Vec<usize> = (0..m).collect();
+        order.sort_by_key(|j| indices[*j]);
+
+        let mut res = Data::with_capacity(outer * (n + m) * inner);
+        for o in 0..outer {
+            let mut order = order.iter().peekable();
+            for k in 0..=n {
+                while let Some(j) = order.next_if(|j| indices[**j] as usize == k) {
+                    res.extend((0..inner).map(|i| inserted_value(o, *j, i)));
+                }
+                if k < n {
+                    let start = (o * n + k) * inner;
+                    res.extend_from_slice(&self.values[start..start + inner]);
+                }
+            }
+        }
+
+        let mut shape = self.shape.clone();
+        shape[axis] += m as u32;
+        Self::new_with_values(shape, res)
+    }
+
+    /// Split the array into two copies along `axis`, before `index`.
+    ///
+    /// `index` may equal the size of `axis`, returning an empty second array.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    /// use facet_core::ndarray::shape::Shape;
+    ///
+    /// let a = NdArray::new_with_values([3, 2], vec![1, 2, 3, 4, 5, 6].into()).unwrap();
+    ///
+    /// let (b, c) = a.split_at(0, 2).unwrap();
+    ///
+    /// assert_eq!(b.shape(), &Shape::Matrix([2, 2]));
+    /// assert_eq!(b.as_slice(), &[1, 2, 3, 4]);
+    /// assert_eq!(c.shape(), &Shape::Matrix([1, 2]));
+    /// assert_eq!(c.as_slice(), &[5, 6]);
+    /// ```
+    pub fn split_at(&self, axis: u32, index: u32) -> Result<(Self, Self), NdArrayError> {
+        let axis = self.check_axis(axis)?;
+        let [outer, n, inner] = self.split_at_axis(axis);
+        let k = index as usize;
+        if k > n {
+            return Err(NdArrayError::IndexOutOfBounds {
+                index: index as i64,
+                axis,
+                size: n as u32,
+            });
+        }
+
+        let mut head = Data::with_capacity(outer * k * inner);
+        let mut tail = Data::with_capacity(outer * (n - k) * inner);
+        // empty lanes have nothing to copy, and `chunks_exact` does not accept 0
+        if n * inner > 0 {
+            for lane in self.values.chunks_exact(n * inner) {
+                head.extend_from_slice(&lane[..k * inner]);
+                tail.extend_from_slice(&lane[k * inner..]);
+            }
+        }
+
+        let mut head_shape = self.shape.clone();
+        head_shape[axis] = index;
+        let mut tail_shape = self.shape.clone();
+        tail_shape[axis] = (n - k) as u32;
+        Ok((
+            Self::new_with_values(head_shape, head)?,
+            Self::new_with_values(tail_shape, tail)?,
+        ))
+    }
+
+    /// Randomly permute the slices along `axis` in place.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let mut a = NdArray::new_with_values([3, 2], vec![1, 1, 2, 2, 3, 3].into()).unwrap();
+    ///
+    /// a.shuffle_axis(0, &mut rand::thread_rng()).unwrap();
+    ///
+    /// // rows stay intact
+    /// let mut rows: Vec<_> = a.iter_rows().map(|row| row.to_vec()).collect();
+    /// rows.sort();
+    /// assert_eq!(rows, vec![vec![1, 1], vec![2, 2], vec![3, 3]]);
+    /// ```
+    pub fn shuffle_axis<R: Rng + ?Sized>(
+        &mut self,
+        axis: u32,
+        rng: &mut R,
+    ) -> Result<&mut Self, NdArrayError> {
+        let axis = self.check_axis(axis)?;
+        let [_, n, inner] = self.split_at_axis(axis);
+        if n * inner == 0 {
+            return Ok(self);
+        }
+        let mut order: Vec<usize> = (0..n).collect();
+        order.shuffle(rng);
+
+        let mut values = Data::with_capacity(self.values.len());
+        for lane in self.values.chunks_exact(n * inner) {
+            for k in order.iter() {
+                values.extend_from_slice(&lane[k * inner..(k + 1) * inner]);
+            }
+        }
+        self.values = values.into();
+        Ok(self)
+    }
+
+    /// Return a copy with the order of the slices along `axis` reversed
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let a = NdArray::new_with_values([2, 3], vec![1, 2, 3, 4, 5, 6].into()).unwrap();
+    ///
+    /// assert_eq!(a.flip_axis(0).unwrap().as_slice(), &[4, 5, 6, 1, 2, 3]);
+    /// assert_eq!(a.flip_axis(1).unwrap().as_slice(), &[3, 2, 1, 6, 5, 4]);
+    /// ```
+    pub fn flip_axis(&self, axis: u32) -> Result<Self, NdArrayError> {
+        let axis = self.check_axis(axis)?;
+        let [_, n, inner] = self.split_at_axis(axis);
+
+        let mut values = Data::with_capacity(self.values.len());
+        if n * inner > 0 {
+            for lane in self.values.chunks_exact(n * inner) {
+                for slice in lane.chunks_exact(inner).rev() {
+                    values.extend_from_slice(slice);
+                }
+            }
+        }
+        Self::new_with_values(self.shape.clone(), values)
+    }
+
+    /// Return a copy with each slice along `axis` repeated `repeats` times in place. Like numpy's
+    /// `repeat` with a single repeat count.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let a = NdArray::new_with_values([2, 2], vec![1, 2, 3, 4].into()).unwrap();
+    ///
+    /// assert_eq!(a.repeat_axis(0, 2).unwrap().as_slice(), &[1, 2, 1, 2, 3, 4, 3, 4]);
+    /// assert_eq!(a.repeat_axis(1, 2).unwrap().as_slice(), &[1, 1, 2, 2, 3, 3, 4, 4]);
+    /// ```
+    pub fn repeat_axis(&self, axis: u32, repeats: u32) -> Result<Self, NdArrayError> {
+        let axis = self.check_axis(axis)?;
+        let [_, n, inner] = self.split_at_axis(axis);
+
+        let mut shape: SmallVec<[u32; 4]> = self.shape.as_slice().into();
+        shape[axis] = shape[axis]
+            .checked_mul(repeats)
+            .ok_or_else(|| NdArrayError::ShapeOverflow(self.shape.clone()))?;
+        let shape = match shape.as_slice() {
+            // `Shape::from` would treat an empty vector as a scalar
+            [n] => Shape::Vector([*n]),
+            shape => Shape::from(shape),
+        };
+
+        let mut values = Data::with_capacity(shape.checked_span().unwrap_or(0));
+        if n * inner > 0 {
+            for lane in self.values.chunks_exact(n * inner) {
+                for slice in lane.chunks_exact(inner) {
+                    for _ in 0..repeats {
+                        values.extend_from_slice(slice);
+                    }
+                }
+            }
+        }
+        Self::new_with_values(shape, values)
+    }
+
+    /// Return a copy with the axes `a` and `b` interchanged. Like numpy's `swapaxes`.
+    ///
+    /// ```
+    /// use facet_core::ndarray::{shape::Shape, NdArray};
+    ///
+    /// let a = NdArray::new_with_values(&[2, 1, 3][..], vec![1, 2, 3, 4, 5, 6].into()).unwrap();
+    ///
+    /// let b = a.swap_axes(0, 2).unwrap();
+    ///
+    /// assert_eq!(b.shape(), &Shape::Tensor((&[3, 1, 2][..]).into()));
+    /// assert_eq!(b.as_slice(), &[1, 4, 2, 5, 3, 6]);
+    /// ```
+    pub fn swap_axes(&self, a: u32, b: u32) -> Result<Self, NdArrayError> {
+        let a = self.check_axis(a)?;
+        let b = self.check_axis(b)?;
+
+        let mut shape = self.shape.clone();
+        let mut stride = self.stride.clone();
+        shape.as_mut_slice().swap(a, b);
+        stride.swap(a, b);
+        self.permuted(shape, &stride)
+    }
+
+    /// Return a copy with the order of the axes reversed, e.g. shape `[2, 3, 4]` becomes
+    /// `[4, 3, 2]`. Like numpy's `.T`.
+    ///
+    /// The values of the result in row-major order are the values of this array in column-major
+    /// order.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let a = NdArray::new_with_values([2, 3], vec![1, 2, 3, 4, 5, 6].into()).unwrap();
+    ///
+    /// assert_eq!(a.reverse_axes().as_slice(), &[1, 4, 2, 5, 3, 6]);
+    /// ```
+    pub fn reverse_axes(&self) -> Self {
+        let mut shape = self.shape.clone();
+        let mut stride = self.stride.clone();
+        shape.as_mut_slice().reverse();
+        stride.reverse();
+        self.permuted(shape, &stride).unwrap()
+    }
+
+    /// Return a copy with the axes permuted, axis `i` of the result is axis `axes[i]` of this
+    /// array. Like numpy's `transpose` with `axes`.
+    ///
+    /// `axes` must contain every axis exactly once.
+    ///
+    /// ```
+    /// use facet_core::ndarray::{shape::Shape, NdArray};
+    ///
+    /// let a = NdArray::new_with_values(&[2, 1, 3][..], vec![1, 2, 3, 4, 5, 6].into()).unwrap();
+    ///
+    /// let b = a.transpose_axes(&[2, 0, 1]).unwrap();
+    ///
+    /// assert_eq!(b.shape(), &Shape::Tensor((&[3, 2, 1][..]).into()));
+    /// assert_eq!(b.as_slice(), &[1, 4, 2, 5, 3, 6]);
+    /// ```
+    pub fn transpose_axes(&self, axes: &[u32]) -> Result<Self, NdArrayError> {
+        let ndims = self.shape.as_slice().len();
+        let mut seen = vec![false; ndims];
+        for axis in axes {
+            let axis = self.check_axis(*axis)?;
+            if std::mem::replace(&mut seen[axis], true) {
+                return Err(NdArrayError::BadInput(format!(
+                    "axis {} is repeated in the permutation {:?}",
+                    axis, axes
+                )));
+            }
+        }
+        if axes.len() != ndims {
+            return Err(NdArrayError::BadInput(format!(
+                "Expected a permutation of {} axes, got {:?}",
+                ndims, axes
+            )));
+        }
+
+        let dims: SmallVec<[u32; 4]> = axes.iter().map(|a| self.shape[*a as usize]).collect();
+        let stride: SmallVec<[usize; 4]> = axes.iter().map(|a| self.stride[*a as usize]).collect();
+        self.permuted(dims.as_slice().into(), &stride)
+    }
+
+    /// Return a copy with the axes `source` moved to the positions `destination`, the other axes
+    /// keep their relative order. Like numpy's `moveaxis`.
+    ///
+    /// ```
+    /// use facet_core::ndarray::{shape::Shape, NdArray};
+    ///
+    /// let a: NdArray<f32> = NdArray::new(&[2, 3, 4][..]);
+    ///
+    /// let b = a.move_axes(&[1], &[2]).unwrap();
+    ///
+    /// assert_eq!(b.shape(), &Shape::Tensor((&[2, 4, 3][..]).into()));
+    /// ```
+    pub fn move_axes(&self, source: &[u32], destination: &[u32]) -> Result<Self, NdArrayError> {
+        if source.len() != destination.len() {
+            return Err(NdArrayError::BadInput(format!(
+                "source {:?} and destination {:?} must have the same number of axes",
+                source, destination
+            )));
+        }
+        let mut moves = Vec::with_capacity(source.len());
+        for (dst, src) in destination.iter().zip(source) {
+            if destination.iter().filter(|d| *d == dst).count() > 1 {
+                return Err(NdArrayError::BadInput(format!(
+                    "axis {} is repeated in the destination {:?}",
+                    dst, destination
+                )));
+            }
+            moves.push((self.check_axis(*dst)?, *src));
+        }
+        // inserting in ascending order of destination puts every moved axis at its position
+        moves.sort_unstable();
+
+        let ndims = self.shape.as_slice().len() as u32;
+        let mut order: Vec<u32> = (0..ndims).filter(|axis| !source.contains(axis)).collect();
+        for (dst, src) in moves {
+            order.insert(dst, src);
+        }
+        // repeated or out of bounds sources are rejected here
+        self.transpose_axes(&order)
+    }
+
+    /// Copy the values into an array of `shape`, where `stride` is the stride of this array with
+    /// its axes permuted the same way as `shape`
+    fn permuted(&self, shape: Shape, stride: &[usize]) -> Result<Self, NdArrayError> {
+        let dims = shape.as_slice();
+
+        // walk the output in row-major order, reading the input through the permuted strides
+        let mut values = Data::with_capacity(self.values.len());
+        let mut index = vec![0u32; dims.len()];
+        for _ in 0..self.values.len() {
+            let offset: usize = index
+                .iter()
+                .zip(stride.iter())
+                .map(|(i, s)| *i as usize * s)
+                .sum();
+            values.push(self.values[offset]);
+            increment_index(&mut index, dims);
+        }
+        Self::new_with_values(shape, values)
+    }
+
+    /// Rotate the array by 90 degrees `k` times in the plane of `axes`, from the first axis towards
+    /// the second. Like numpy's `rot90`, negative `k` rotates in the opposite direction.
+    ///
+    /// ```
+    /// use facet_core::ndarray::{shape::Shape, NdArray};
+    ///
+    /// let a = NdArray::new_with_values([2, 2], vec![1, 2, 3, 4].into()).unwrap();
+    ///
+    /// assert_eq!(a.rot90(1, [0, 1]).unwrap().as_slice(), &[2, 4, 1, 3]);
+    /// assert_eq!(a.rot90(-1, [0, 1]).unwrap().as_slice(), &[3, 1, 4, 2]);
+    /// ```
+    pub fn rot90(&self, k: i32, [a, b]: [u32; 2]) -> Result<Self, NdArrayError> {
+        self.check_axis(a)?;
+        self.check_axis(b)?;
+        if a == b {
+            return Err(NdArrayError::BadInput(format!(
+                "rot90 axes must be different, got {} and {}",
+                a, b
+            )));
+        }
+        match k.rem_euclid(4) {
+            0 => Ok(self.clone()),
+            1 => self.flip_axis(b)?.swap_axes(a, b),
+            2 => self.flip_axis(a)?.flip_axis(b),
+            _ => self.swap_axes(a, b)?.flip_axis(b),
+        }
+    }
+
+    /// Copy `src` into the region of this array starting at `offsets`.
+    ///
+    /// `src` must have the same number of dimensions as this array and fit inside it when placed
+    /// at `offsets`. Whole rows are copied at once.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let mut a = NdArray::new_with_values([3, 3], vec![0; 9].into()).unwrap();
+    /// let b = NdArray::new_with_values([2, 2], vec![1, 2, 3, 4].into()).unwrap();
+    ///
+    /// a.write_region(&[1, 0], &b).unwrap();
+    ///
+    /// assert_eq!(a.as_slice(), &[0, 0, 0, 1, 2, 0, 3, 4, 0]);
+    /// ```
+    pub fn write_region(&mut self, offsets: &[u32], src: &Self) -> Result<&mut Self, NdArrayError> {
+        let shape = self.shape.as_slice();
+        let src_shape = src.shape.as_slice();
+        if offsets.len() != shape.len() {
+            return Err(NdArrayError::DimensionMismatch {
+                expected: shape.len(),
+                actual: offsets.len(),
+            });
+        }
+        if src_shape.len() != shape.len() {
+            return Err(NdArrayError::DimensionMismatch {
+                expected: shape.len(),
+                actual: src_shape.len(),
+            });
+        }
+        for (axis, ((n, m), o)) in shape.iter().zip(src_shape).zip(offsets).enumerate() {
+            if *o as u64 + *m as u64 > *n as u64 {
+                return Err(NdArrayError::IndexOutOfBounds {
+                    index: *o as i64 + *m as i64 - 1,
+                    axis,
+                    size: *n,
+                });
+            }
+        }
+        if shape.is_empty() {
+            // scalars
+            self.values[0] = src.values[0];
+            return Ok(self);
+        }
+        if src.values.is_empty() {
+            return Ok(self);
+        }
+
+        let stride = stride_vec(1, shape);
+        let nd = shape.len();
+        let row_len = src_shape[nd - 1] as usize;
+        // index of the current row in `src`, the last dimension is always 0
+        let mut index = vec![0u32; nd];
+        for row in src.values.chunks_exact(row_len) {
+            let start: usize = index
+                .iter()
+                .zip(offsets)
+                .zip(stride.iter())
+                .map(|((i, o), s)| (i + o) as usize * s)
+                .sum();
+            self.values[start..start + row_len].copy_from_slice(row);
+            increment_index(&mut index[..nd - 1], &src_shape[..nd - 1]);
+        }
+        Ok(self)
+    }
+
+    /// Split the shape into `[outer, n, inner]` where `n` is the size of `axis`, `outer` is the
+    /// number of elements spanned by the previous dimensions and `inner` by the following
+    /// dimensions
+    pub(crate) fn split_at_axis(&self, axis: usize) -> [usize; 3] {
+        let shape = self.shape.as_slice();
+        let outer = shape[..axis].iter().map(|x| *x as usize).product();
+        let inner = shape[axis + 1..].iter().map(|x| *x as usize).product();
+        [outer, shape[axis] as usize, inner]
+    }
+
+    pub(crate) fn check_axis(&self, axis: u32) -> Result<usize, NdArrayError> {
+        let ndims = self.shape.as_slice().len();
+        if ndims == 0 {
+            return Err(NdArrayError::UnsupportedShape(self.shape.clone()));
+        }
+        let axis = axis as usize;
+        if axis >= ndims {
+            return Err(NdArrayError::BadInput(format!(
+                "axis {} is out of bounds for an array of {} dimensions",
+                axis, ndims
+            )));
+        }
+        Ok(axis)
+    }
+
+    /// Translate every entry of `index` into an offset into the values of this array
+    fn gather_offsets(&self, axis: u32, index: &NdArray<i64>) -> Result<Vec<usize>, NdArrayError> {
+        let axis = self.check_axis(axis)?;
+        let shape = self.shape.as_slice();
+        let index_shape = index.shape.as_slice();
+        if shape.len() != index_shape.len() {
+            return Err(NdArrayError::DimensionMismatch {
+                expected: shape.len(),
+                actual: index_shape.len(),
+            });
+        }
+        // the index may not be larger than the array in the non-indexed dimensions
+        for (d, (n, m)) in shape.iter().zip(index_shape.iter()).enumerate() {
+            if d != axis && m > n {
+                return Err(NdArrayError::ShapeMismatch {
+                    expected: self.shape.clone(),
+                    actual: index.shape.clone(),
+                });
+            }
+        }
+
+        let stride = stride_vec(1, shape);
+        let size = shape[axis];
+        let mut coords = vec![0u32; shape.len()];
+        let mut res = Vec::with_capacity(index.len());
+        for &i in index.values.iter() {
+            if i < 0 || i >= size as i64 {
+                return Err(NdArrayError::IndexOutOfBounds {
+                    index: i,
+                    axis,
+                    size,
+                });
+            }
+            let offset = coords
+                .iter()
+                .zip(stride.iter())
+                .enumerate()
+                .map(|(d, (c, s))| if d == axis { i as usize } else { *c as usize } * s)
+                .sum();
+            res.push(offset);
+            increment_index(&mut coords, index_shape);
+        }
+        Ok(res)
+    }
+}
+
+impl<T> NdArray<T>
+where
+    T: Copy + std::ops::AddAssign,
+{
+    /// Like [scatter](NdArray::scatter), but the values of `src` are added to this array instead
+    /// of overwriting it. Positions selected multiple times accumulate every value.
+    ///
+    /// ```
+    /// use facet_core::ndarray::NdArray;
+    ///
+    /// let mut a = NdArray::new_with_values([3, 2], vec![0; 6].into()).unwrap();
+    /// let index = NdArray::new_with_values([3, 2], vec![0, 0, 2, 2, 0, 0].into()).unwrap();
+    /// let src = NdArray::new_with_values([3, 2], vec![1, 2, 3, 4, 5, 6].into()).unwrap();
+    ///
+    /// a.scatter_add(0, &index, &src).unwrap();
+    ///
+    /// assert_eq!(a.as_slice(), &[6, 8, 0, 0, 3, 4]);
+    /// ```
+    pub fn scatter_add(
+        &mut self,
+        axis: u32,
+        index: &NdArray<i64>,
+        src: &Self,
+    ) -> Result<&mut Self, NdArrayError> {
+        if src.shape != index.shape {
+            return Err(NdArrayError::ShapeMismatch {
+                expected: index.shape.clone(),
+                actual: src.shape.clone(),
+            });
+        }
+        let offsets = self.gather_offsets(axis, index)?;
+        for (i, x) in offsets.into_iter().zip(src.values.iter()) {
+            self.values[i] += *x;
+        }
+        Ok(self)
+    }
+}
+
+/// Step a multi-index to the next position in row-major order
+fn increment_index(index: &mut [u32], shape: &[u32]) {
+    for (i, n) in index.iter_mut().zip(shape.iter()).rev() {
+        *i += 1;
+        if *i < *n {
+            return;
+        }
+        *i = 0;
+    }
+}
@@ -12,6 +12,9 @@ use rayon::prelude::*;
 /// Raw matrix multiplication method
 ///
 /// multiplies m*k and k*n matrices and outputs an m*n matrix
+///
+/// The slices must hold the matrices densely in row-major order, see
+/// [is_contiguous](NdArray::is_contiguous).
 // this really should be optimized...
 pub fn matmul_impl<'a, T>(
     [m, k, n]: [u32; 3],
@@ -26,21 +29,28 @@ where
     debug_assert_eq!((n as usize * k as usize), in1.len());
     debug_assert_eq!(out.len(), m as usize * n as usize);
 
+    if out.is_empty() {
+        // m or n is 0, nothing to compute
+        return Ok(());
+    }
+
     #[cfg(feature = "rayon")]
     {
         let k = k as usize;
         let n = n as usize;
         // iterate over the result's rows
-        out.par_chunks_mut(n).enumerate().for_each(|(i, row)| {
-            for j in 0..row.len() {
-                let mut valout = Default::default();
-                for l in 0usize..k {
-                    let val0 = in0[i * k + l];
-                    let val1 = in1[l * n + j];
-                    valout += val0 * val1
+        crate::thread_pool::install(|| {
+            out.par_chunks_mut(n).enumerate().for_each(|(i, row)| {
+                for j in 0..row.len() {
+                    let mut valout = Default::default();
+                    for l in 0usize..k {
+                        let val0 = in0[i * k + l];
+                        let val1 = in1[l * n + j];
+                        valout += val0 * val1
+                    }
+                    row[j] = valout;
                 }
-                row[j] = valout;
-            }
+            })
         });
     }
 
@@ -60,7 +70,7 @@ where
     Ok(())
 }
 
-/// f32 specialized method
+/// f32 specialized method, with the same preconditions as [matmul_impl]
 pub fn matmul_impl_f32<'a>(
     [m, k, n]: [u32; 3],
     in0: &'a [f32],
@@ -70,6 +80,122 @@ pub fn matmul_impl_f32<'a>(
     matmul_impl([m, k, n], in0, in1, out)
 }
 
+/// Tile sizes of [matmul_tiled].
+///
+/// A single step of the multiplication only touches an `m*k`, a `k*n` and an `m*n` tile, bounding
+/// the memory a step needs, regardless of the size of the input matrices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatmulTiles {
+    pub m: u32,
+    pub k: u32,
+    pub n: u32,
+}
+
+impl Default for MatmulTiles {
+    fn default() -> Self {
+        Self {
+            m: 256,
+            k: 256,
+            n: 256,
+        }
+    }
+}
+
+impl MatmulTiles {
+    /// Largest square tiles whose buffers fit into `budget` number of elements
+    pub fn from_budget(budget: usize) -> Self {
+        let size = ((budget / 3) as f64).sqrt() as u32;
+        let size = size.max(1);
+        Self {
+            m: size,
+            k: size,
+            n: size,
+        }
+    }
+
+    /// Number of elements the buffers of a single step span
+    pub fn span(&self) -> usize {
+        let [m, k, n] = [self.m as usize, self.k as usize, self.n as usize];
+        m * k + k * n + m * n
+    }
+}
+
+/// Multiply m*k and k*n matrices block by block, accumulating the partial products into the m*n
+/// `out` matrix.
+///
+/// Each pair of tiles is copied into dense buffers and multiplied by `f`, which has the same
+/// signature as [matmul_impl].
+pub fn matmul_tiled<T, F>(
+    tiles: MatmulTiles,
+    [m, k, n]: [u32; 3],
+    in0: &[T],
+    in1: &[T],
+    out: &mut [T],
+    f: F,
+) -> Result<(), NdArrayError>
+where
+    T: AddAssign + Default + Copy,
+    F: Fn([u32; 3], &[T], &[T], &mut [T]) -> Result<(), NdArrayError>,
+{
+    debug_assert_eq!((m as usize * k as usize), in0.len());
+    debug_assert_eq!((n as usize * k as usize), in1.len());
+    debug_assert_eq!(out.len(), m as usize * n as usize);
+
+    if tiles.m == 0 || tiles.k == 0 || tiles.n == 0 {
+        return Err(NdArrayError::BadInput(format!(
+            "tile sizes must be positive, got {:?}",
+            tiles
+        )));
+    }
+
+    let [m, k, n] = [m as usize, k as usize, n as usize];
+    out.iter_mut().for_each(|x| *x = T::default());
+
+    let mut a = Vec::with_capacity(tiles.m as usize * tiles.k as usize);
+    let mut b = Vec::with_capacity(tiles.k as usize * tiles.n as usize);
+    let mut c = Vec::with_capacity(tiles.m as usize * tiles.n as usize);
+    for i0 in (0..m).step_by(tiles.m as usize) {
+        let i1 = (i0 + tiles.m as usize).min(m);
+        for j0 in (0..n).step_by(tiles.n as usize) {
+            let j1 = (j0 + tiles.n as usize).min(n);
+            for l0 in (0..k).step_by(tiles.k as usize) {
+                let l1 = (l0 + tiles.k as usize).min(k);
+
+                a.clear();
+                for row in in0.chunks(k).skip(i0).take(i1 - i0) {
+                    a.extend_from_slice(&row[l0..l1]);
+                }
+                b.clear();
+                for row in in1.chunks(n).skip(l0).take(l1 - l0) {
+                    b.extend_from_slice(&row[j0..j1]);
+                }
+                c.clear();
+                c.resize((i1 - i0) * (j1 - j0), T::default());
+
+                f(
+                    [(i1 - i0) as u32, (l1 - l0) as u32, (j1 - j0) as u32],
+                    &a,
+                    &b,
+                    &mut c,
+                )?;
+
+                for (row, partial) in out
+                    .chunks_mut(n)
+                    .skip(i0)
+                    .take(i1 - i0)
+                    .zip(c.chunks(j1 - j0))
+                {
+                    for (x, y) in row[j0..j1].iter_mut().zip(partial) {
+                        *x += *y;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub fn transpose_mat<T: Clone>([m, n]: [usize; 2], inp: &[T], out: &mut [T]) {
     assert!(inp.len() >= m * n);
     assert!(inp.len() <= out.len());
@@ -265,11 +391,57 @@ impl<T> NdArray<T> {
         self._matmul(other, out, matmul_impl)
     }
 
+    /// Same as [matmul](NdArray::matmul), but multiplies the matrices tile by tile, bounding the
+    /// size of the intermediate buffers by `tiles`.
+    ///
+    /// ```
+    /// use facet_core::ndarray::{NdArray, Data};
+    /// use facet_core::ndarray::matrix::MatmulTiles;
+    ///
+    /// let a = NdArray::new_with_values([2, 3], Data::from_slice(&[1, 2, -1, 2, 0, 1])).unwrap();
+    /// let b = NdArray::new_with_values([3, 2], Data::from_slice(&[3, 1, 0, -1, -2, 3])).unwrap();
+    ///
+    /// let mut c = NdArray::new(0);
+    /// a.matmul_tiled(&b, &mut c, MatmulTiles { m: 1, k: 2, n: 1 }).expect("matmul");
+    ///
+    /// assert_eq!(c.as_slice(), &[5, -4, 4, 5]);
+    /// ```
+    pub fn matmul_tiled<'a>(
+        &'a self,
+        other: &'a Self,
+        out: &mut Self,
+        tiles: MatmulTiles,
+    ) -> Result<(), NdArrayError>
+    where
+        T: AddAssign + Add<Output = T> + Mul<Output = T> + Default + 'a + Copy + Sync + Send,
+    {
+        self._matmul(other, out, |dims, in0, in1, out| {
+            matmul_tiled(tiles, dims, in0, in1, out, |dims, a, b, c| {
+                matmul_impl(dims, a, b, c)
+            })
+        })
+    }
+
+    /// The last dimension of the left operand has to match the first dimension of the inner
+    /// matrices of the right operand
+    fn check_contracted(&self, other: &Self, k0: u32, k1: u32) -> Result<(), NdArrayError> {
+        if k0 != k1 {
+            return Err(NdArrayError::MatmulShapeMismatch {
+                shape_a: self.shape.clone(),
+                shape_b: other.shape.clone(),
+            });
+        }
+        Ok(())
+    }
+
     fn _matmul<'a, F>(&'a self, other: &'a Self, out: &mut Self, f: F) -> Result<(), NdArrayError>
     where
         F: Fn([u32; 3], &'a [T], &'a [T], &mut [T]) -> Result<(), NdArrayError> + Sync,
-        T: Default + Send + Sync,
+        T: Default + Send + Sync + Clone,
     {
+        // the kernels index into the raw value slices
+        debug_assert!(self.is_contiguous());
+        debug_assert!(other.is_contiguous());
         match (&self.shape, &other.shape) {
             shapes @ (Shape::Scalar(_), Shape::Scalar(_))
             | shapes @ (Shape::Scalar(_), Shape::Vector(_))
@@ -285,7 +457,8 @@ impl<T> NdArray<T> {
                 })
             }
 
-            (Shape::Vector([l]), Shape::Matrix([_, n])) => {
+            (Shape::Vector([l]), Shape::Matrix([k, n])) => {
+                self.check_contracted(other, *l, *k)?;
                 out.reshape(Shape::Matrix([1, *n]));
                 f(
                     [1, *l, *n],
@@ -293,10 +466,12 @@ impl<T> NdArray<T> {
                     other.as_slice(),
                     out.as_mut_slice(),
                 )?;
-                out.reshape(*n);
+                // `reshape(0)` would produce a scalar
+                out.reshape(Shape::Vector([*n]));
                 Ok(())
             }
-            (Shape::Matrix([m, n]), Shape::Vector([_])) => {
+            (Shape::Matrix([m, n]), Shape::Vector([l])) => {
+                self.check_contracted(other, *n, *l)?;
                 out.reshape(Shape::Matrix([*m, 1]));
                 f(
                     [*m, *n, 1],
@@ -304,10 +479,11 @@ impl<T> NdArray<T> {
                     other.as_slice(),
                     out.as_mut_slice(),
                 )?;
-                out.reshape(*n);
+                out.reshape(Shape::Vector([*m]));
                 Ok(())
             }
-            (Shape::Matrix([a, b]), Shape::Matrix([_, d])) => {
+            (Shape::Matrix([a, b]), Shape::Matrix([c, d])) => {
+                self.check_contracted(other, *b, *c)?;
                 out.reshape(Shape::Matrix([*a, *d]));
                 f(
                     [*a, *b, *d],
@@ -320,22 +496,23 @@ impl<T> NdArray<T> {
 
             // broadcast matrices
             (Shape::Vector([l]), shp @ Shape::Tensor(_)) => {
-                let [n, m] = shp.last_two().unwrap();
+                let [k, n] = shp.last_two().unwrap();
+                self.check_contracted(other, *l, k)?;
 
-                let it = ColumnIter::new(&other.values, m as usize * n as usize);
-                out.reshape([(other.len() / (m as usize * n as usize)) as u32, *l]);
-                for (mat, out) in it.zip(ColumnIterMut::new(&mut out.values, *l as usize)) {
+                let it = ColumnIter::new(&other.values, k as usize * n as usize);
+                out.reshape([(other.len() / (k as usize * n as usize)) as u32, n]);
+                for (mat, out) in it.zip(ColumnIterMut::new(&mut out.values, n as usize)) {
                     f([1, *l, n], self.as_slice(), mat, out)?;
                 }
                 Ok(())
             }
             (shp @ Shape::Tensor(_), Shape::Vector([l])) => {
-                let [n, m] = shp.last_two().unwrap();
+                let [m, n] = shp.last_two().unwrap();
+                self.check_contracted(other, n, *l)?;
 
                 let it = ColumnIter::new(&self.values, m as usize * n as usize);
-                let l: u32 = *l;
-                out.reshape([(self.len() / (m as usize * n as usize)) as u32, l]);
-                for (mat, out) in it.zip(ColumnIterMut::new(&mut out.values, l as usize)) {
+                out.reshape([(self.len() / (m as usize * n as usize)) as u32, m]);
+                for (mat, out) in it.zip(ColumnIterMut::new(&mut out.values, m as usize)) {
                     f([m, n, 1], mat, other.as_slice(), out)?;
                 }
                 Ok(())
@@ -343,6 +520,7 @@ impl<T> NdArray<T> {
             (Shape::Matrix([a, b]), shp @ Shape::Tensor(_)) => {
                 let [a, b] = [*a, *b];
                 let [c, d] = shp.last_two().unwrap();
+                self.check_contracted(other, b, c)?;
 
                 let it = ColumnIter::new(&other.values, c as usize * d as usize);
                 out.reshape(vec![(other.len() / (c as usize * d as usize)) as u32, a, d]);
@@ -356,21 +534,21 @@ impl<T> NdArray<T> {
             (shp @ Shape::Tensor(_), Shape::Matrix([c, d])) => {
                 let [a, b] = shp.last_two().unwrap();
                 let [c, d] = [*c, *d];
-
-                assert_eq!(b, c);
+                self.check_contracted(other, b, c)?;
 
                 let it = ColumnIter::new(&self.values, a as usize * b as usize);
-                out.reshape(vec![(self.len() / (c as usize * d as usize)) as u32, a, d]);
+                out.reshape(vec![(self.len() / (a as usize * b as usize)) as u32, a, d]);
                 for (mat, out) in
                     it.zip(ColumnIterMut::new(&mut out.values, a as usize * d as usize))
                 {
-                    f([a, b, d], self.as_slice(), mat, out)?;
+                    f([a, b, d], mat, other.as_slice(), out)?;
                 }
                 Ok(())
             }
             (ab @ Shape::Tensor(_), cd @ Shape::Tensor(_)) => {
                 let [a, b] = ab.last_two().unwrap();
                 let [c, d] = cd.last_two().unwrap();
+                self.check_contracted(other, b, c)?;
 
                 // number of matrices
                 let nmatrices = self.shape.span() / (b as usize * a as usize);
@@ -388,7 +566,7 @@ impl<T> NdArray<T> {
                 #[cfg(not(feature = "rayon"))]
                 {
                     let it_0 = self.values.as_slice().chunks(a as usize * b as usize);
-                    let it_1 = other.values.as_slice().chunks(a as usize * b as usize);
+                    let it_1 = other.values.as_slice().chunks(c as usize * d as usize);
                     for (out, (lhs, rhs)) in
                         ColumnIterMut::new(&mut out.values, a as usize * d as usize)
                             .zip(it_0.zip(it_1))
@@ -399,12 +577,14 @@ impl<T> NdArray<T> {
                 #[cfg(feature = "rayon")]
                 {
                     let it_0 = self.values.as_slice().par_chunks(a as usize * b as usize);
-                    let it_1 = other.values.as_slice().par_chunks(a as usize * b as usize);
-                    out.values
-                        .as_mut_slice()
-                        .par_chunks_mut(a as usize * d as usize)
-                        .zip(it_0.zip(it_1))
-                        .try_for_each(|(out, (lhs, rhs))| f([a, b, d], lhs, rhs, out))?;
+                    let it_1 = other.values.as_slice().par_chunks(c as usize * d as usize);
+                    crate::thread_pool::install(|| {
+                        out.values
+                            .as_mut_slice()
+                            .par_chunks_mut(a as usize * d as usize)
+                            .zip(it_0.zip(it_1))
+                            .try_for_each(|(out, (lhs, rhs))| f([a, b, d], lhs, rhs, out))
+                    })?;
                 }
 
                 Ok(())
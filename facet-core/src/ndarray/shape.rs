@@ -41,15 +41,77 @@ impl Shape {
     /// Total number of elements in an array this shape spans.
     ///
     /// For NdArrays this is equal to the `len` of its value array
+    ///
+    /// Panics if the span does not fit into `usize`, see [checked_span](Shape::checked_span).
     pub fn span(&self) -> usize {
+        self.checked_span()
+            .unwrap_or_else(|| panic!("The span of shape {:?} overflows usize", self))
+    }
+
+    /// Total number of elements in an array this shape spans, or `None` if it, or the stride of
+    /// any dimension, does not fit into `usize`.
+    ///
+    /// ```
+    /// use facet_core::ndarray::shape::Shape;
+    ///
+    /// assert_eq!(Shape::from(&[2, 3, 4][..]).checked_span(), Some(24));
+    /// assert_eq!(Shape::from(&[u32::MAX; 3][..]).checked_span(), None);
+    /// // the span is 0, but the stride of the first dimension would still overflow
+    /// assert_eq!(Shape::from(&[0, u32::MAX, u32::MAX, u32::MAX][..]).checked_span(), None);
+    /// ```
+    pub fn checked_span(&self) -> Option<usize> {
         match self {
-            Shape::Scalar(_) => 1,
-            Shape::Vector([n]) => *n as usize,
-            Shape::Matrix([n, m]) => *n as usize * *m as usize,
-            Shape::Tensor(vals) => vals.iter().map(|x| *x as usize).product(),
+            Shape::Scalar(_) => Some(1),
+            Shape::Vector([n]) => Some(*n as usize),
+            Shape::Matrix([n, m]) => (*n as usize).checked_mul(*m as usize),
+            // multiply from the back, so every intermediate product is the stride of a dimension
+            Shape::Tensor(vals) => vals
+                .iter()
+                .rev()
+                .try_fold(1usize, |span, x| span.checked_mul(*x as usize)),
         }
     }
 
+    /// Position of the multi-dimensional `index` in the row-major values of an array of this
+    /// shape. `None` if `index` is out of bounds or does not have one entry per dimension.
+    ///
+    /// ```
+    /// use facet_core::ndarray::shape::Shape;
+    ///
+    /// let shape = Shape::from(&[2, 4, 8][..]);
+    ///
+    /// assert_eq!(shape.ravel_index(&[1, 3, 5]), Some(61));
+    /// assert_eq!(shape.unravel_index(61).as_deref(), Some(&[1, 3, 5][..]));
+    /// assert_eq!(shape.ravel_index(&[1, 4, 0]), None);
+    /// ```
+    pub fn ravel_index(&self, index: &[u32]) -> Option<usize> {
+        let dims = self.as_slice();
+        if index.len() != dims.len() || index.iter().zip(dims).any(|(i, n)| i >= n) {
+            return None;
+        }
+        let stride = stride_vec(1, dims);
+        Some(index.iter().zip(stride).map(|(i, s)| *i as usize * s).sum())
+    }
+
+    /// Inverse of [ravel_index](Shape::ravel_index). `None` if `flat` is out of bounds.
+    pub fn unravel_index(&self, flat: usize) -> Option<SmallVec<[u32; 4]>> {
+        if flat >= self.checked_span()? {
+            return None;
+        }
+        let dims = self.as_slice();
+        let mut rem = flat;
+        let index = stride_vec(1, dims)
+            .into_iter()
+            .take(dims.len())
+            .map(|s| {
+                let i = rem / s;
+                rem %= s;
+                i as u32
+            })
+            .collect();
+        Some(index)
+    }
+
     /// Number of columns spanned by this shape.
     pub fn col_span(&self) -> usize {
         match self {
@@ -151,6 +213,37 @@ impl<'a> From<&'a [u32]> for Shape {
     }
 }
 
+/// Shape of the result of an element-wise operation between arrays of shapes `a` and `b`, like
+/// numpy. The shapes are aligned at their last axis, axes of length 1 and missing leading axes
+/// stretch to the length of the other shape.
+///
+/// Returns `None` if the shapes are incompatible.
+///
+/// ```
+/// use facet_core::ndarray::shape::{broadcast_shape, Shape};
+///
+/// let a = Shape::from(&[2, 1, 3][..]);
+/// let b = Shape::from([4, 1]);
+///
+/// assert_eq!(broadcast_shape(&a, &b), Some(Shape::from(&[2, 4, 3][..])));
+/// assert_eq!(broadcast_shape(&a, &Shape::from([4, 2])), None);
+/// ```
+pub fn broadcast_shape(a: &Shape, b: &Shape) -> Option<Shape> {
+    let (a, b) = (a.as_slice(), b.as_slice());
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let offset = long.len() - short.len();
+    let mut res: SmallVec<[u32; 4]> = long.into();
+    for (x, y) in res[offset..].iter_mut().zip(short.iter()) {
+        match (*x, *y) {
+            (x, y) if x == y => {}
+            (1, y) => *x = y,
+            (_, 1) => {}
+            _ => return None,
+        }
+    }
+    Some(Shape::from(res.as_slice()))
+}
+
 /// Vector with the stride of each element of each dimension
 ///
 /// 0 long shapes will return [1], they span a single item
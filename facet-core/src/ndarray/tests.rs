@@ -60,6 +60,36 @@ fn test_vector_inner() {
     assert_eq!(c, Some((69i32).pow(2) * 8));
 }
 
+#[test]
+fn test_inner_mismatched_span_is_none() {
+    let a = NdArray::new_vector(vec![1, 2, 3]);
+    let b = NdArray::new_vector(vec![1, 2]);
+    assert_eq!(a.inner(&b), None);
+
+    let m = NdArray::new_with_values([2, 2], Data::from_slice(&[1, 2, 3, 4])).unwrap();
+    assert_eq!(a.inner(&m), None);
+    assert_eq!(m.inner(&a), None);
+
+    let n = NdArray::new_with_values([3, 2], Data::from_slice(&[1, 2, 3, 4, 5, 6])).unwrap();
+    assert_eq!(m.inner(&n), None);
+}
+
+#[test]
+fn test_inner_zero_result_is_some() {
+    let a = NdArray::new_vector(vec![1, -1]);
+    let b = NdArray::new_vector(vec![1, 1]);
+    assert_eq!(a.inner(&b), Some(0));
+
+    let m = NdArray::new_with_values([2, 2], Data::from_slice(&[1, 2, 3, 4])).unwrap();
+    // (1 - 2) + (3 - 4)
+    let v = NdArray::new_vector(vec![1, -1]);
+    assert_eq!(m.inner(&v), Some(-2));
+    assert_eq!(v.inner(&m), Some(-2));
+
+    let z = NdArray::new_with_values([2, 2], Data::from_slice(&[0, 0, 0, 0])).unwrap();
+    assert_eq!(m.inner(&z), Some(0));
+}
+
 #[test]
 fn test_mat_mat_inner() {
     let mut a = NdArray::new([3, 3]);
@@ -230,12 +260,22 @@ fn test_mat_mat_mul_many() {
 fn test_mat_transpose() {
     let a = NdArray::new_with_values(&[2, 3][..], Data::from_slice(&[1, 2, 3, 4, 5, 6])).unwrap();
 
-    println!("{}", a.to_string());
+    println!("{}", a);
     let b = a.transpose();
-    println!("{}", b.to_string());
+    println!("{}", b);
 
     assert_eq!(b.shape, Shape::Matrix([3, 2]));
-    assert_eq!(b.as_slice(), &[1, 4, 2, 5, 3, 6], "{}", b.to_string());
+    assert_eq!(b.as_slice(), &[1, 4, 2, 5, 3, 6], "{}", b);
+}
+
+#[test]
+fn test_vector_transpose_is_noop() {
+    let a = NdArray::new_vector(vec![1, 2, 3, 4]);
+
+    let b = a.clone().transpose();
+
+    assert_eq!(b.shape(), &Shape::Vector([4]));
+    assert_eq!(b.as_slice(), a.as_slice());
 }
 
 #[test]
@@ -248,7 +288,7 @@ fn test_tensor_transpose() {
     )
     .unwrap();
 
-    println!("{}", a.to_string());
+    println!("{}", a);
     let b = a.transpose();
 
     assert_eq!(b.shape, Shape::Tensor(SmallVec::from_slice(&[4, 3, 2])));
@@ -256,7 +296,7 @@ fn test_tensor_transpose() {
         b.as_slice(),
         &[1, 4, 2, 5, 3, 6, 1, 4, 2, 5, 3, 6, 1, 4, 2, 5, 3, 6, 1, 4, 2, 5, 3, 6],
         "\n{}",
-        b.to_string()
+        b
     );
 }
 
@@ -325,7 +365,7 @@ fn test_col_iter_returns_one_scalar() {
     let s = c.next().expect("expected one");
 
     assert_eq!(s[0], 42.69);
-    assert!(matches!(c.next(), None));
+    assert!(c.next().is_none());
 }
 
 #[test]
@@ -342,3 +382,402 @@ fn test_iter_rows_returns_all_items_in_vec() {
 
     assert_eq!(count, 1);
 }
+
+#[test]
+fn test_gather_scatter_roundtrip() {
+    let a = NdArray::new_with_values([2, 3], Data::from_slice(&[1, 2, 3, 4, 5, 6])).unwrap();
+    let index = NdArray::new_with_values([2, 2], Data::from_slice(&[2, 0, 1, 1])).unwrap();
+
+    let b = a.gather(1, &index).unwrap();
+    assert_eq!(b.shape, Shape::Matrix([2, 2]));
+    assert_eq!(b.as_slice(), &[3, 1, 5, 5]);
+
+    let mut c = NdArray::new_default([2, 3]);
+    c.scatter(1, &index, &b).unwrap();
+    assert_eq!(c.as_slice(), &[1, 0, 3, 0, 5, 0]);
+}
+
+#[test]
+fn test_gather_out_of_bounds() {
+    let a = NdArray::new_with_values([2, 3], Data::from_slice(&[1, 2, 3, 4, 5, 6])).unwrap();
+    let index = NdArray::new_with_values([2, 1], Data::from_slice(&[0, 3])).unwrap();
+
+    let err = a.gather(1, &index).unwrap_err();
+    assert!(matches!(
+        err,
+        NdArrayError::IndexOutOfBounds {
+            index: 3,
+            axis: 1,
+            size: 3
+        }
+    ));
+}
+
+#[test]
+fn test_delete_column() {
+    let a = NdArray::new_with_values([2, 3], Data::from_slice(&[1, 2, 3, 4, 5, 6])).unwrap();
+
+    let b = a.delete(1, &[1]).unwrap();
+
+    assert_eq!(b.shape, Shape::Matrix([2, 2]));
+    assert_eq!(b.as_slice(), &[1, 3, 4, 6]);
+}
+
+#[test]
+fn test_insert_rows() {
+    let a = NdArray::new_with_values([2, 3], Data::from_slice(&[1, 2, 3, 4, 5, 6])).unwrap();
+    let rows = NdArray::new_with_values([2, 3], Data::from_slice(&[7, 8, 9, 0, 0, 0])).unwrap();
+
+    let b = a.insert(0, &[2, 0], &rows).unwrap();
+
+    assert_eq!(b.shape, Shape::Matrix([4, 3]));
+    assert_eq!(b.as_slice(), &[0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn test_insert_scalar_into_tensor() {
+    let a = NdArray::new_with_values(&[2, 2, 2][..], (0..8).collect()).unwrap();
+
+    let b = a.insert(1, &[1], &NdArray::new_scalar(-1)).unwrap();
+
+    assert_eq!(b.shape, Shape::Tensor(SmallVec::from_slice(&[2, 3, 2])));
+    assert_eq!(b.as_slice(), &[0, 1, -1, -1, 2, 3, 4, 5, -1, -1, 6, 7]);
+}
+
+#[test]
+fn test_tiled_matmul_within_budget() {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+    let [m, k, n] = [37u32, 53, 29];
+    let a: NdArray<i64> =
+        NdArray::new_with_values([m, k], (0..m * k).map(|_| rng.gen_range(-10, 10)).collect())
+            .unwrap();
+    let b: NdArray<i64> =
+        NdArray::new_with_values([k, n], (0..k * n).map(|_| rng.gen_range(-10, 10)).collect())
+            .unwrap();
+
+    // the full problem would not fit into the budget
+    let budget = 300;
+    let tiles = matrix::MatmulTiles::from_budget(budget);
+    assert!(tiles.span() <= budget);
+    assert!(a.len() + b.len() > budget);
+
+    let mut expected = NdArray::new(0);
+    a.matmul(&b, &mut expected).unwrap();
+    let mut actual = NdArray::new(0);
+    a.matmul_tiled(&b, &mut actual, tiles).unwrap();
+
+    assert_eq!(actual.shape, Shape::Matrix([m, n]));
+    assert_eq!(actual.as_slice(), expected.as_slice());
+}
+
+#[test]
+fn test_diff_of_matrix_rows() {
+    let a = NdArray::new_with_values([2, 4], Data::from_slice(&[1, 2, 4, 7, 0, 0, 3, 3])).unwrap();
+
+    let b = a.diff(1, 1).unwrap();
+    assert_eq!(b.shape(), &Shape::Matrix([2, 3]));
+    assert_eq!(b.as_slice(), &[1, 2, 3, 0, 3, 0]);
+
+    let b = a.diff(1, 5).unwrap();
+    assert_eq!(b.shape(), &Shape::Matrix([2, 0]));
+}
+
+#[test]
+fn test_matmul_empty_contraction_is_zeros() {
+    // K = 0
+    let a = NdArray::<i32>::new_with_values([2, 0], Data::new()).unwrap();
+    let b = NdArray::<i32>::new_with_values([0, 3], Data::new()).unwrap();
+
+    let mut c = NdArray::new_with_values([2, 3], Data::from_slice(&[1; 6])).unwrap();
+    a.matmul(&b, &mut c).unwrap();
+    assert_eq!(c.shape(), &Shape::Matrix([2, 3]));
+    assert_eq!(c.as_slice(), &[0; 6]);
+
+    let mut c = NdArray::new(0);
+    a.matmul_tiled(&b, &mut c, matrix::MatmulTiles::default())
+        .unwrap();
+    assert_eq!(c.shape(), &Shape::Matrix([2, 3]));
+    assert_eq!(c.as_slice(), &[0; 6]);
+}
+
+#[test]
+fn test_matmul_empty_outer_dimensions() {
+    // M = 0
+    let a = NdArray::<i32>::new_with_values([0, 2], Data::new()).unwrap();
+    let b = NdArray::new_with_values([2, 3], Data::from_slice(&[1; 6])).unwrap();
+    let mut c = NdArray::new(0);
+    a.matmul(&b, &mut c).unwrap();
+    assert_eq!(c.shape(), &Shape::Matrix([0, 3]));
+    assert!(c.as_slice().is_empty());
+
+    // N = 0
+    let a = NdArray::new_with_values([3, 2], Data::from_slice(&[1; 6])).unwrap();
+    let b = NdArray::<i32>::new_with_values([2, 0], Data::new()).unwrap();
+    let mut c = NdArray::new(0);
+    a.matmul(&b, &mut c).unwrap();
+    assert_eq!(c.shape(), &Shape::Matrix([3, 0]));
+    assert!(c.as_slice().is_empty());
+
+    let v = NdArray::new_vector(vec![1, 1]);
+    let mut c = NdArray::new(0);
+    v.matmul(&b, &mut c).unwrap();
+    assert_eq!(c.shape(), &Shape::Vector([0]));
+    assert!(c.as_slice().is_empty());
+
+    let mut c = NdArray::new(0);
+    a.matmul_tiled(&b, &mut c, matrix::MatmulTiles::default())
+        .unwrap();
+    assert_eq!(c.shape(), &Shape::Matrix([3, 0]));
+}
+
+#[test]
+fn test_non_square_matrix_vector_mul() {
+    let a = NdArray::new_with_values([3, 2], Data::from_slice(&[1, 2, 3, 4, 5, 6])).unwrap();
+    let v = NdArray::new_vector(vec![1, -1]);
+
+    let mut c = NdArray::new(0);
+    a.matmul(&v, &mut c).unwrap();
+
+    assert_eq!(c.shape(), &Shape::Vector([3]));
+    assert_eq!(c.as_slice(), &[-1, -1, -1]);
+}
+
+#[test]
+fn test_matmul_transposed_operand() {
+    let a = NdArray::new_with_values([3, 2], Data::from_slice(&[1, 2, 3, 4, 5, 6])).unwrap();
+    let a = a.transpose();
+    let b = NdArray::new_with_values([3, 2], Data::from_slice(&[1, 0, 0, 1, 1, 1])).unwrap();
+
+    assert!(a.is_contiguous());
+
+    let mut c = NdArray::new(0);
+    a.matmul(&b, &mut c).unwrap();
+
+    assert_eq!(c.shape(), &Shape::Matrix([2, 2]));
+    assert_eq!(c.as_slice(), &[6, 8, 8, 10]);
+}
+
+#[test]
+fn test_iter_indexed_row_major() {
+    let a = NdArray::new_with_values([2, 2], Data::from_slice(&[1, 2, 3, 4])).unwrap();
+
+    let res: Vec<_> = a.iter_indexed().map(|(i, x)| (i.to_vec(), *x)).collect();
+
+    assert_eq!(
+        res,
+        vec![
+            (vec![0, 0], 1),
+            (vec![0, 1], 2),
+            (vec![1, 0], 3),
+            (vec![1, 1], 4)
+        ]
+    );
+}
+
+#[test]
+fn test_matmul_contracted_dimension_mismatch() {
+    let a = NdArray::new_with_values([2, 3], Data::from_slice(&[1, 2, 3, 4, 5, 6])).unwrap();
+    let b = NdArray::new_with_values([2, 2], Data::from_slice(&[1, 2, 3, 4])).unwrap();
+
+    let mut c = NdArray::new(0);
+    let err = a.matmul(&b, &mut c).unwrap_err();
+
+    assert!(matches!(
+        err,
+        NdArrayError::MatmulShapeMismatch {
+            shape_a: Shape::Matrix([2, 3]),
+            shape_b: Shape::Matrix([2, 2]),
+        }
+    ));
+
+    let t = NdArray::new_with_values(&[2, 2, 2][..], Data::from_slice(&[1; 8])).unwrap();
+    assert!(a.matmul(&t, &mut c).is_err());
+    assert!(t.matmul(&a.clone().transpose(), &mut c).is_err());
+    assert!(t
+        .matmul(&NdArray::new_vector(vec![1, 2, 3]), &mut c)
+        .is_err());
+}
+
+#[test]
+fn test_tensor_non_square_broadcast_matmul() {
+    // two 2x3 matrices
+    let t = NdArray::new_with_values(
+        &[2, 2, 3][..],
+        Data::from_slice(&[1, 2, 3, 4, 5, 6, 0, 1, 0, 1, 0, 1]),
+    )
+    .unwrap();
+    let m = NdArray::new_with_values([3, 1], Data::from_slice(&[1, 1, 1])).unwrap();
+
+    let mut c = NdArray::new(0);
+    t.matmul(&m, &mut c).unwrap();
+    assert_eq!(c.shape(), &Shape::Tensor((&[2, 2, 1][..]).into()));
+    assert_eq!(c.as_slice(), &[6, 15, 1, 2]);
+
+    let v = NdArray::new_vector(vec![1, 1, 1]);
+    t.matmul(&v, &mut c).unwrap();
+    assert_eq!(c.shape(), &Shape::Matrix([2, 2]));
+    assert_eq!(c.as_slice(), &[6, 15, 1, 2]);
+}
+
+#[test]
+fn test_write_region_into_corner() {
+    let mut a = NdArray::new_with_values([4, 4], Data::from_slice(&[0; 16])).unwrap();
+    let b = NdArray::new_with_values([2, 2], Data::from_slice(&[1, 2, 3, 4])).unwrap();
+
+    a.write_region(&[2, 2], &b).unwrap();
+
+    #[rustfmt::skip]
+    assert_eq!(a.as_slice(), &[
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+        0, 0, 1, 2,
+        0, 0, 3, 4,
+    ]);
+
+    assert!(a.write_region(&[3, 2], &b).is_err());
+    assert!(a.write_region(&[0], &b).is_err());
+}
+
+#[test]
+fn test_write_region_tensor() {
+    let mut a = NdArray::new_with_values(&[2, 3, 2][..], Data::from_slice(&[0; 12])).unwrap();
+    let b = NdArray::new_with_values(&[2, 2, 1][..], Data::from_slice(&[1, 2, 3, 4])).unwrap();
+
+    a.write_region(&[0, 1, 1], &b).unwrap();
+
+    assert_eq!(a.as_slice(), &[0, 0, 0, 1, 0, 2, 0, 0, 0, 3, 0, 4]);
+}
+
+#[test]
+fn test_shape_span_overflow_is_an_error() {
+    let shape: &[u32] = &[u32::MAX; 10];
+    let res = NdArray::<f32>::new_with_values(shape, Data::new());
+
+    assert!(matches!(res, Err(NdArrayError::ShapeOverflow(_))));
+    assert_eq!(Shape::from(shape).checked_span(), None);
+}
+
+#[test]
+fn test_from_bytes_size_overflow_is_an_error() {
+    // the span fits into usize, but not the number of bytes
+    let res = NdArray::<f64>::from_bytes([u32::MAX, u32::MAX], &[]);
+
+    assert!(matches!(res, Err(NdArrayError::ShapeOverflow(_))));
+}
+
+#[test]
+fn test_reshape_updates_stride() {
+    let mut a = NdArray::new_with_values(&[2, 3, 2][..], (0..12).collect()).unwrap();
+    a.reshape(&[3, 2, 2][..]);
+
+    assert_eq!(a.stride().as_slice(), &[4, 2, 1]);
+    assert_eq!(a.get(&[2, 1, 0]), Some(&10));
+}
+
+#[test]
+fn test_broadcast_keeps_operand_order() {
+    let v = NdArray::new_vector(vec![10, 20]);
+    let m = NdArray::new_with_values([2, 2], Data::from_slice(&[1, 2, 3, 4])).unwrap();
+
+    assert_eq!(v.sub(&m).unwrap().as_slice(), &[9, 18, 7, 16]);
+    assert_eq!(m.sub(&v).unwrap().as_slice(), &[-9, -18, -7, -16]);
+
+    let t = NdArray::new_with_values(&[2, 2, 2][..], (0..8).collect()).unwrap();
+    let res = m.sub(&t).unwrap();
+    assert_eq!(res.as_slice(), &[1, 1, 1, 1, -3, -3, -3, -3]);
+}
+
+#[test]
+fn test_broadcast_size_one_axes() {
+    let col = NdArray::new_with_values([3, 1], Data::from_slice(&[1, 2, 3])).unwrap();
+    let t = NdArray::new_with_values(&[2, 1, 2][..], Data::from_slice(&[10, 20, 30, 40])).unwrap();
+
+    let res = NdArray::add(&col, &t).unwrap();
+    assert_eq!(res.shape().as_slice(), &[2, 3, 2]);
+    #[rustfmt::skip]
+    assert_eq!(
+        res.as_slice(),
+        &[
+            11, 21, 12, 22, 13, 23,
+            31, 41, 32, 42, 33, 43,
+        ]
+    );
+    assert_eq!(t.sub(&col).unwrap().as_slice()[..2], [9, 19]);
+
+    let m = NdArray::new_with_values([2, 3], Data::from_slice(&[0; 6])).unwrap();
+    assert!(matches!(
+        NdArray::mul(&m, &NdArray::new_vector(vec![1, 2])),
+        Err(NdArrayError::BroadcastMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_split_at_middle_axis() {
+    let a = NdArray::new_with_values(&[2, 3, 2][..], (0..12).collect()).unwrap();
+
+    let (b, c) = a.split_at(1, 1).unwrap();
+
+    assert_eq!(b.shape(), &Shape::Tensor((&[2, 1, 2][..]).into()));
+    assert_eq!(b.as_slice(), &[0, 1, 6, 7]);
+    assert_eq!(c.shape(), &Shape::Tensor((&[2, 2, 2][..]).into()));
+    assert_eq!(c.as_slice(), &[2, 3, 4, 5, 8, 9, 10, 11]);
+
+    let (b, c) = a.split_at(1, 3).unwrap();
+    assert_eq!(b.as_slice(), a.as_slice());
+    assert!(c.as_slice().is_empty());
+
+    assert!(matches!(
+        a.split_at(1, 4),
+        Err(NdArrayError::IndexOutOfBounds { .. })
+    ));
+}
+
+#[test]
+fn test_bytes_round_trip() {
+    let a = NdArray::new_with_values([2, 3], Data::from_slice(&[1.5f32, -2., 0., 4., 1e-3, 6.]))
+        .unwrap();
+    let bytes = a.as_bytes();
+    assert_eq!(bytes.len(), 6 * 4);
+    assert_eq!(&bytes[..4], &1.5f32.to_le_bytes());
+
+    let b = NdArray::<f32>::from_bytes([2, 3], &bytes).unwrap();
+    assert_eq!(a, b);
+
+    assert!(NdArray::<f32>::from_bytes([2, 2], &bytes).is_err());
+}
+
+#[test]
+fn test_clone_shares_values_until_mutated() {
+    let a = NdArray::new_with_values([2, 2], Data::from_slice(&[1, 2, 3, 4])).unwrap();
+    let mut b = a.clone();
+
+    assert_eq!(a.as_slice().as_ptr(), b.as_slice().as_ptr());
+
+    b.as_mut_slice()[0] = 10;
+
+    assert_ne!(a.as_slice().as_ptr(), b.as_slice().as_ptr());
+    assert_eq!(a.as_slice(), &[1, 2, 3, 4]);
+    assert_eq!(b.as_slice(), &[10, 2, 3, 4]);
+
+    // the remaining owner mutates in place
+    let ptr = b.as_slice().as_ptr();
+    b.as_mut_slice()[1] = 20;
+    assert_eq!(b.as_slice().as_ptr(), ptr);
+}
+
+#[test]
+fn test_clone_on_write_through_other_mutators() {
+    let a = NdArray::new_with_values([2, 2], Data::from_slice(&[1, 2, 3, 4])).unwrap();
+
+    let mut b = a.clone();
+    *b.get_mut(&[1, 1]).unwrap() = 0;
+    b.iter_rows_mut().for_each(|row| row[0] = -1);
+    let mut c = a.clone();
+    c.reshape([1, 2]);
+
+    assert_eq!(a.as_slice(), &[1, 2, 3, 4]);
+    assert_eq!(b.as_slice(), &[-1, 2, -1, 0]);
+    assert_eq!(c.as_slice(), &[1, 2]);
+}
@@ -0,0 +1,236 @@
+//! Strided views into the values of an [NdArray]
+//!
+use smallvec::SmallVec;
+
+use super::{buffer::Buffer, shape::Shape, Data, NdArray, NdArrayError};
+
+/// Selection along a single axis of an [ArrayView]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxisSlice {
+    /// Select a single index, the axis is removed
+    Index(u32),
+    /// Select `len` indices starting at `start`, `step` apart. Negative steps walk backwards.
+    Range { start: u32, step: i64, len: u32 },
+}
+
+impl AxisSlice {
+    /// Select the whole axis of length `len`
+    pub fn full(len: u32) -> Self {
+        AxisSlice::Range {
+            start: 0,
+            step: 1,
+            len,
+        }
+    }
+}
+
+/// Read-only view of an [NdArray] with arbitrary strides
+///
+/// Views share the copy-on-write buffer of the array they were created from, so creating and
+/// slicing them does not copy any values. Writing to the source array afterwards copies its
+/// buffer instead of changing the view.
+#[derive(Debug)]
+pub struct ArrayView<T> {
+    values: Buffer<T>,
+    offset: usize,
+    shape: SmallVec<[u32; 4]>,
+    strides: SmallVec<[isize; 4]>,
+}
+
+impl<T> Clone for ArrayView<T> {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            offset: self.offset,
+            shape: self.shape.clone(),
+            strides: self.strides.clone(),
+        }
+    }
+}
+
+impl<T> NdArray<T> {
+    /// View of the whole array
+    pub fn view(&self) -> ArrayView<T> {
+        ArrayView {
+            values: self.values.clone(),
+            offset: 0,
+            shape: self.shape.as_slice().into(),
+            strides: self.stride.iter().map(|s| *s as isize).collect(),
+        }
+    }
+
+    /// View of the part of the array selected by `slices`, see [ArrayView::slice]
+    ///
+    /// ```
+    /// use facet_core::ndarray::{view::AxisSlice, NdArray};
+    ///
+    /// let a = NdArray::new_with_values([3, 4], (0..12).collect()).unwrap();
+    ///
+    /// // a[1:3, ::2]
+    /// let v = a
+    ///     .slice_view(&[
+    ///         AxisSlice::Range { start: 1, step: 1, len: 2 },
+    ///         AxisSlice::Range { start: 0, step: 2, len: 2 },
+    ///     ])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(v.shape(), &[2, 2]);
+    /// assert_eq!(v.iter().copied().collect::<Vec<_>>(), vec![4, 6, 8, 10]);
+    /// ```
+    pub fn slice_view(&self, slices: &[AxisSlice]) -> Result<ArrayView<T>, NdArrayError> {
+        self.view().slice(slices)
+    }
+}
+
+impl<T> ArrayView<T> {
+    pub fn shape(&self) -> &[u32] {
+        &self.shape
+    }
+
+    /// Distance between neighbouring elements of each axis, in elements
+    pub fn strides(&self) -> &[isize] {
+        &self.strides
+    }
+
+    /// Number of elements in the view
+    pub fn len(&self) -> usize {
+        self.shape.iter().map(|x| *x as usize).product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: &[u32]) -> Option<&T> {
+        if index.len() != self.shape.len() || index.iter().zip(&self.shape).any(|(i, n)| i >= n) {
+            return None;
+        }
+        let offset = index
+            .iter()
+            .zip(&self.strides)
+            .fold(self.offset as isize, |o, (i, s)| o + *i as isize * s);
+        self.values.get(offset as usize)
+    }
+
+    /// Narrow the view. `slices` selects along the leading axes, the remaining axes are kept
+    /// whole. [AxisSlice::Index] removes its axis, like indexing a numpy array with an integer.
+    pub fn slice(&self, slices: &[AxisSlice]) -> Result<Self, NdArrayError> {
+        if slices.len() > self.shape.len() {
+            return Err(NdArrayError::BadInput(format!(
+                "Too many indices, got {} for a view of {} dimensions",
+                slices.len(),
+                self.shape.len()
+            )));
+        }
+        let mut offset = self.offset as isize;
+        let mut shape = SmallVec::new();
+        let mut strides = SmallVec::new();
+        for (axis, (size, stride)) in self.shape.iter().zip(&self.strides).enumerate() {
+            let (size, stride) = (*size, *stride);
+            let out_of_bounds = |index: i64| NdArrayError::IndexOutOfBounds { index, axis, size };
+            match slices.get(axis).copied().unwrap_or(AxisSlice::full(size)) {
+                AxisSlice::Index(i) => {
+                    if i >= size {
+                        return Err(out_of_bounds(i as i64));
+                    }
+                    offset += i as isize * stride;
+                }
+                AxisSlice::Range { start, step, len } => {
+                    if len > 0 {
+                        let last = start as i64 + step * (len as i64 - 1);
+                        if start >= size {
+                            return Err(out_of_bounds(start as i64));
+                        }
+                        if last < 0 || last >= size as i64 {
+                            return Err(out_of_bounds(last));
+                        }
+                        offset += start as isize * stride;
+                    }
+                    shape.push(len);
+                    strides.push(step as isize * stride);
+                }
+            }
+        }
+        Ok(Self {
+            values: self.values.clone(),
+            offset: offset as usize,
+            shape,
+            strides,
+        })
+    }
+
+    /// Iterate over the elements in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        let mut index: SmallVec<[u32; 4]> = smallvec::smallvec![0; self.shape.len()];
+        let mut offset = self.offset as isize;
+        (0..self.len()).map(move |_| {
+            let item = &self.values[offset as usize];
+            for d in (0..index.len()).rev() {
+                index[d] += 1;
+                offset += self.strides[d];
+                if index[d] < self.shape[d] {
+                    break;
+                }
+                offset -= self.strides[d] * index[d] as isize;
+                index[d] = 0;
+            }
+            item
+        })
+    }
+
+    /// Copy the elements of the view into a new array
+    pub fn to_array(&self) -> NdArray<T>
+    where
+        T: Clone,
+    {
+        let values: Data<T> = self.iter().cloned().collect();
+        let shape = match self.shape.as_slice() {
+            [n] => Shape::Vector([*n]),
+            shape => Shape::from(shape),
+        };
+        NdArray::new_with_values(shape, values).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_shares_buffer_until_source_is_written() {
+        let mut a = NdArray::new_with_values(&[2, 3, 4][..], (0..24).collect()).unwrap();
+
+        // a[1, ::-1, 2]
+        let v = a
+            .slice_view(&[
+                AxisSlice::Index(1),
+                AxisSlice::Range {
+                    start: 2,
+                    step: -1,
+                    len: 3,
+                },
+                AxisSlice::Index(2),
+            ])
+            .unwrap();
+        assert_eq!(v.shape(), &[3]);
+        assert!(std::ptr::eq(v.get(&[0]).unwrap(), &a.as_slice()[22]));
+
+        a.as_mut_slice()[22] = -1;
+        assert_eq!(v.iter().copied().collect::<Vec<_>>(), vec![22, 18, 14]);
+        assert_eq!(v.to_array().as_slice(), &[22, 18, 14]);
+
+        let w = v
+            .slice(&[AxisSlice::Range {
+                start: 1,
+                step: 1,
+                len: 2,
+            }])
+            .unwrap();
+        assert_eq!(w.iter().copied().collect::<Vec<_>>(), vec![18, 14]);
+
+        assert!(matches!(
+            a.slice_view(&[AxisSlice::Index(2)]),
+            Err(NdArrayError::IndexOutOfBounds { .. })
+        ));
+    }
+}
@@ -18,25 +18,25 @@ fn test_moving_average_matrix() {
     assert_eq!(
         ma.as_slice(),
         &[
-            8.666666666666666,
-            9.666666666666666,
+            26.0 / 3.0,
+            29.0 / 3.0,
             10.0,
             11.0,
-            10.666666666666666,
+            32.0 / 3.0,
             10.0,
-            10.333333333333334,
-            9.666666666666666,
+            31.0 / 3.0,
+            29.0 / 3.0,
             11.0,
             10.0,
             // row 2
-            8.666666666666666,
-            9.666666666666666,
+            26.0 / 3.0,
+            29.0 / 3.0,
             10.0,
             11.0,
-            10.666666666666666,
+            32.0 / 3.0,
             10.0,
-            10.333333333333334,
-            9.666666666666666,
+            31.0 / 3.0,
+            29.0 / 3.0,
             11.0,
             10.0
         ]
@@ -159,3 +159,207 @@ fn test_f64_vec_norm_accuracy() {
         assert!(err < 0.002, "len {} err {}", len, err);
     }
 }
+
+#[test]
+fn test_add_bias() {
+    let mut out = NdArray::new_with_values([4, 3], (0..12).map(|x| x as f32).collect()).unwrap();
+    let bias = NdArray::new_vector(smallvec![0.5f32, -1.0, 2.0]);
+
+    crate::add_bias(&mut out, &bias).unwrap();
+
+    assert_eq!(out.shape(), &Shape::Matrix([4, 3]));
+    for (i, row) in out.iter_rows().enumerate() {
+        let i = (i * 3) as f32;
+        assert_eq!(row, &[i + 0.5, i, i + 4.0]);
+    }
+
+    let bias = NdArray::new_vector(smallvec![1.0f32, 2.0]);
+    assert!(crate::add_bias(&mut out, &bias).is_err());
+}
+
+#[test]
+fn test_cov_and_corrcoef() {
+    // numpy.cov([[1, 2, 3, 4], [2, 4, 6, 9], [4, 3, 2, 2]])
+    let a = NdArray::new_with_values(
+        [3, 4],
+        smallvec![1.0f32, 2.0, 3.0, 4.0, 2.0, 4.0, 6.0, 9.0, 4.0, 3.0, 2.0, 2.0],
+    )
+    .unwrap();
+
+    let c = crate::cov(&a).unwrap();
+
+    assert_eq!(c.shape(), &Shape::Matrix([3, 3]));
+    let expected = [
+        1.6666666, 3.8333333, -1.1666666, //
+        3.8333333, 8.916667, -2.5833333, //
+        -1.1666666, -2.5833333, 0.9166667,
+    ];
+    for (x, y) in c.as_slice().iter().zip(expected.iter()) {
+        assert!((x - y).abs() < 1e-5, "{} != {}", x, y);
+    }
+
+    let r = crate::corrcoef(&a).unwrap();
+    assert_eq!(r.shape(), &Shape::Matrix([3, 3]));
+    for i in 0..3 {
+        assert!((r.as_slice()[i * 3 + i] - 1.0).abs() < 1e-6);
+    }
+    for x in r.as_slice() {
+        assert!(x.abs() <= 1.0 + 1e-6);
+    }
+}
+
+#[test]
+fn test_sum_wide_does_not_overflow() {
+    let a = NdArray::new_with_values([2, 1000], (0..2000).map(|_| i64::MAX).collect()).unwrap();
+
+    let s = crate::sum_wide(&a);
+
+    assert_eq!(s.shape(), &Shape::Vector([2]));
+    assert_eq!(s.as_slice(), &[i64::MAX as i128 * 1000; 2]);
+}
+
+#[test]
+fn test_logsumexp_along_axis() {
+    let a = NdArray::new_with_values([2, 2], smallvec![0.0f32, 0.0, 1000.0, 1000.0]).unwrap();
+
+    let rows = crate::logsumexp_f32(&a, 1).unwrap();
+    assert_eq!(rows.shape(), &Shape::Vector([2]));
+    assert!((rows.as_slice()[0] - 2.0f32.ln()).abs() < 1e-6);
+    assert!((rows.as_slice()[1] - (1000.0 + 2.0f32.ln())).abs() < 1e-3);
+
+    let cols = crate::logsumexp_f32(&a, 0).unwrap();
+    assert_eq!(cols.shape(), &Shape::Vector([2]));
+    for x in cols.as_slice() {
+        assert!((x - 1000.0).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_gelu_modes() {
+    use crate::activation::{dgelu, gelu, GeluApproximation};
+
+    let x = NdArray::new_vector(vec![-1.0f32, 0.0, 1.0, 2.0]);
+    let exact = gelu(&x, GeluApproximation::None);
+    let approx = gelu(&x, GeluApproximation::Tanh);
+    for (a, b) in exact
+        .as_slice()
+        .iter()
+        .zip(&[-0.158_655_3, 0.0, 0.841_344_7, 1.954_499_7])
+    {
+        assert!((a - b).abs() < 1e-5, "{} {}", a, b);
+    }
+    for (a, b) in approx
+        .as_slice()
+        .iter()
+        .zip(&[-0.158_808, 0.0, 0.841_192, 1.954_598])
+    {
+        assert!((a - b).abs() < 1e-5, "{} {}", a, b);
+    }
+
+    let ones = NdArray::new_vector(vec![1.0f32; 4]);
+    let grad = dgelu(&x, &ones, GeluApproximation::None).unwrap();
+    // gelu'(0) = 0.5
+    assert!((grad.as_slice()[1] - 0.5).abs() < 1e-6);
+    assert!(dgelu(
+        &x,
+        &NdArray::new_vector(vec![1.0f32; 3]),
+        GeluApproximation::None
+    )
+    .is_err());
+}
+
+#[test]
+fn test_broadcast_add_axis() {
+    let mut out = NdArray::new_with_values([4, 3], (0..12).collect()).unwrap();
+    let vec = NdArray::new_vector(smallvec![100, 200, 300]);
+
+    crate::broadcast_add_axis(&mut out, &vec, 1).unwrap();
+
+    for (i, row) in out.iter_rows().enumerate() {
+        let i = i as i32 * 3;
+        assert_eq!(row, &[i + 100, i + 201, i + 302]);
+    }
+    assert!(crate::broadcast_add_axis(&mut out, &vec, 0).is_err());
+    assert!(crate::broadcast_add_axis(&mut out, &vec, 2).is_err());
+
+    // middle axis of a tensor
+    let mut out = NdArray::new_with_values(&[2, 2, 2][..], smallvec![0; 8]).unwrap();
+    let vec = NdArray::new_vector(smallvec![1, 2]);
+    crate::broadcast_add_axis(&mut out, &vec, 1).unwrap();
+    assert_eq!(out.as_slice(), &[1, 1, 2, 2, 1, 1, 2, 2]);
+}
+
+#[test]
+fn test_scalar_inputs() {
+    let s = NdArray::new_scalar(5.0f32);
+
+    assert_eq!(crate::sum(&s).as_slice(), &[5.0]);
+    assert_eq!(crate::mean(&s).unwrap().as_slice(), &[5.0]);
+    assert_eq!(s.clone().transpose().as_slice(), &[5.0]);
+
+    let mut out = NdArray::new(0);
+    crate::veclen(&s, &mut out);
+    assert!(matches!(out.shape(), Shape::Scalar(_)));
+    assert_eq!(out.as_slice(), &[5.0]);
+
+    // softmax of a single value is always 1, its gradient vanishes
+    let d = crate::activation::dsoftmax(&NdArray::new_scalar(1.0), &s).unwrap();
+    assert_eq!(d.as_slice(), &[0.0]);
+
+    let mut r = s.clone();
+    r.reshape([1, 1]);
+    assert_eq!(r.shape(), &Shape::Matrix([1, 1]));
+    r.reshape(0);
+    assert!(matches!(r.shape(), Shape::Scalar(_)));
+    assert_eq!(r.as_slice(), &[5.0]);
+}
+
+#[test]
+fn test_mean_var_axis_matches_separate_reductions() {
+    let mut rng = rand::thread_rng();
+    let values: Data<f32> = (0..3 * 4 * 5).map(|_| rng.gen_range(-10.0, 10.0)).collect();
+    let a = NdArray::new_with_values(&[3, 4, 5][..], values).unwrap();
+
+    // `mean` and `std_squared` reduce the last axis
+    let (mean, var) = crate::mean_var_axis(&a, 2).unwrap();
+    let expected_mean = crate::mean(&a).unwrap();
+    let expected_var = crate::std_squared(&a, None).unwrap();
+
+    assert_eq!(mean.shape(), &Shape::Matrix([3, 4]));
+    assert_eq!(var.shape(), &Shape::Matrix([3, 4]));
+    for (x, y) in mean.as_slice().iter().zip(expected_mean.as_slice()) {
+        assert!((x - y).abs() < 1e-4, "{} {}", x, y);
+    }
+    for (x, y) in var.as_slice().iter().zip(expected_var.as_slice()) {
+        assert!((x - y).abs() < 1e-3, "{} {}", x, y);
+    }
+
+    // the first axis, e.g. the batch of a normalization layer, after moving it last
+    let (mean, var) = crate::mean_var_axis(&a, 0).unwrap();
+    let moved = a.move_axes(&[0], &[2]).unwrap();
+    let expected_mean = crate::mean(&moved).unwrap();
+    let expected_var = crate::std_squared(&moved, None).unwrap();
+    assert_eq!(mean.shape(), &Shape::Matrix([4, 5]));
+    for (x, y) in mean.as_slice().iter().zip(expected_mean.as_slice()) {
+        assert!((x - y).abs() < 1e-4, "{} {}", x, y);
+    }
+    for (x, y) in var.as_slice().iter().zip(expected_var.as_slice()) {
+        assert!((x - y).abs() < 1e-3, "{} {}", x, y);
+    }
+}
+
+#[test]
+fn test_sum_is_reproducible() {
+    let mut rng = rand::thread_rng();
+    // magnitudes far apart, so a different summation order changes the rounding
+    let inp: Data<f32> = (0..100_000)
+        .map(|i| rng.gen_range(-1.0, 1.0) * 10f32.powi(i % 8))
+        .collect();
+    let mut inp = NdArray::new_vector(inp);
+    inp.shuffle_axis(0, &mut rng).unwrap();
+
+    let expected = crate::sum(&inp).as_slice()[0].to_bits();
+    for _ in 0..10 {
+        assert_eq!(crate::sum(&inp).as_slice()[0].to_bits(), expected);
+    }
+}
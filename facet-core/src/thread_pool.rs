@@ -0,0 +1,64 @@
+//! Crate-owned rayon thread pool.
+//!
+//! The parallel operations of this crate run on this pool instead of rayon's global pool, so
+//! they don't contend with other rayon users in the host process.
+//!
+use std::sync::{Arc, RwLock};
+
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+static POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
+
+fn build_pool(num_threads: usize) -> Result<ThreadPool, ThreadPoolBuildError> {
+    ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .thread_name(|i| format!("facet-worker-{}", i))
+        .build()
+}
+
+fn pool() -> Arc<ThreadPool> {
+    if let Some(pool) = POOL.read().unwrap().as_ref() {
+        return Arc::clone(pool);
+    }
+    let mut pool = POOL.write().unwrap();
+    let pool = pool.get_or_insert_with(|| {
+        Arc::new(build_pool(0).expect("Failed to build the default thread pool"))
+    });
+    Arc::clone(pool)
+}
+
+/// Replace the thread pool with one running `num_threads` threads.
+///
+/// `0` lets rayon pick the number of threads, by default the number of logical CPUs.
+///
+/// Operations already running finish on the previous pool.
+pub fn set_num_threads(num_threads: usize) -> Result<(), ThreadPoolBuildError> {
+    let pool = build_pool(num_threads)?;
+    *POOL.write().unwrap() = Some(Arc::new(pool));
+    Ok(())
+}
+
+/// Number of threads in the current pool
+pub fn num_threads() -> usize {
+    pool().current_num_threads()
+}
+
+/// Execute `op` inside the thread pool. Parallel iterators used in `op` will run on this pool.
+///
+/// ```
+/// use facet_core::rayon::prelude::*;
+/// use facet_core::thread_pool;
+///
+/// thread_pool::set_num_threads(2).unwrap();
+/// let sum: i32 = thread_pool::install(|| (0..100).into_par_iter().sum());
+///
+/// assert_eq!(sum, 4950);
+/// assert_eq!(thread_pool::num_threads(), 2);
+/// ```
+pub fn install<R, F>(op: F) -> R
+where
+    F: FnOnce() -> R + Send,
+    R: Send,
+{
+    pool().install(op)
+}
@@ -1,4 +1,5 @@
 from . import pyfacet as pf
+from .loss import Loss
 
 class Activation:
     def __init__(self, fn, *, df=None, pred_fn=None):
@@ -43,10 +44,52 @@ class ActivationLinear:
         self.dinputs = dvalues.clone()
 
 
+class ActivationSigmoid:
+    """
+    Sigmoid activation function, backed by `SigmoidLayer`
+    """
+
+    def __init__(self):
+        self.layer = pf.SigmoidLayer()
+
+    def predictions(self):
+        return self.output > pf.scalar(0.5)
+
+    def forward(self, inputs):
+        self.layer.forward(inputs)
+        self.output = self.layer.output
+        return self.output
+
+    def backward(self, dvalues):
+        self.layer.backward(dvalues)
+        self.dinputs = self.layer.dinputs
+
+
+class ActivationSoftmax:
+    """
+    Softmax activation function applied to each row, backed by `SoftmaxLayer`
+    """
+
+    def __init__(self):
+        self.layer = pf.SoftmaxLayer()
+
+    def predictions(self):
+        return pf.argmax(self.output)
+
+    def forward(self, inputs):
+        self.layer.forward(inputs)
+        self.output = self.layer.output
+        return self.output
+
+    def backward(self, dvalues):
+        self.layer.backward(dvalues)
+        self.dinputs = self.layer.dinputs
+
+
 class Activation_Softmax_Loss_CategoricalCrossentropy:
     def __init__(self):
-        self.activation = Activation(pyfacet.softmax)
-        self.loss = Loss(pyfacet.categorical_cross_entropy)
+        self.activation = ActivationSoftmax()
+        self.loss = Loss(pf.categorical_cross_entropy)
 
     def forward(self, inputs, target):
         y = self.activation.forward(inputs)
@@ -64,5 +107,5 @@ class Activation_Softmax_Loss_CategoricalCrossentropy:
         """
         samples = dvalues.shape[0]
         self.dinputs = dvalues - target
-        s = pyfacet.scalar(samples)
+        s = pf.scalar(samples)
         self.dinputs = self.dinputs / s
@@ -1,5 +1,5 @@
 from .pyfacet import binomial, scalar
-from .pyfacet import DenseLayer  # reexport
+from .pyfacet import Conv2d, DenseLayer, Embedding, Upsample  # reexport
 
 
 class InputLayer:
@@ -52,42 +52,28 @@ class Loss:
 
 
 class BinaryCrossentropy(Loss):
-    def forward(self, pred, y):
-        pred_clipped = pf.clip(pred, 1e-7, 1 - 1e-7)
-
-        tp = y * pf.log(pred_clipped)
-        t1 = scalar(1) - y
-        t2 = pf.log(scalar(1) - pred_clipped)
+    """
+    Binary cross entropy, backed by `binary_cross_entropy` and `dbinary_cross_entropy`
+    """
 
-        res = scalar(-1) * (tp + t1 * t2)
-        self.output = res
-        return res
+    def forward(self, pred, y):
+        self.output = pf.binary_cross_entropy(pred, y)
+        return self.output
 
     def backward(self, dvalues, y):
-
-        samples, outputs = dvalues.shape[:2]
-
-        # clip data to prevent division by zero
-        clipped_dvalues = pf.clip(dvalues, 1e-7, 1 - 1e-7)
-        self.dinputs = (
-            scalar(-1)
-            * (y / clipped_dvalues - (scalar(1) - y) / (scalar(1) - clipped_dvalues))
-            / scalar(outputs)
-        )
-
-        self.dinputs = self.dinputs / scalar(samples)
+        self.dinputs = pf.dbinary_cross_entropy(dvalues, y)
+        return self.dinputs
 
 
 class MeanSquaredError(Loss):
+    """
+    Mean squared error, backed by `mean_squared_error` and `dmean_squared_error`
+    """
+
     def forward(self, pred, y):
-        sample_losses = pf.mean((y - pred) ** 2)
-        # flatten
-        sample_losses = sample_losses.reshape([pred.shape[0]])
-        self.output = sample_losses
+        self.output = pf.mean_squared_error(pred, y)
         return self.output
 
     def backward(self, dvalues, y):
-        samples, outputs = dvalues.shape
-
-        self.dinputs = scalar(-2) * (y - dvalues) / scalar(outputs)
-        self.dinputs /= scalar(samples)
+        self.dinputs = pf.dmean_squared_error(dvalues, y)
+        return self.dinputs
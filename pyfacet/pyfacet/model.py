@@ -2,6 +2,7 @@ import progressbar
 from uuid import uuid4
 
 from .layer import InputLayer
+from .pyfacet import load_weights, save_weights
 
 
 class Model:
@@ -114,3 +115,15 @@ class Model:
                     print(
                         f"Validation  Loss: {data_loss:.16f} Accuracy: {accuracy:.16f}"
                     )
+
+    def save_weights(self, path):
+        """
+        saves the parameters of the trainable layers to the file at `path`
+        """
+        save_weights(path, self.trainable)
+
+    def load_weights(self, path):
+        """
+        loads the parameters saved by `save_weights` into the trainable layers
+        """
+        load_weights(path, self.trainable)
@@ -1,8 +1,44 @@
 from .pyfacet import scalar, zeros, sqrt
 
 
+class GradientAccumulator:
+    """
+    Sums the gradients of the layers over `steps` updates, so the optimizers only apply every
+    `steps`-th update. The update equals a single update with the summed gradients, divide the
+    loss by `steps` to apply the average instead.
+    """
+
+    def __init__(self, steps=1):
+        assert steps >= 1, "accumulation_steps must be at least 1"
+        self.steps = steps
+        self.calls = 0
+        self.grads = {}
+
+    def tick(self):
+        """
+        Count an update, return whether the accumulated gradients are applied in this update
+        """
+        self.calls += 1
+        return self.calls % self.steps == 0
+
+    def gradients(self, layer, params):
+        """
+        Add the gradients of `params`, the parameters of `layer`, return the sums to apply or
+        `None` while accumulating
+        """
+        grads = [p.grad for p in params]
+        if layer.id in self.grads:
+            grads = [acc + g for acc, g in zip(self.grads.pop(layer.id), grads)]
+        if self.calls % self.steps != 0:
+            self.grads[layer.id] = grads
+            return None
+        return grads
+
+
 class SGD:
-    def __init__(self, *, learning_rate, decay=0.0, momentum=None):
+    def __init__(
+        self, *, learning_rate, decay=0.0, momentum=None, accumulation_steps=1
+    ):
         self.initial_lr = learning_rate
         self.lr = scalar(learning_rate)
         self.decay = decay
@@ -11,36 +47,50 @@ class SGD:
             self.momentum = scalar(momentum)
         else:
             self.momentum = momentum
+        self.momentums = {}
+        self.accumulator = GradientAccumulator(accumulation_steps)
 
     def pre_update(self):
+        if not self.accumulator.tick():
+            return
         self.lr = scalar(self.initial_lr * (1.0 / (1 + self.decay * self.iters)))
         self.iters += 1
 
     def update_params(self, layer):
-        # calculate deltas
-        if self.momentum is not None:
-            # SGD with momentum
-            if not hasattr(layer, "weight_momentums"):
-                layer.weight_momentums = zeros(layer.weights.shape)
-                layer.bias_momentums = zeros(layer.biases.shape)
-
-            nabla_w = self.momentum * layer.weight_momentums - self.lr * layer.dweights
-            layer.weight_momentums = nabla_w
-            nabla_b = self.momentum * layer.bias_momentums - self.lr * layer.dbiases
-            layer.bias_momentums = nabla_b
-        else:
-            # vanilla SGD
-            nabla_w = scalar(-1.0) * self.lr * layer.dweights
-            nabla_b = scalar(-1.0) * self.lr * layer.dbiases
-
-        # update params
-        layer.weights += nabla_w
-        layer.biases += nabla_b
+        """
+        Update the parameters of `layer` by assigning to the `Parameter`s it returns
+        """
+        params = layer.parameters()
+        grads = self.accumulator.gradients(layer, params)
+        if grads is None:
+            return
+
+        for i, (param, grad) in enumerate(zip(params, grads)):
+            # calculate deltas
+            if self.momentum is not None:
+                # SGD with momentum
+                key = (layer.id, i)
+                if key not in self.momentums:
+                    self.momentums[key] = zeros(grad.shape)
+                delta = self.momentum * self.momentums[key] - self.lr * grad
+                self.momentums[key] = delta
+            else:
+                # vanilla SGD
+                delta = scalar(-1.0) * self.lr * grad
+
+            param.value = param.value + delta
 
 
 class Adam:
     def __init__(
-        self, *, learning_rate, decay=0.0, epsilon=1e-7, beta_1=0.9, beta_2=0.999
+        self,
+        *,
+        learning_rate,
+        decay=0.0,
+        epsilon=1e-7,
+        beta_1=0.9,
+        beta_2=0.999,
+        accumulation_steps=1,
     ):
         self.initial_lr = learning_rate
         self.lr = scalar(learning_rate)
@@ -50,54 +100,43 @@ class Adam:
         self.beta_1 = beta_1
         self.beta_2 = beta_2
 
-        self.weight_cache = {}
-        self.bias_cache = {}
-        self.weight_momentums = {}
-        self.bias_momentums = {}
+        self.cache = {}
+        self.momentums = {}
+        self.accumulator = GradientAccumulator(accumulation_steps)
 
     def pre_update(self):
+        if not self.accumulator.tick():
+            return
         self.lr = scalar(self.initial_lr * (1.0 / (1 + self.decay * self.iters)))
         self.iters += 1
 
     def update_params(self, layer):
-        lid = layer.id
-        if not hasattr(layer, "weight_cache"):
-            self.weight_cache[lid] = zeros(layer.weights.shape)
-            self.bias_cache[lid] = zeros(layer.biases.shape)
-            self.weight_momentums[lid] = zeros(layer.weights.shape)
-            self.bias_momentums[lid] = zeros(layer.biases.shape)
-
-        self.weight_momentums[lid] = (
-            scalar(self.beta_1) * self.weight_momentums[lid]
-        ) + (scalar(1.0 - self.beta_1) * layer.dweights)
-        self.bias_momentums[lid] = (scalar(self.beta_1) * self.bias_momentums[lid]) + (
-            scalar(1.0 - self.beta_1) * layer.dbiases
-        )
-
-        beta1_inv = scalar( 1 - (self.beta_1 ** self.iters))
-        beta2_inv = scalar(
-            1 - self.beta_2
-        )
-        weight_momentum_corrected = self.weight_momentums[lid] / beta1_inv
-        bias_momentum_corrected = self.bias_momentums[lid] / beta1_inv
-
-        self.weight_cache[lid] = scalar(self.beta_2) * self.weight_cache[lid] + beta2_inv * (layer.dweights ** 2)
-        self.bias_cache[lid] = scalar(self.beta_2) * self.bias_cache[lid] + beta2_inv * (layer.dbiases ** 2)
-
-        weight_cache_corrected = self.weight_cache[lid] / scalar(
-            1 - self.beta_2 ** self.iters
-        )
-        bias_cache_corrected = self.bias_cache[lid] / scalar(
-            1 - self.beta_2 ** self.iters
-        )
-
-        layer.weights -= (
-            self.lr
-            * weight_momentum_corrected
-            / (sqrt(weight_cache_corrected) + self.epsilon)
-        )
-        layer.biases -= (
-            self.lr
-            * bias_momentum_corrected
-            / (sqrt(bias_cache_corrected) + self.epsilon)
-        )
+        """
+        Update the parameters of `layer` by assigning to the `Parameter`s it returns
+        """
+        params = layer.parameters()
+        grads = self.accumulator.gradients(layer, params)
+        if grads is None:
+            return
+
+        beta1_inv = scalar(1 - (self.beta_1 ** self.iters))
+        beta2_inv = scalar(1 - self.beta_2)
+        cache_inv = scalar(1 - self.beta_2 ** self.iters)
+
+        for i, (param, grad) in enumerate(zip(params, grads)):
+            key = (layer.id, i)
+            if key not in self.cache:
+                self.cache[key] = zeros(grad.shape)
+                self.momentums[key] = zeros(grad.shape)
+
+            self.momentums[key] = (scalar(self.beta_1) * self.momentums[key]) + (
+                scalar(1.0 - self.beta_1) * grad
+            )
+            momentum_corrected = self.momentums[key] / beta1_inv
+
+            self.cache[key] = scalar(self.beta_2) * self.cache[key] + beta2_inv * (grad ** 2)
+            cache_corrected = self.cache[key] / cache_inv
+
+            param.value = param.value - (
+                self.lr * momentum_corrected / (sqrt(cache_corrected) + self.epsilon)
+            )
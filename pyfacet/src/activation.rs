@@ -1,5 +1,7 @@
 use pyo3::{exceptions::PyValueError, prelude::*, wrap_pyfunction};
 
+use facet_core::activation::{Activation, GeluApproximation};
+
 use crate::pyndarray::NdArrayD;
 
 #[pyfunction]
@@ -47,6 +49,65 @@ pub fn dsigmoid(output: PyRef<'_, NdArrayD>, dvalues: PyRef<'_, NdArrayD>) -> Py
         .map(|inner| NdArrayD { inner })
 }
 
+fn gelu_approximation(approximate: Option<&str>) -> PyResult<GeluApproximation> {
+    match approximate.unwrap_or("none") {
+        "none" => Ok(GeluApproximation::None),
+        "tanh" => Ok(GeluApproximation::Tanh),
+        approximate => Err(PyValueError::new_err(format!(
+            "Unknown approximation {}, expected one of none, tanh",
+            approximate
+        ))),
+    }
+}
+
+/// Parse the name of an activation a layer can fuse into its forward pass
+pub fn parse_activation(name: &str) -> PyResult<Activation> {
+    match name {
+        "relu" => Ok(Activation::Relu),
+        "sigmoid" => Ok(Activation::Sigmoid),
+        "gelu" => Ok(Activation::Gelu(GeluApproximation::None)),
+        "gelu_tanh" => Ok(Activation::Gelu(GeluApproximation::Tanh)),
+        name => Err(PyValueError::new_err(format!(
+            "Unknown activation {}, expected one of relu, sigmoid, gelu, gelu_tanh",
+            name
+        ))),
+    }
+}
+
+/// Inverse of [parse_activation]
+pub fn activation_name(activation: Activation) -> &'static str {
+    match activation {
+        Activation::Relu => "relu",
+        Activation::Sigmoid => "sigmoid",
+        Activation::Gelu(GeluApproximation::None) => "gelu",
+        Activation::Gelu(GeluApproximation::Tanh) => "gelu_tanh",
+    }
+}
+
+/// Gaussian Error Linear Unit, applied elementwise
+///
+/// `approximate` is one of `"none"` (default), computing `x * 0.5 * (1 + erf(x / sqrt(2)))`, or
+/// `"tanh"`, using the tanh approximation.
+#[pyfunction]
+pub fn gelu(inp: PyRef<'_, NdArrayD>, approximate: Option<&str>) -> PyResult<NdArrayD> {
+    let approximate = gelu_approximation(approximate)?;
+    let res = facet_core::activation::gelu(&inp.inner, approximate);
+    Ok(NdArrayD { inner: res })
+}
+
+/// GELU derivative, `inputs` are the inputs of the forward pass
+#[pyfunction]
+pub fn gelu_backward(
+    inputs: PyRef<'_, NdArrayD>,
+    dvalues: PyRef<'_, NdArrayD>,
+    approximate: Option<&str>,
+) -> PyResult<NdArrayD> {
+    let approximate = gelu_approximation(approximate)?;
+    facet_core::activation::dgelu(&inputs.inner, &dvalues.inner, approximate)
+        .map_err(|err| PyValueError::new_err(format!("Failed to perform gelu_backward {}", err)))
+        .map(|inner| NdArrayD { inner })
+}
+
 pub fn setup_module(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(relu, m)?)?;
     m.add_function(wrap_pyfunction!(softmax, m)?)?;
@@ -54,5 +115,7 @@ pub fn setup_module(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(drelu_dz, m)?)?;
     m.add_function(wrap_pyfunction!(sigmoid, m)?)?;
     m.add_function(wrap_pyfunction!(dsigmoid, m)?)?;
+    m.add_function(wrap_pyfunction!(gelu, m)?)?;
+    m.add_function(wrap_pyfunction!(gelu_backward, m)?)?;
     Ok(())
 }
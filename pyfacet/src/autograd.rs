@@ -0,0 +1,215 @@
+//! Reverse-mode automatic differentiation
+//!
+//! Operations on the `Variable`s of a `Tape` are recorded, `Variable.backward` computes the
+//! gradients of a single-value output with respect to every variable it was computed from.
+//!
+//! ```python
+//! tape = Tape()
+//! x = tape.variable(array([[1, 2]]))
+//! w = tape.variable(array([[1], [2]]))
+//! y = x @ w
+//! (y * y).sum().backward()
+//! w.grad
+//! ```
+use facet_core::autograd::{AutogradError, Gradients, Tape as CoreTape, Var};
+use pyo3::{exceptions::PyValueError, prelude::*, AsPyPointer, PyNumberProtocol};
+
+use crate::pyndarray::NdArrayD;
+
+pub fn setup_module(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<Tape>()?;
+    m.add_class::<Variable>()?;
+    Ok(())
+}
+
+fn autograd_error_to_py(err: AutogradError) -> PyErr {
+    PyValueError::new_err(format!("{}", err))
+}
+
+/// Records the operations applied to its variables
+#[pyclass]
+#[derive(Default)]
+pub struct Tape {
+    inner: CoreTape,
+    /// Gradients of the last `backward`
+    grads: Option<Gradients>,
+}
+
+#[pymethods]
+impl Tape {
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a leaf variable holding `value`, e.g. an input or a trainable parameter
+    pub fn variable(slf: &PyCell<Self>, py: Python, value: PyObject) -> PyResult<Variable> {
+        let value = crate::pyobj_to_arrayd(py, value)?;
+        let value = value.borrow(py).inner.clone();
+        let var = slf.borrow_mut().inner.variable(value);
+        Ok(Variable {
+            tape: slf.into(),
+            var,
+        })
+    }
+}
+
+/// A value recorded on a `Tape`
+///
+/// Supports `+`, `-`, `*`, `/` and `@` with variables of the same tape.
+#[pyclass]
+#[derive(Clone)]
+pub struct Variable {
+    tape: Py<Tape>,
+    var: Var,
+}
+
+impl Variable {
+    fn unary(&self, py: Python, op: impl FnOnce(&mut CoreTape, Var) -> Var) -> Variable {
+        let var = op(&mut self.tape.borrow_mut(py).inner, self.var);
+        Variable {
+            tape: self.tape.clone_ref(py),
+            var,
+        }
+    }
+
+    fn fallible(
+        &self,
+        py: Python,
+        op: impl FnOnce(&mut CoreTape, Var) -> Result<Var, AutogradError>,
+    ) -> PyResult<Variable> {
+        let var =
+            op(&mut self.tape.borrow_mut(py).inner, self.var).map_err(autograd_error_to_py)?;
+        Ok(Variable {
+            tape: self.tape.clone_ref(py),
+            var,
+        })
+    }
+
+    fn binary(
+        &self,
+        py: Python,
+        other: &Variable,
+        op: impl FnOnce(&mut CoreTape, Var, Var) -> Result<Var, AutogradError>,
+    ) -> PyResult<Variable> {
+        if self.tape.as_ptr() != other.tape.as_ptr() {
+            return Err(PyValueError::new_err(
+                "Can not combine variables of different tapes",
+            ));
+        }
+        let var = op(&mut self.tape.borrow_mut(py).inner, self.var, other.var)
+            .map_err(autograd_error_to_py)?;
+        Ok(Variable {
+            tape: self.tape.clone_ref(py),
+            var,
+        })
+    }
+}
+
+#[pymethods]
+impl Variable {
+    /// Copies the value.
+    #[getter]
+    pub fn value(&self, py: Python) -> NdArrayD {
+        NdArrayD {
+            inner: self.tape.borrow(py).inner.value(self.var).clone(),
+        }
+    }
+
+    /// Gradient computed by the last `backward` of the tape, `None` if that output does not
+    /// depend on this variable
+    #[getter]
+    pub fn grad(&self, py: Python) -> Option<NdArrayD> {
+        let tape = self.tape.borrow(py);
+        let grad = tape.grads.as_ref()?.get(self.var)?;
+        Some(NdArrayD {
+            inner: grad.clone(),
+        })
+    }
+
+    /// Compute the gradients of this variable with respect to the variables it was computed
+    /// from, available via their `grad`. The variable must hold a single value.
+    pub fn backward(&self, py: Python) -> PyResult<()> {
+        let mut tape = self.tape.borrow_mut(py);
+        let grads = tape
+            .inner
+            .backward(self.var)
+            .map_err(autograd_error_to_py)?;
+        tape.grads = Some(grads);
+        Ok(())
+    }
+
+    pub fn matmul(&self, py: Python, other: Variable) -> PyResult<Variable> {
+        self.binary(py, &other, CoreTape::matmul)
+    }
+
+    /// Sum of all elements, or along `axis` keeping it with length 1
+    #[args(axis = "None")]
+    pub fn sum(&self, py: Python, axis: Option<i64>) -> PyResult<Variable> {
+        let axis = match axis {
+            Some(axis) => axis,
+            None => return Ok(self.unary(py, CoreTape::sum)),
+        };
+        let ndims = self
+            .tape
+            .borrow(py)
+            .inner
+            .value(self.var)
+            .shape()
+            .as_slice()
+            .len();
+        let axis = crate::normalize_axis(axis, ndims)?;
+        self.fallible(py, |tape, var| tape.sum_axis(var, axis))
+    }
+
+    /// Mean of all elements
+    pub fn mean(&self, py: Python) -> Variable {
+        self.unary(py, CoreTape::mean)
+    }
+
+    /// The values with a new shape holding the same number of elements
+    pub fn reshape(&self, py: Python, shape: Vec<u32>) -> PyResult<Variable> {
+        self.fallible(py, |tape, var| tape.reshape(var, shape.as_slice()))
+    }
+
+    pub fn exp(&self, py: Python) -> Variable {
+        self.unary(py, CoreTape::exp)
+    }
+
+    /// Natural logarithm
+    pub fn log(&self, py: Python) -> Variable {
+        self.unary(py, CoreTape::ln)
+    }
+
+    pub fn relu(&self, py: Python) -> Variable {
+        self.unary(py, CoreTape::relu)
+    }
+
+    /// Softmax of each row
+    pub fn softmax(&self, py: Python) -> PyResult<Variable> {
+        self.fallible(py, CoreTape::softmax)
+    }
+}
+
+#[pyproto]
+impl PyNumberProtocol for Variable {
+    fn __add__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Variable> {
+        lhs.binary(lhs.py(), &rhs, CoreTape::add)
+    }
+
+    fn __sub__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Variable> {
+        lhs.binary(lhs.py(), &rhs, CoreTape::sub)
+    }
+
+    fn __mul__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Variable> {
+        lhs.binary(lhs.py(), &rhs, CoreTape::mul)
+    }
+
+    fn __truediv__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Variable> {
+        lhs.binary(lhs.py(), &rhs, CoreTape::div)
+    }
+
+    fn __matmul__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Variable> {
+        lhs.binary(lhs.py(), &rhs, CoreTape::matmul)
+    }
+}
@@ -0,0 +1,46 @@
+//! Opt-in checks for debugging numerical problems, e.g. training instability.
+//!
+//! When enabled, the arithmetic operators, the element-wise math functions and the reductions
+//! scan their outputs and raise `ValueError` naming the operation that produced a NaN or infinite
+//! value. Scanning every output is expensive, so the checks are disabled by default.
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use facet_core::ndarray::NdArray;
+use pyo3::{exceptions::PyValueError, prelude::*, wrap_pyfunction};
+
+static DEBUG_NAN: AtomicBool = AtomicBool::new(false);
+
+pub fn setup_module(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(set_debug_nan, m)?)?;
+    m.add_function(wrap_pyfunction!(get_debug_nan, m)?)?;
+    Ok(())
+}
+
+/// Enable or disable raising `ValueError` when an operation produces NaN or infinite values
+#[pyfunction]
+pub fn set_debug_nan(enabled: bool) {
+    DEBUG_NAN.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether NaN and infinite outputs raise `ValueError`, see `set_debug_nan`
+#[pyfunction]
+pub fn get_debug_nan() -> bool {
+    DEBUG_NAN.load(Ordering::Relaxed)
+}
+
+/// Raise `ValueError` if the checks are enabled and `res`, the output of `op`, holds a NaN or
+/// infinite value
+pub fn check_finite(op: &str, res: &NdArray<f32>) -> PyResult<()> {
+    if !get_debug_nan() {
+        return Ok(());
+    }
+    match res.iter_indexed().find(|(_, x)| !x.is_finite()) {
+        Some((index, x)) => Err(PyValueError::new_err(format!(
+            "`{}` produced {} at index {:?}",
+            op,
+            x,
+            index.as_slice()
+        ))),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,281 @@
+//! [DLPack](https://dmlc.github.io/dlpack/latest/) interchange with other array libraries, e.g.
+//! `torch.from_dlpack(arr)` and `from_dlpack(tensor)`.
+//!
+//! Exports do not copy, the tensor points into the buffer of the source array and holds a reference
+//! to it. Arrays are copy-on-write, so reshaping, writing to or dropping the source afterwards can
+//! not leave the consumer with a dangling pointer. The reverse does not hold, the buffer is shared
+//! with the source and its clones, so consumers must not write to it. `__array__` hands numpy a
+//! read-only view for this reason. Imports copy the values into a new array.
+use std::{convert::TryFrom, ffi::c_void, os::raw::c_char};
+
+use facet_core::ndarray::{shape::Shape, NdArray};
+use pyo3::{
+    exceptions::PyValueError, ffi, prelude::*, types::IntoPyDict, wrap_pyfunction, AsPyPointer,
+};
+
+use crate::pyndarray::{NdArrayD, NdArrayI};
+
+const DLTENSOR_NAME: &[u8] = b"dltensor\0";
+const USED_DLTENSOR_NAME: &[u8] = b"used_dltensor\0";
+
+/// `kDLCPU`
+pub const DEVICE_CPU: i32 = 1;
+
+const CODE_INT: u8 = 0;
+const CODE_FLOAT: u8 = 2;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct DLDevice {
+    device_type: i32,
+    device_id: i32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DLDataType {
+    code: u8,
+    bits: u8,
+    lanes: u16,
+}
+
+#[repr(C)]
+struct DLTensor {
+    data: *mut c_void,
+    device: DLDevice,
+    ndim: i32,
+    dtype: DLDataType,
+    shape: *mut i64,
+    strides: *mut i64,
+    byte_offset: u64,
+}
+
+#[repr(C)]
+struct DLManagedTensor {
+    dl_tensor: DLTensor,
+    manager_ctx: *mut c_void,
+    deleter: Option<unsafe extern "C" fn(*mut DLManagedTensor)>,
+}
+
+/// Element types that can cross the DLPack boundary
+pub trait DlElement: Copy {
+    const DTYPE: (u8, u8);
+}
+
+impl DlElement for f32 {
+    const DTYPE: (u8, u8) = (CODE_FLOAT, 32);
+}
+
+impl DlElement for f64 {
+    const DTYPE: (u8, u8) = (CODE_FLOAT, 64);
+}
+
+impl DlElement for i64 {
+    const DTYPE: (u8, u8) = (CODE_INT, 64);
+}
+
+/// Buffers an exported tensor points into
+struct ExportCtx<T> {
+    array: NdArray<T>,
+    shape: Vec<i64>,
+}
+
+unsafe extern "C" fn delete_managed<T>(managed: *mut DLManagedTensor) {
+    let managed = Box::from_raw(managed);
+    drop(Box::from_raw(managed.manager_ctx as *mut ExportCtx<T>));
+}
+
+/// Frees the tensor, unless a consumer took ownership by renaming the capsule
+unsafe extern "C" fn capsule_destructor(capsule: *mut ffi::PyObject) {
+    let name = DLTENSOR_NAME.as_ptr() as *const c_char;
+    if ffi::PyCapsule_IsValid(capsule, name) == 1 {
+        let managed = ffi::PyCapsule_GetPointer(capsule, name) as *mut DLManagedTensor;
+        if let Some(deleter) = (*managed).deleter {
+            deleter(managed);
+        }
+    }
+}
+
+/// Wrap `arr` into a `dltensor` capsule, sharing its buffer
+pub fn to_dlpack<T: DlElement>(py: Python, arr: &NdArray<T>) -> PyResult<PyObject> {
+    let mut ctx = Box::new(ExportCtx {
+        array: arr.clone(),
+        shape: arr.shape().as_slice().iter().map(|x| *x as i64).collect(),
+    });
+    let (code, bits) = T::DTYPE;
+    let dl_tensor = DLTensor {
+        data: ctx.array.as_slice().as_ptr() as *mut c_void,
+        device: DLDevice {
+            device_type: DEVICE_CPU,
+            device_id: 0,
+        },
+        ndim: ctx.shape.len() as i32,
+        dtype: DLDataType {
+            code,
+            bits,
+            lanes: 1,
+        },
+        shape: ctx.shape.as_mut_ptr(),
+        // compact row-major
+        strides: std::ptr::null_mut(),
+        byte_offset: 0,
+    };
+    let managed = Box::into_raw(Box::new(DLManagedTensor {
+        dl_tensor,
+        manager_ctx: Box::into_raw(ctx) as *mut c_void,
+        deleter: Some(delete_managed::<T>),
+    }));
+    unsafe {
+        let capsule = ffi::PyCapsule_New(
+            managed as *mut c_void,
+            DLTENSOR_NAME.as_ptr() as *const c_char,
+            Some(capsule_destructor),
+        );
+        if capsule.is_null() {
+            delete_managed::<T>(managed);
+        }
+        PyObject::from_owned_ptr_or_err(py, capsule)
+    }
+}
+
+/// Copy the values of `tensor` into an array, following its strides
+unsafe fn copy_tensor<T: DlElement>(tensor: &DLTensor) -> PyResult<NdArray<T>> {
+    let ndim = tensor.ndim as usize;
+    let shape: &[i64] = if ndim == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(tensor.shape, ndim)
+    };
+    let dims = shape
+        .iter()
+        .map(|x| {
+            u32::try_from(*x)
+                .map_err(|_| PyValueError::new_err(format!("Unsupported dimension {}", x)))
+        })
+        .collect::<PyResult<Vec<u32>>>()?;
+    let strides: Vec<i64> = if tensor.strides.is_null() || ndim == 0 {
+        facet_core::ndarray::shape::stride_vec(1, &dims)
+            .iter()
+            .map(|x| *x as i64)
+            .collect()
+    } else {
+        std::slice::from_raw_parts(tensor.strides, ndim).to_vec()
+    };
+
+    let span: usize = dims.iter().map(|x| *x as usize).product();
+    let data = (tensor.data as *const u8).add(tensor.byte_offset as usize) as *const T;
+    let mut values = Vec::with_capacity(span);
+    let mut index = vec![0i64; ndim];
+    for _ in 0..span {
+        let offset: i64 = index.iter().zip(strides.iter()).map(|(i, s)| i * s).sum();
+        values.push(*data.offset(offset as isize));
+        // step the index in row-major order
+        for d in (0..ndim).rev() {
+            index[d] += 1;
+            if index[d] < shape[d] {
+                break;
+            }
+            index[d] = 0;
+        }
+    }
+
+    let shape = if ndim == 0 {
+        Shape::Scalar(Default::default())
+    } else if ndim == 1 {
+        Shape::Vector([dims[0]])
+    } else {
+        Shape::from(dims)
+    };
+    NdArray::new_with_values(shape, values.into())
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Create an array from an object implementing `__dlpack__` or from a `dltensor` capsule.
+///
+/// Only CPU tensors of f32 or f64 (returning `NdArrayD`, f64 values are rounded to f32) and i64
+/// (returning `NdArrayI`) are supported. The values are copied.
+#[pyfunction]
+pub fn from_dlpack(py: Python, inp: &PyAny) -> PyResult<PyObject> {
+    let capsule = if inp.hasattr("__dlpack__")? {
+        inp.call_method0("__dlpack__")?
+    } else {
+        inp
+    };
+    let name = DLTENSOR_NAME.as_ptr() as *const c_char;
+    let managed = unsafe {
+        if ffi::PyCapsule_IsValid(capsule.as_ptr(), name) != 1 {
+            return Err(PyValueError::new_err(
+                "Expected an unconsumed dltensor capsule or an object implementing __dlpack__",
+            ));
+        }
+        ffi::PyCapsule_GetPointer(capsule.as_ptr(), name) as *mut DLManagedTensor
+    };
+
+    let tensor = unsafe { &(*managed).dl_tensor };
+    if tensor.device.device_type != DEVICE_CPU {
+        return Err(PyValueError::new_err(format!(
+            "Only CPU tensors are supported, got device type {}",
+            tensor.device.device_type
+        )));
+    }
+    let dtype = (tensor.dtype.code, tensor.dtype.bits);
+    let res = unsafe {
+        match (dtype, tensor.dtype.lanes) {
+            (f32::DTYPE, 1) => {
+                copy_tensor::<f32>(tensor).map(|inner| NdArrayD { inner }.into_py(py))
+            }
+            (f64::DTYPE, 1) => copy_tensor::<f64>(tensor).map(|values| {
+                NdArrayD {
+                    inner: values.map(|x| *x as f32),
+                }
+                .into_py(py)
+            }),
+            (i64::DTYPE, 1) => {
+                copy_tensor::<i64>(tensor).map(|inner| NdArrayI { inner }.into_py(py))
+            }
+            _ => Err(PyValueError::new_err(format!(
+                "Unsupported dtype {:?}, expected f32, f64 or i64",
+                tensor.dtype
+            ))),
+        }
+    }?;
+
+    // take ownership of the tensor and free it, the values are copied
+    unsafe {
+        ffi::PyCapsule_SetName(
+            capsule.as_ptr(),
+            USED_DLTENSOR_NAME.as_ptr() as *const c_char,
+        );
+        if let Some(deleter) = (*managed).deleter {
+            deleter(managed);
+        }
+    }
+    Ok(res)
+}
+
+/// Implementation of `__array__`, a read-only numpy view of `producer` unless `dtype` or `copy`
+/// ask for a copy
+pub fn to_numpy(
+    py: Python,
+    producer: &PyAny,
+    dtype: Option<PyObject>,
+    copy: Option<bool>,
+) -> PyResult<PyObject> {
+    let res = py
+        .import("numpy")?
+        .call_method1("from_dlpack", (producer,))?;
+    if let Some(dtype) = dtype {
+        return Ok(res.call_method1("astype", (dtype,))?.into());
+    }
+    if copy == Some(true) {
+        return Ok(res.call_method0("copy")?.into());
+    }
+    // the view shares the buffer of `producer` and its clones
+    res.call_method("setflags", (), Some([("write", false)].into_py_dict(py)))?;
+    Ok(res.into())
+}
+
+pub fn setup_module(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(from_dlpack, m)?)?;
+    Ok(())
+}
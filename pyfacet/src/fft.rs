@@ -0,0 +1,114 @@
+use std::f64::consts::PI;
+
+use facet_core::ndarray::{shape::Shape, NdArray};
+use num_complex::Complex;
+use pyo3::{exceptions::PyValueError, prelude::*, wrap_pyfunction};
+
+use crate::pyndarray::{NdArrayC, NdArrayD};
+
+/// Radix-2 Cooley-Tukey, `values.len()` must be a power of two
+fn radix2(values: &[Complex<f64>], sign: f64) -> Vec<Complex<f64>> {
+    let n = values.len();
+    if n == 1 {
+        return values.to_vec();
+    }
+    let even: Vec<_> = values.iter().step_by(2).copied().collect();
+    let odd: Vec<_> = values.iter().skip(1).step_by(2).copied().collect();
+    let even = radix2(&even, sign);
+    let odd = radix2(&odd, sign);
+
+    let mut res = vec![Complex::new(0.0, 0.0); n];
+    for k in 0..n / 2 {
+        let t = Complex::from_polar(1.0, sign * 2.0 * PI * k as f64 / n as f64) * odd[k];
+        res[k] = even[k] + t;
+        res[k + n / 2] = even[k] - t;
+    }
+    res
+}
+
+/// Naive O(n^2) DFT, used for lengths that are not a power of two
+fn dft(values: &[Complex<f64>], sign: f64) -> Vec<Complex<f64>> {
+    let n = values.len();
+    (0..n)
+        .map(|k| {
+            values
+                .iter()
+                .enumerate()
+                .map(|(j, x)| {
+                    x * Complex::from_polar(1.0, sign * 2.0 * PI * (j * k % n) as f64 / n as f64)
+                })
+                .sum()
+        })
+        .collect()
+}
+
+fn transform(values: &[Complex<f64>], inverse: bool) -> Vec<Complex<f64>> {
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut res = if values.is_empty() {
+        vec![]
+    } else if values.len().is_power_of_two() {
+        radix2(values, sign)
+    } else {
+        dft(values, sign)
+    };
+    if inverse {
+        let n = values.len() as f64;
+        res.iter_mut().for_each(|x| *x /= n);
+    }
+    res
+}
+
+/// Accepts either an `NdArrayC` or a real `NdArrayD` vector
+fn extract_complex_vector(inp: &PyAny) -> PyResult<Vec<Complex<f64>>> {
+    let values: Vec<Complex<f64>> = if let Ok(inp) = inp.extract::<PyRef<NdArrayC>>() {
+        if !matches!(inp.inner.shape(), Shape::Vector(_)) {
+            return Err(PyValueError::new_err(format!(
+                "fft expects a vector, got shape {:?}",
+                inp.inner.shape()
+            )));
+        }
+        inp.inner.as_slice().to_vec()
+    } else {
+        let inp: PyRef<NdArrayD> = inp.extract()?;
+        if !matches!(inp.inner.shape(), Shape::Vector(_)) {
+            return Err(PyValueError::new_err(format!(
+                "fft expects a vector, got shape {:?}",
+                inp.inner.shape()
+            )));
+        }
+        inp.inner
+            .as_slice()
+            .iter()
+            .map(|x| Complex::new(*x as f64, 0.0))
+            .collect()
+    };
+    Ok(values)
+}
+
+fn to_ndarrayc(values: Vec<Complex<f64>>) -> NdArrayC {
+    let n = values.len() as u32;
+    let inner = NdArray::new_with_values(Shape::Vector([n]), values.into()).unwrap();
+    NdArrayC { inner }
+}
+
+/// 1-D discrete Fourier transform of a vector
+///
+/// Power of two lengths use a radix-2 Cooley-Tukey, other lengths fall back to the O(n^2) DFT.
+#[pyfunction]
+pub fn fft(inp: &PyAny) -> PyResult<NdArrayC> {
+    let values = extract_complex_vector(inp)?;
+    Ok(to_ndarrayc(transform(&values, false)))
+}
+
+/// Inverse of `fft`, scaled by `1/n` so `ifft(fft(x)) == x`
+#[pyfunction]
+pub fn ifft(inp: &PyAny) -> PyResult<NdArrayC> {
+    let values = extract_complex_vector(inp)?;
+    Ok(to_ndarrayc(transform(&values, true)))
+}
+
+pub fn setup_module(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(fft, m)?)?;
+    m.add_function(wrap_pyfunction!(ifft, m)?)?;
+    Ok(())
+}
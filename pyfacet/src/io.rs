@@ -1,10 +1,22 @@
-use crate::pyndarray::NdArrayD;
-use facet_core::ndarray::{Data, NdArray};
-use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict, wrap_pyfunction};
+use crate::{
+    layer::{extract_layer, sequential::Sequential, Layer},
+    pyndarray::{NdArrayD, NdArrayI, PyNdIndex},
+};
+use facet_core::{
+    layer::checkpoint::{load_parameters, read_weights, write_weights, CheckpointError},
+    ndarray::{shape::Shape, Data, LeBytes, NdArray, NdArrayError},
+};
+use pyo3::{
+    exceptions::{PyOSError, PyValueError},
+    prelude::*,
+    types::{PyDict, PyList, PyTuple},
+    wrap_pyfunction, PyNativeType,
+};
 
 use std::{
     collections::HashMap,
-    io::{BufRead, BufReader},
+    fs::File,
+    io::{BufRead, BufReader, BufWriter},
 };
 
 /// The column names in `labels` will be treated as row labels instead of data points.
@@ -121,7 +133,120 @@ pub fn load_csv<'a, 'py>(
     Ok(res)
 }
 
+fn array_from_bytes<T: LeBytes>(shape: Shape, buffer: &[u8]) -> PyResult<NdArray<T>> {
+    NdArray::from_bytes(shape, buffer).map_err(|err: NdArrayError| {
+        PyValueError::new_err(format!("failed to build nd array from buffer {}", err))
+    })
+}
+
+/// Create an array from the raw little-endian bytes of its values, e.g. the output of `tobytes`.
+///
+/// `dtype` is one of `"f32"` (returning `NdArrayD`) or `"i64"` (returning `NdArrayI`). The values
+/// are read in row-major order and `buffer` must hold exactly as many values as `shape` spans.
+#[pyfunction]
+pub fn frombuffer(py: Python, buffer: &[u8], dtype: &str, shape: PyObject) -> PyResult<PyObject> {
+    let shape: PyNdIndex = shape
+        .extract(py)
+        .or_else(|_| PyNdIndex::new(shape.extract(py)?))?;
+    let shape = Shape::from(shape.inner);
+    let res = match dtype {
+        "f32" => NdArrayD {
+            inner: array_from_bytes(shape, buffer)?,
+        }
+        .into_py(py),
+        "i64" => NdArrayI {
+            inner: array_from_bytes(shape, buffer)?,
+        }
+        .into_py(py),
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown dtype {}, expected one of f32, i64",
+                dtype
+            )))
+        }
+    };
+    Ok(res)
+}
+
+fn checkpoint_error_to_py(err: CheckpointError) -> PyErr {
+    match err {
+        CheckpointError::Io(err) => PyOSError::new_err(format!("{}", err)),
+        err => PyValueError::new_err(format!("{}", err)),
+    }
+}
+
+/// Flatten `layers`, a layer, a `Sequential` or a list or tuple of those, into the layers holding
+/// the parameters
+fn collect_layers(layers: &PyAny, out: &mut Vec<Box<dyn Layer>>) -> PyResult<()> {
+    let py = layers.py();
+    let items: Vec<PyObject> = if let Ok(seq) = layers.extract::<PyRef<Sequential>>() {
+        seq.layers(py)
+    } else if let Ok(list) = layers.downcast::<PyList>() {
+        list.iter().map(|l| l.into()).collect()
+    } else if let Ok(tuple) = layers.downcast::<PyTuple>() {
+        tuple.iter().map(|l| l.into()).collect()
+    } else {
+        out.push(extract_layer(layers)?);
+        return Ok(());
+    };
+    for item in items {
+        collect_layers(item.as_ref(py), out)?;
+    }
+    Ok(())
+}
+
+/// Save the trainable parameters of `layers` to the file at `path`, replacing it if it exists.
+///
+/// `layers` is a layer, a `Sequential` or a list of those. The shapes and `f32` values of the
+/// parameters are stored in order, see `load_weights`.
+///
+/// ```python
+/// model = Sequential(DenseLayer(2, 8), ReluLayer(), DenseLayer(8, 2))
+/// save_weights("model.weights", model)
+/// ```
+#[pyfunction]
+pub fn save_weights(py: Python, path: &str, layers: &PyAny) -> PyResult<()> {
+    let mut handles = Vec::new();
+    collect_layers(layers, &mut handles)?;
+    let values = handles
+        .iter()
+        .flat_map(|l| l.parameters(py))
+        .map(|p| p.get(py).map(|p| p.value))
+        .collect::<PyResult<Vec<NdArray<f32>>>>()?;
+    let file = File::create(path)?;
+    write_weights(BufWriter::new(file), &values.iter().collect::<Vec<_>>())
+        .map_err(checkpoint_error_to_py)
+}
+
+/// Load the parameters saved by `save_weights` into `layers`.
+///
+/// `layers` must have the same parameter shapes, in the same order, as the layers saved. Nothing
+/// is loaded if they do not.
+#[pyfunction]
+pub fn load_weights(py: Python, path: &str, layers: &PyAny) -> PyResult<()> {
+    let mut handles = Vec::new();
+    collect_layers(layers, &mut handles)?;
+    let file = File::open(path)?;
+    let values = read_weights(BufReader::new(file)).map_err(checkpoint_error_to_py)?;
+
+    let mut params: Vec<_> = handles.iter().flat_map(|l| l.parameters(py)).collect();
+    // validate and assign copies first, so nothing is loaded if any parameter does not match
+    let mut loaded = params
+        .iter()
+        .map(|p| p.get(py))
+        .collect::<PyResult<Vec<_>>>()?;
+    load_parameters(loaded.iter_mut().collect(), values).map_err(checkpoint_error_to_py)?;
+
+    for (param, loaded) in params.iter_mut().zip(loaded) {
+        param.with_inner(py, |param| param.value = loaded.value)?;
+    }
+    Ok(())
+}
+
 pub fn setup_module(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(load_csv, m)?)?;
+    m.add_function(wrap_pyfunction!(frombuffer, m)?)?;
+    m.add_function(wrap_pyfunction!(save_weights, m)?)?;
+    m.add_function(wrap_pyfunction!(load_weights, m)?)?;
     Ok(())
 }
@@ -1,11 +1,70 @@
 //! Commonly used artificial neural network layer implementations
 //!
 
+pub mod conv2d;
 pub mod dense_layer;
+pub mod embedding;
+pub mod parameter;
+pub mod relu_layer;
+pub mod sequential;
+pub mod sigmoid_layer;
+pub mod softmax_layer;
+pub mod upsample;
 
-use pyo3::prelude::*;
+use crate::pyndarray::NdArrayD;
+use parameter::Parameter;
+use pyo3::{exceptions::PyTypeError, prelude::*};
 
 pub fn setup_module(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<conv2d::Conv2d>()?;
     m.add_class::<dense_layer::DenseLayer>()?;
+    m.add_class::<embedding::Embedding>()?;
+    m.add_class::<parameter::Parameter>()?;
+    m.add_class::<relu_layer::ReluLayer>()?;
+    m.add_class::<sequential::Sequential>()?;
+    m.add_class::<sigmoid_layer::SigmoidLayer>()?;
+    m.add_class::<softmax_layer::SoftmaxLayer>()?;
+    m.add_class::<upsample::Upsample>()?;
     Ok(())
 }
+
+/// Common interface of the layers, used to chain them in a
+/// [Sequential](sequential::Sequential).
+///
+/// Implemented for handles of the layer classes, so the layers stay shared with the Python
+/// objects they were created from.
+pub trait Layer: Send {
+    /// Run the forward pass, returning the output of the layer
+    fn forward(&self, py: Python, inputs: NdArrayD) -> PyResult<NdArrayD>;
+    /// Run the backward pass, returning the gradient of the inputs of the layer
+    fn backward(&self, py: Python, dvalues: NdArrayD) -> PyResult<NdArrayD>;
+    /// Views of the trainable parameters of the layer
+    fn parameters(&self, py: Python) -> Vec<Parameter>;
+    fn to_object(&self, py: Python) -> PyObject;
+}
+
+/// Extract a handle to any of the layer classes
+pub fn extract_layer(obj: &PyAny) -> PyResult<Box<dyn Layer>> {
+    if let Ok(layer) = obj.extract::<Py<dense_layer::DenseLayer>>() {
+        return Ok(Box::new(layer));
+    }
+    if let Ok(layer) = obj.extract::<Py<conv2d::Conv2d>>() {
+        return Ok(Box::new(layer));
+    }
+    if let Ok(layer) = obj.extract::<Py<relu_layer::ReluLayer>>() {
+        return Ok(Box::new(layer));
+    }
+    if let Ok(layer) = obj.extract::<Py<upsample::Upsample>>() {
+        return Ok(Box::new(layer));
+    }
+    if let Ok(layer) = obj.extract::<Py<sigmoid_layer::SigmoidLayer>>() {
+        return Ok(Box::new(layer));
+    }
+    if let Ok(layer) = obj.extract::<Py<softmax_layer::SoftmaxLayer>>() {
+        return Ok(Box::new(layer));
+    }
+    Err(PyTypeError::new_err(format!(
+        "Expected a layer, got {}",
+        obj.get_type().name()?
+    )))
+}
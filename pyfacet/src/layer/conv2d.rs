@@ -0,0 +1,233 @@
+use super::{
+    parameter::{HasParameters, Parameter},
+    Layer,
+};
+use crate::pyndarray::NdArrayD;
+use facet_core::layer::conv2d::{Conv2d as CoreLayer, Padding};
+use facet_core::layer::Module;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// 2D convolution over `[N, C, H, W]` inputs with square `kernel_size x kernel_size` filters
+///
+/// Outputs `[N, out_channels, OH, OW]` where `OH = (H + 2 * padding - kernel_size) / stride + 1`,
+/// `OW` likewise.
+///
+/// `padding` is the number of zeros added to each side, or one of
+///
+/// - `"same"`: pad so that `OH = ceil(H / stride)`, keeping the spatial size for stride 1. An odd
+///   number of zeros puts the extra one at the bottom and right.
+/// - `"valid"`: no padding
+#[pyclass]
+#[derive(Clone)]
+pub struct Conv2d {
+    inner: CoreLayer,
+    id: uuid::Uuid,
+}
+
+fn parse_padding(padding: &PyAny) -> PyResult<Padding> {
+    if let Ok(padding) = padding.extract::<&str>() {
+        return match padding {
+            "same" => Ok(Padding::Same),
+            "valid" => Ok(Padding::Valid),
+            _ => Err(PyValueError::new_err(format!(
+                "Unsupported padding {}, expected a number, same or valid",
+                padding
+            ))),
+        };
+    }
+    Ok(Padding::Fixed(padding.extract()?))
+}
+
+#[pymethods]
+impl Conv2d {
+    #[new]
+    #[args(stride = "1", padding = "None")]
+    pub fn new(
+        in_channels: u32,
+        out_channels: u32,
+        kernel_size: u32,
+        stride: u32,
+        padding: Option<&PyAny>,
+    ) -> PyResult<Self> {
+        let padding = match padding {
+            None => Padding::Valid,
+            Some(padding) => parse_padding(padding)?,
+        };
+        if in_channels == 0 || out_channels == 0 || kernel_size == 0 || stride == 0 {
+            return Err(PyValueError::new_err(
+                "in_channels, out_channels, kernel_size and stride must be positive",
+            ));
+        }
+        Ok(Self {
+            inner: CoreLayer::new(in_channels, out_channels, kernel_size, stride, padding),
+            id: uuid::Uuid::new_v4(),
+        })
+    }
+
+    #[getter]
+    pub fn in_channels(&self) -> u32 {
+        self.inner.in_channels()
+    }
+    #[getter]
+    pub fn out_channels(&self) -> u32 {
+        self.inner.out_channels()
+    }
+    #[getter]
+    pub fn kernel_size(&self) -> u32 {
+        self.inner.kernel_size()
+    }
+    #[getter]
+    pub fn stride(&self) -> u32 {
+        self.inner.stride
+    }
+    /// The number of zeros added to each side, or `"same"` or `"valid"`
+    #[getter]
+    pub fn padding(&self, py: Python) -> PyObject {
+        match self.inner.padding {
+            Padding::Fixed(p) => p.into_py(py),
+            Padding::Same => "same".into_py(py),
+            Padding::Valid => "valid".into_py(py),
+        }
+    }
+
+    /// Shares the buffer of the layer copy-on-write, writing to the result does not change the
+    /// layer. Assign to `biases` or use `parameters` to update them.
+    #[getter]
+    pub fn biases(&self) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.biases.value.clone(),
+        }
+    }
+
+    /// Shares the buffer of the layer copy-on-write, writing to the result does not change the
+    /// layer. Assign to `weights` or use `parameters` to update them. The filters have shape
+    /// `[out_channels, in_channels, kernel_size, kernel_size]`.
+    #[getter]
+    pub fn weights(&self) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.weights.value.clone(),
+        }
+    }
+
+    #[setter]
+    pub fn set_biases(&mut self, b: NdArrayD) -> PyResult<()> {
+        if b.inner.shape() != self.inner.biases.value.shape() {
+            return Err(PyValueError::new_err(format!(
+                "Expected biases of shape {:?}, got {:?}",
+                self.inner.biases.value.shape(),
+                b.inner.shape()
+            )));
+        }
+        self.inner.biases.value = b.inner;
+        Ok(())
+    }
+
+    #[setter]
+    pub fn set_weights(&mut self, w: NdArrayD) -> PyResult<()> {
+        if w.inner.shape() != self.inner.weights.value.shape() {
+            return Err(PyValueError::new_err(format!(
+                "Expected weights of shape {:?}, got {:?}",
+                self.inner.weights.value.shape(),
+                w.inner.shape()
+            )));
+        }
+        self.inner.weights.value = w.inner;
+        Ok(())
+    }
+
+    #[getter]
+    pub fn id(&self) -> String {
+        self.id.to_string()
+    }
+
+    /// Shares the buffer of the layer copy-on-write, writing to the result does not change the
+    /// layer.
+    #[getter]
+    pub fn output(&self) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.output.clone(),
+        }
+    }
+
+    /// Shares the buffer of the layer copy-on-write, writing to the result does not change the
+    /// layer.
+    #[getter]
+    pub fn dweights(&self) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.weights.grad.clone(),
+        }
+    }
+
+    /// Shares the buffer of the layer copy-on-write, writing to the result does not change the
+    /// layer.
+    #[getter]
+    pub fn dbiases(&self) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.biases.grad.clone(),
+        }
+    }
+
+    /// Shares the buffer of the layer copy-on-write, writing to the result does not change the
+    /// layer.
+    #[getter]
+    pub fn dinputs(&self) -> Option<NdArrayD> {
+        self.inner
+            .dinputs
+            .as_ref()
+            .map(|o| NdArrayD { inner: o.clone() })
+    }
+
+    /// Views of the weights and biases, see `Parameter`
+    pub fn parameters(slf: &PyCell<Self>) -> Vec<Parameter> {
+        Parameter::views(slf)
+    }
+
+    /// Reset the gradients of the weights and biases to zeros
+    pub fn zero_grad(&mut self) {
+        self.inner.weights.zero_grad();
+        self.inner.biases.zero_grad();
+    }
+
+    pub fn forward(&mut self, inputs: NdArrayD) -> PyResult<()> {
+        self.inner
+            .forward(inputs.inner)
+            .map_err(|err| PyValueError::new_err(format!("Failed to forward {}", err)))
+    }
+
+    /// Consumes the last inputs replacing them with `None`.
+    pub fn backward(&mut self, dvalues: NdArrayD) -> PyResult<()> {
+        self.inner
+            .backward(dvalues.inner)
+            .map_err(|err| PyValueError::new_err(format!("Failed to back propagate {}", err)))
+    }
+}
+
+impl HasParameters for Conv2d {
+    const PARAMETERS: usize = 2;
+
+    fn parameters_mut(&mut self) -> Vec<&mut facet_core::layer::Parameter> {
+        self.inner.parameters_mut()
+    }
+}
+
+impl Layer for Py<Conv2d> {
+    fn forward(&self, py: Python, inputs: NdArrayD) -> PyResult<NdArrayD> {
+        let mut layer = self.try_borrow_mut(py)?;
+        layer.forward(inputs)?;
+        Ok(layer.output())
+    }
+
+    fn backward(&self, py: Python, dvalues: NdArrayD) -> PyResult<NdArrayD> {
+        let mut layer = self.try_borrow_mut(py)?;
+        layer.backward(dvalues)?;
+        Ok(layer.dinputs().unwrap())
+    }
+
+    fn parameters(&self, py: Python) -> Vec<Parameter> {
+        Parameter::views(self.as_ref(py))
+    }
+
+    fn to_object(&self, py: Python) -> PyObject {
+        self.clone_ref(py).into_py(py)
+    }
+}
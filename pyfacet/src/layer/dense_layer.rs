@@ -1,5 +1,10 @@
+use super::{
+    parameter::{HasParameters, Parameter},
+    Layer,
+};
 use crate::pyndarray::NdArrayD;
 use facet_core::layer::dense_layer::DenseLayer as CoreLayer;
+use facet_core::layer::Module;
 use pyo3::{exceptions::PyValueError, prelude::*};
 
 #[pyclass]
@@ -15,7 +20,8 @@ impl DenseLayer {
         "weight_regularizer_l1=None",
         "weight_regularizer_l2=None",
         "bias_regularizer_l1=None",
-        "bias_regularizer_l2=None"
+        "bias_regularizer_l2=None",
+        "activation=None"
     )]
     pub fn new(
         inputs: u32,
@@ -24,17 +30,31 @@ impl DenseLayer {
         weight_regularizer_l2: Option<f32>,
         bias_regularizer_l1: Option<f32>,
         bias_regularizer_l2: Option<f32>,
+        activation: Option<&str>,
     ) -> PyResult<Self> {
+        let mut inner = CoreLayer::new(inputs, outputs).with_training(
+            weight_regularizer_l1,
+            weight_regularizer_l2,
+            bias_regularizer_l1,
+            bias_regularizer_l2,
+        );
+        if let Some(activation) = activation {
+            inner = inner.with_activation(crate::activation::parse_activation(activation)?);
+        }
         Ok(Self {
-            inner: CoreLayer::new(inputs, outputs).with_training(
-                weight_regularizer_l1,
-                weight_regularizer_l2,
-                bias_regularizer_l1,
-                bias_regularizer_l2,
-            ),
+            inner,
             id: uuid::Uuid::new_v4(),
         })
     }
+
+    /// Name of the activation applied to the output, one of `relu`, `sigmoid`, `gelu` or
+    /// `gelu_tanh`
+    #[getter]
+    pub fn activation(&self) -> Option<&'static str> {
+        self.inner
+            .activation
+            .map(crate::activation::activation_name)
+    }
     #[getter]
     pub fn weight_regularizer_l1(&self) -> Option<f32> {
         self.inner
@@ -70,7 +90,7 @@ impl DenseLayer {
     #[getter]
     pub fn biases(&self) -> NdArrayD {
         NdArrayD {
-            inner: self.inner.biases.clone(),
+            inner: self.inner.biases.value.clone(),
         }
     }
 
@@ -80,18 +100,18 @@ impl DenseLayer {
     #[getter]
     pub fn weights(&self) -> NdArrayD {
         NdArrayD {
-            inner: self.inner.weights.clone(),
+            inner: self.inner.weights.value.clone(),
         }
     }
 
     #[setter]
     pub fn set_biases(&mut self, b: NdArrayD) {
-        self.inner.biases = b.inner;
+        self.inner.biases.value = b.inner;
     }
 
     #[setter]
     pub fn set_weights(&mut self, w: NdArrayD) {
-        self.inner.weights = w.inner;
+        self.inner.weights.value = w.inner;
     }
 
     #[getter]
@@ -114,11 +134,9 @@ impl DenseLayer {
     /// TODO: return view
     #[getter]
     pub fn dweights(&self) -> Option<NdArrayD> {
-        self.inner
-            .training
-            .as_ref()
-            .map(|t| &t.dweights)
-            .map(|o| NdArrayD { inner: o.clone() })
+        self.inner.training.as_ref().map(|_| NdArrayD {
+            inner: self.inner.weights.grad.clone(),
+        })
     }
 
     /// Copies the output.
@@ -126,11 +144,9 @@ impl DenseLayer {
     /// TODO: return view
     #[getter]
     pub fn dbiases(&self) -> Option<NdArrayD> {
-        self.inner
-            .training
-            .as_ref()
-            .map(|t| &t.dbiases)
-            .map(|o| NdArrayD { inner: o.clone() })
+        self.inner.training.as_ref().map(|_| NdArrayD {
+            inner: self.inner.biases.grad.clone(),
+        })
     }
 
     /// Copies the output.
@@ -145,6 +161,17 @@ impl DenseLayer {
             .map(|o| NdArrayD { inner: o.clone() })
     }
 
+    /// Views of the weights and biases, see `Parameter`
+    pub fn parameters(slf: &PyCell<Self>) -> Vec<Parameter> {
+        Parameter::views(slf)
+    }
+
+    /// Reset the gradients of the weights and biases to zeros
+    pub fn zero_grad(&mut self) {
+        self.inner.weights.zero_grad();
+        self.inner.biases.zero_grad();
+    }
+
     pub fn forward(&mut self, inputs: NdArrayD) -> PyResult<()> {
         let inputs = inputs.inner;
         self.inner
@@ -160,3 +187,35 @@ impl DenseLayer {
             .map_err(|err| PyValueError::new_err(format!("Failed to back propagate {}", err)))
     }
 }
+
+impl HasParameters for DenseLayer {
+    const PARAMETERS: usize = 2;
+
+    fn parameters_mut(&mut self) -> Vec<&mut facet_core::layer::Parameter> {
+        self.inner.parameters_mut()
+    }
+}
+
+impl Layer for Py<DenseLayer> {
+    fn forward(&self, py: Python, inputs: NdArrayD) -> PyResult<NdArrayD> {
+        let mut layer = self.try_borrow_mut(py)?;
+        layer.forward(inputs)?;
+        Ok(layer.output())
+    }
+
+    fn backward(&self, py: Python, dvalues: NdArrayD) -> PyResult<NdArrayD> {
+        let mut layer = self.try_borrow_mut(py)?;
+        layer.backward(dvalues)?;
+        layer
+            .dinputs()
+            .ok_or_else(|| PyValueError::new_err("Layer is not set up for training"))
+    }
+
+    fn parameters(&self, py: Python) -> Vec<Parameter> {
+        Parameter::views(self.as_ref(py))
+    }
+
+    fn to_object(&self, py: Python) -> PyObject {
+        self.clone_ref(py).into_py(py)
+    }
+}
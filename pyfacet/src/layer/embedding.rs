@@ -0,0 +1,114 @@
+use super::parameter::{HasParameters, Parameter};
+use crate::pyndarray::NdArrayD;
+use facet_core::layer::embedding::Embedding as CoreLayer;
+use pyo3::{
+    exceptions::{PyIndexError, PyValueError},
+    prelude::*,
+};
+
+/// Maps integer indices to rows of a trainable `[num_embeddings, embedding_dim]` weight matrix.
+///
+/// If `normalize` is set the looked up rows are scaled to unit L2 norm.
+#[pyclass]
+#[derive(Clone)]
+pub struct Embedding {
+    inner: CoreLayer,
+    id: uuid::Uuid,
+}
+
+#[pymethods]
+impl Embedding {
+    #[new]
+    #[args(normalize = "false")]
+    pub fn new(num_embeddings: u32, embedding_dim: u32, normalize: bool) -> Self {
+        Self {
+            inner: CoreLayer::new(num_embeddings, embedding_dim, normalize),
+            id: uuid::Uuid::new_v4(),
+        }
+    }
+
+    #[getter]
+    pub fn normalize(&self) -> bool {
+        self.inner.normalize
+    }
+
+    /// Copies the output.
+    ///
+    /// TODO: return view
+    #[getter]
+    pub fn weights(&self) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.weights.value.clone(),
+        }
+    }
+
+    #[setter]
+    pub fn set_weights(&mut self, w: NdArrayD) {
+        self.inner.weights.value = w.inner;
+    }
+
+    #[getter]
+    pub fn id(&self) -> String {
+        self.id.to_string()
+    }
+
+    /// Copies the output.
+    ///
+    /// TODO: return view
+    #[getter]
+    pub fn output(&self) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.output.clone(),
+        }
+    }
+
+    /// Copies the output.
+    ///
+    /// TODO: return view
+    #[getter]
+    pub fn dweights(&self) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.weights.grad.clone(),
+        }
+    }
+
+    /// View of the weights, see `Parameter`
+    pub fn parameters(slf: &PyCell<Self>) -> Vec<Parameter> {
+        Parameter::views(slf)
+    }
+
+    /// Reset the gradient of the weights to zeros
+    pub fn zero_grad(&mut self) {
+        self.inner.weights.zero_grad();
+    }
+
+    /// Look up the rows of the integer `indices`, returning the output. Its shape is the shape of
+    /// `indices` with the embedding dimension appended.
+    pub fn forward(&mut self, py: Python, indices: PyObject) -> PyResult<NdArrayD> {
+        let indices = crate::pyobj_to_arrayi(py, indices)?;
+        self.inner.forward(indices).map_err(|err| match err {
+            facet_core::layer::embedding::EmbeddingError::LookupFail(_) => {
+                PyIndexError::new_err(format!("Failed to forward {}", err))
+            }
+            _ => PyValueError::new_err(format!("Failed to forward {}", err)),
+        })?;
+        Ok(self.output())
+    }
+
+    /// Scatter `dvalues` back to the gradients of the rows used in the last `forward`.
+    ///
+    /// Consumes the last `inputs` replacing it with `None`.
+    pub fn backward(&mut self, dvalues: NdArrayD) -> PyResult<()> {
+        self.inner
+            .backward(dvalues.inner)
+            .map_err(|err| PyValueError::new_err(format!("Failed to back propagate {}", err)))
+    }
+}
+
+impl HasParameters for Embedding {
+    const PARAMETERS: usize = 1;
+
+    fn parameters_mut(&mut self) -> Vec<&mut facet_core::layer::Parameter> {
+        vec![&mut self.inner.weights]
+    }
+}
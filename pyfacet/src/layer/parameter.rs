@@ -0,0 +1,140 @@
+use crate::pyndarray::NdArrayD;
+use facet_core::layer::Parameter as CoreParameter;
+use pyo3::{exceptions::PyIndexError, prelude::*, PyClass, PyNativeType};
+
+/// Layer classes whose [Parameter]s are views of the parameters of the layer
+pub trait HasParameters: PyClass + 'static {
+    /// Number of trainable parameters of the layer
+    const PARAMETERS: usize;
+
+    fn parameters_mut(&mut self) -> Vec<&mut CoreParameter>;
+}
+
+type LayerAccess = fn(&PyAny, usize, &mut dyn FnMut(&mut CoreParameter)) -> PyResult<()>;
+
+#[derive(Clone)]
+enum Storage {
+    Owned(CoreParameter),
+    /// The `index`-th parameter of `layer`
+    Layer {
+        layer: PyObject,
+        index: usize,
+        access: LayerAccess,
+    },
+}
+
+fn access_layer<T: HasParameters>(
+    layer: &PyAny,
+    index: usize,
+    f: &mut dyn FnMut(&mut CoreParameter),
+) -> PyResult<()> {
+    let layer: &PyCell<T> = layer.downcast()?;
+    let mut layer = layer.try_borrow_mut()?;
+    let param = layer
+        .parameters_mut()
+        .into_iter()
+        .nth(index)
+        .ok_or_else(|| PyIndexError::new_err(format!("The layer has no parameter {}", index)))?;
+    f(param);
+    Ok(())
+}
+
+/// A trainable value and the gradient of the loss with respect to it
+///
+/// The parameters returned by the layers are views, assigning to `value` or `grad` updates the
+/// layer.
+#[pyclass]
+#[derive(Clone)]
+pub struct Parameter {
+    storage: Storage,
+}
+
+impl Parameter {
+    /// Views of all parameters of `layer`, in order
+    pub fn views<T: HasParameters>(layer: &PyCell<T>) -> Vec<Parameter> {
+        let py = layer.py();
+        (0..T::PARAMETERS)
+            .map(|index| Parameter {
+                storage: Storage::Layer {
+                    layer: layer.to_object(py),
+                    index,
+                    access: access_layer::<T>,
+                },
+            })
+            .collect()
+    }
+
+    /// Run `f` on the parameter, in the layer it belongs to if it is a view
+    pub fn with_inner<R>(
+        &mut self,
+        py: Python,
+        f: impl FnOnce(&mut CoreParameter) -> R,
+    ) -> PyResult<R> {
+        match &mut self.storage {
+            Storage::Owned(inner) => Ok(f(inner)),
+            Storage::Layer {
+                layer,
+                index,
+                access,
+            } => {
+                let mut f = Some(f);
+                let mut res = None;
+                access(layer.as_ref(py), *index, &mut |param| {
+                    res = f.take().map(|f| f(param));
+                })?;
+                Ok(res.expect("the parameter was not accessed"))
+            }
+        }
+    }
+
+    /// Copies the parameter
+    pub fn get(&self, py: Python) -> PyResult<CoreParameter> {
+        self.clone().with_inner(py, |param| param.clone())
+    }
+}
+
+#[pymethods]
+impl Parameter {
+    /// Initializes the gradient to zeros with the shape of `value`
+    #[new]
+    pub fn new(value: NdArrayD) -> Self {
+        Self {
+            storage: Storage::Owned(CoreParameter::new(value.inner)),
+        }
+    }
+
+    /// Current value of the parameter, read from the layer if this is a view. The array shares the
+    /// buffer copy-on-write, so writing to it does not change the parameter, assign to `value` to
+    /// update the parameter and the layer.
+    #[getter]
+    pub fn value(&self, py: Python) -> PyResult<NdArrayD> {
+        Ok(NdArrayD {
+            inner: self.get(py)?.value,
+        })
+    }
+
+    /// Current gradient of the parameter, read from the layer if this is a view. The array shares
+    /// the buffer copy-on-write, so writing to it does not change the parameter, assign to `grad`
+    /// to update the parameter and the layer.
+    #[getter]
+    pub fn grad(&self, py: Python) -> PyResult<NdArrayD> {
+        Ok(NdArrayD {
+            inner: self.get(py)?.grad,
+        })
+    }
+
+    #[setter]
+    pub fn set_value(&mut self, py: Python, v: NdArrayD) -> PyResult<()> {
+        self.with_inner(py, |param| param.value = v.inner)
+    }
+
+    #[setter]
+    pub fn set_grad(&mut self, py: Python, g: NdArrayD) -> PyResult<()> {
+        self.with_inner(py, |param| param.grad = g.inner)
+    }
+
+    /// Reset the gradient to zeros with the shape of the value
+    pub fn zero_grad(&mut self, py: Python) -> PyResult<()> {
+        self.with_inner(py, |param| param.zero_grad())
+    }
+}
@@ -0,0 +1,73 @@
+use super::{parameter::Parameter, Layer};
+use crate::pyndarray::NdArrayD;
+use facet_core::layer::relu::Relu as CoreLayer;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// Applies ReLU to its inputs
+#[pyclass]
+#[derive(Clone, Default)]
+pub struct ReluLayer {
+    inner: CoreLayer,
+}
+
+#[pymethods]
+impl ReluLayer {
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copies the output.
+    ///
+    /// TODO: return view
+    #[getter]
+    pub fn output(&self) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.output.clone(),
+        }
+    }
+
+    /// Copies the output.
+    ///
+    /// TODO: return view
+    #[getter]
+    pub fn dinputs(&self) -> Option<NdArrayD> {
+        self.inner
+            .dinputs
+            .as_ref()
+            .map(|o| NdArrayD { inner: o.clone() })
+    }
+
+    pub fn forward(&mut self, inputs: NdArrayD) {
+        self.inner.forward(inputs.inner);
+    }
+
+    /// Consumes the last `inputs` replacing it with `None`.
+    pub fn backward(&mut self, dvalues: NdArrayD) -> PyResult<()> {
+        self.inner
+            .backward(dvalues.inner)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+}
+
+impl Layer for Py<ReluLayer> {
+    fn forward(&self, py: Python, inputs: NdArrayD) -> PyResult<NdArrayD> {
+        let mut layer = self.try_borrow_mut(py)?;
+        layer.forward(inputs);
+        Ok(layer.output())
+    }
+
+    fn backward(&self, py: Python, dvalues: NdArrayD) -> PyResult<NdArrayD> {
+        let mut layer = self.try_borrow_mut(py)?;
+        layer.backward(dvalues)?;
+        Ok(layer.dinputs().unwrap())
+    }
+
+    fn parameters(&self, _py: Python) -> Vec<Parameter> {
+        Vec::new()
+    }
+
+    fn to_object(&self, py: Python) -> PyObject {
+        self.clone_ref(py).into_py(py)
+    }
+}
@@ -0,0 +1,63 @@
+use super::{extract_layer, parameter::Parameter, Layer};
+use crate::pyndarray::NdArrayD;
+use pyo3::{prelude::*, types::PyTuple};
+
+/// Chains layers, feeding the output of each layer into the next one.
+///
+/// The layers are shared with the objects passed to the constructor, their state is updated by
+/// the passes of the container.
+#[pyclass]
+pub struct Sequential {
+    layers: Vec<Box<dyn Layer>>,
+}
+
+#[pymethods]
+impl Sequential {
+    #[new]
+    #[args(layers = "*")]
+    pub fn new(layers: &PyTuple) -> PyResult<Self> {
+        let layers = layers
+            .iter()
+            .map(extract_layer)
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(Self { layers })
+    }
+
+    #[getter]
+    pub fn layers(&self, py: Python) -> Vec<PyObject> {
+        self.layers.iter().map(|l| l.to_object(py)).collect()
+    }
+
+    /// Pipe `inputs` through the layers in order, returning the output of the last layer
+    pub fn forward(&self, py: Python, inputs: NdArrayD) -> PyResult<NdArrayD> {
+        self.layers
+            .iter()
+            .try_fold(inputs, |x, layer| layer.forward(py, x))
+    }
+
+    /// Back propagate `dvalues` through the layers in reverse order, returning the gradient of
+    /// the inputs of the first layer
+    pub fn backward(&self, py: Python, dvalues: NdArrayD) -> PyResult<NdArrayD> {
+        self.layers
+            .iter()
+            .rev()
+            .try_fold(dvalues, |d, layer| layer.backward(py, d))
+    }
+
+    /// Trainable parameters of all layers, in order.
+    ///
+    /// The parameters are views, assigning to them updates the layers.
+    pub fn parameters(&self, py: Python) -> Vec<Parameter> {
+        self.layers.iter().flat_map(|l| l.parameters(py)).collect()
+    }
+
+    /// Save the parameters of the layers to the file at `path`, see `save_weights`
+    pub fn save_weights(slf: &PyCell<Self>, py: Python, path: &str) -> PyResult<()> {
+        crate::io::save_weights(py, path, slf.as_ref())
+    }
+
+    /// Load the parameters saved by `save_weights` into the layers
+    pub fn load_weights(slf: &PyCell<Self>, py: Python, path: &str) -> PyResult<()> {
+        crate::io::load_weights(py, path, slf.as_ref())
+    }
+}
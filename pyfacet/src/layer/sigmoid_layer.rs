@@ -0,0 +1,69 @@
+use super::{parameter::Parameter, Layer};
+use crate::pyndarray::NdArrayD;
+use facet_core::layer::sigmoid::Sigmoid as CoreLayer;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// Applies the sigmoid function to its inputs
+#[pyclass]
+#[derive(Clone, Default)]
+pub struct SigmoidLayer {
+    inner: CoreLayer,
+}
+
+#[pymethods]
+impl SigmoidLayer {
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copies the output.
+    #[getter]
+    pub fn output(&self) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.output.clone(),
+        }
+    }
+
+    /// Copies the output.
+    #[getter]
+    pub fn dinputs(&self) -> Option<NdArrayD> {
+        self.inner
+            .dinputs
+            .as_ref()
+            .map(|o| NdArrayD { inner: o.clone() })
+    }
+
+    pub fn forward(&mut self, inputs: NdArrayD) {
+        self.inner.forward(inputs.inner);
+    }
+
+    /// Consumes the output of the last `forward`, a second call fails until the next `forward`.
+    pub fn backward(&mut self, dvalues: NdArrayD) -> PyResult<()> {
+        self.inner
+            .backward(dvalues.inner)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+}
+
+impl Layer for Py<SigmoidLayer> {
+    fn forward(&self, py: Python, inputs: NdArrayD) -> PyResult<NdArrayD> {
+        let mut layer = self.try_borrow_mut(py)?;
+        layer.forward(inputs);
+        Ok(layer.output())
+    }
+
+    fn backward(&self, py: Python, dvalues: NdArrayD) -> PyResult<NdArrayD> {
+        let mut layer = self.try_borrow_mut(py)?;
+        layer.backward(dvalues)?;
+        Ok(layer.dinputs().unwrap())
+    }
+
+    fn parameters(&self, _py: Python) -> Vec<Parameter> {
+        Vec::new()
+    }
+
+    fn to_object(&self, py: Python) -> PyObject {
+        self.clone_ref(py).into_py(py)
+    }
+}
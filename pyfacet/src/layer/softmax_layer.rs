@@ -0,0 +1,71 @@
+use super::{parameter::Parameter, Layer};
+use crate::pyndarray::NdArrayD;
+use facet_core::layer::softmax::Softmax as CoreLayer;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// Applies softmax to each row of its inputs
+#[pyclass]
+#[derive(Clone, Default)]
+pub struct SoftmaxLayer {
+    inner: CoreLayer,
+}
+
+#[pymethods]
+impl SoftmaxLayer {
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copies the output.
+    #[getter]
+    pub fn output(&self) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.output.clone(),
+        }
+    }
+
+    /// Copies the output.
+    #[getter]
+    pub fn dinputs(&self) -> Option<NdArrayD> {
+        self.inner
+            .dinputs
+            .as_ref()
+            .map(|o| NdArrayD { inner: o.clone() })
+    }
+
+    pub fn forward(&mut self, inputs: NdArrayD) -> PyResult<()> {
+        self.inner
+            .forward(inputs.inner)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+
+    /// Consumes the output of the last `forward`, a second call fails until the next `forward`.
+    pub fn backward(&mut self, dvalues: NdArrayD) -> PyResult<()> {
+        self.inner
+            .backward(dvalues.inner)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+}
+
+impl Layer for Py<SoftmaxLayer> {
+    fn forward(&self, py: Python, inputs: NdArrayD) -> PyResult<NdArrayD> {
+        let mut layer = self.try_borrow_mut(py)?;
+        layer.forward(inputs)?;
+        Ok(layer.output())
+    }
+
+    fn backward(&self, py: Python, dvalues: NdArrayD) -> PyResult<NdArrayD> {
+        let mut layer = self.try_borrow_mut(py)?;
+        layer.backward(dvalues)?;
+        Ok(layer.dinputs().unwrap())
+    }
+
+    fn parameters(&self, _py: Python) -> Vec<Parameter> {
+        Vec::new()
+    }
+
+    fn to_object(&self, py: Python) -> PyObject {
+        self.clone_ref(py).into_py(py)
+    }
+}
@@ -0,0 +1,92 @@
+use super::{parameter::Parameter, Layer};
+use crate::pyndarray::NdArrayD;
+use facet_core::layer::upsample::Upsample as CoreLayer;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// Enlarges the last two axes, e.g. `H` and `W` of `[N, C, H, W]` inputs, by an integer factor
+///
+/// Only the `nearest` mode, replicating each element into a `scale_factor x scale_factor` block,
+/// is supported.
+#[pyclass]
+#[derive(Clone)]
+pub struct Upsample {
+    inner: CoreLayer,
+}
+
+#[pymethods]
+impl Upsample {
+    /// `mode` defaults to `nearest`
+    #[new("mode=None")]
+    pub fn new(scale_factor: u32, mode: Option<&str>) -> PyResult<Self> {
+        if scale_factor == 0 {
+            return Err(PyValueError::new_err("scale_factor must be positive"));
+        }
+        let mode = mode.unwrap_or("nearest");
+        if mode != "nearest" {
+            return Err(PyValueError::new_err(format!(
+                "Unsupported upsampling mode {}, expected nearest",
+                mode
+            )));
+        }
+        Ok(Self {
+            inner: CoreLayer::new(scale_factor),
+        })
+    }
+
+    #[getter]
+    pub fn scale_factor(&self) -> u32 {
+        self.inner.scale_factor
+    }
+
+    /// Copies the output.
+    #[getter]
+    pub fn output(&self) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.output.clone(),
+        }
+    }
+
+    /// Copies the output.
+    #[getter]
+    pub fn dinputs(&self) -> Option<NdArrayD> {
+        self.inner
+            .dinputs
+            .as_ref()
+            .map(|o| NdArrayD { inner: o.clone() })
+    }
+
+    pub fn forward(&mut self, inputs: NdArrayD) -> PyResult<()> {
+        self.inner
+            .forward(inputs.inner)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+
+    /// Consumes the last input shape replacing it with `None`.
+    pub fn backward(&mut self, dvalues: NdArrayD) -> PyResult<()> {
+        self.inner
+            .backward(dvalues.inner)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+}
+
+impl Layer for Py<Upsample> {
+    fn forward(&self, py: Python, inputs: NdArrayD) -> PyResult<NdArrayD> {
+        let mut layer = self.try_borrow_mut(py)?;
+        layer.forward(inputs)?;
+        Ok(layer.output())
+    }
+
+    fn backward(&self, py: Python, dvalues: NdArrayD) -> PyResult<NdArrayD> {
+        let mut layer = self.try_borrow_mut(py)?;
+        layer.backward(dvalues)?;
+        Ok(layer.dinputs().unwrap())
+    }
+
+    fn parameters(&self, _py: Python) -> Vec<Parameter> {
+        Vec::new()
+    }
+
+    fn to_object(&self, py: Python) -> PyObject {
+        self.clone_ref(py).into_py(py)
+    }
+}
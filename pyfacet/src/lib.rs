@@ -1,15 +1,28 @@
 pub mod activation;
+pub mod autograd;
+pub mod debug;
+pub mod dlpack;
+pub mod fft;
 pub mod io;
 pub mod layer;
 pub mod loss;
 pub mod pyndarray;
-use facet_core::rayon::iter::ParallelIterator;
-
-use facet_core::ndarray::{shape::Shape, NdArray};
-use pyndarray::{NdArrayD, NdArrayI, PyNdIndex};
+pub mod random;
+pub mod sparse;
+pub mod stats;
+pub mod ufunc;
+use facet_core::{rayon::iter::ParallelIterator, thread_pool};
+
+use facet_core::ndarray::{shape::Shape, NdArray, NdArrayError};
+use pyndarray::{
+    NdArrayB, NdArrayD, NdArrayI, NdArrayI32, NdArrayU8, NdArrayView, NdEnumerate, PyNdIndex,
+};
 use pyo3::{
-    exceptions::{PyAssertionError, PyValueError},
+    exceptions::{
+        PyAssertionError, PyIndexError, PyOverflowError, PyValueError, PyZeroDivisionError,
+    },
     prelude::*,
+    types::{PyDict, PyFloat, PyLong, PyTuple},
     wrap_pyfunction,
 };
 
@@ -23,9 +36,27 @@ pub fn eye(dims: u32) -> NdArrayD {
     }
 }
 
+/// Integer inputs (`NdArrayI`, `NdArrayI32`, `NdArrayU8`) are converted to float, so functions with
+/// fractional results like `mean` return an `NdArrayD` for them. Views are copied.
 fn pyobj_to_arrayd(py: Python, inp: PyObject) -> PyResult<Py<NdArrayD>> {
     let inp: Py<NdArrayD> = inp
         .extract(py)
+        .or_else(|_| {
+            let inp: PyRef<NdArrayI> = inp.extract(py)?;
+            Py::new(py, inp.as_f32())
+        })
+        .or_else(|_| {
+            let inp: PyRef<NdArrayI32> = inp.extract(py)?;
+            Py::new(py, inp.as_f32())
+        })
+        .or_else(|_| {
+            let inp: PyRef<NdArrayU8> = inp.extract(py)?;
+            Py::new(py, inp.as_f32())
+        })
+        .or_else(|_| {
+            let view: PyRef<NdArrayView> = inp.extract(py)?;
+            pyobj_to_arrayd(py, view.copy(py))
+        })
         .or_else(|_| pyndarray::array(py, inp.extract(py)?)?.extract(py))
         .or_else(|_| inp.extract(py).and_then(|inp| Py::new(py, scalar(inp))))?;
     Ok(inp)
@@ -43,50 +74,159 @@ macro_rules! unwrap_obj {
     };
 }
 
+/// Accepts index arrays or anything convertible to an array of floats, which are truncated
+fn pyobj_to_arrayi(py: Python, inp: PyObject) -> PyResult<NdArray<i64>> {
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        return Ok(inp.inner.clone());
+    }
+    unwrap_obj!(py, inp);
+    Ok(inp.inner.map(|x| *x as i64))
+}
+
+/// Out of bounds indices raise `IndexError`, other errors raise `ValueError`
+fn index_error_to_py(err: NdArrayError) -> PyErr {
+    match err {
+        NdArrayError::IndexOutOfBounds { .. } => PyIndexError::new_err(format!("{}", err)),
+        _ => PyValueError::new_err(format!("{}", err)),
+    }
+}
+
+/// Accepts a single index or a list of indices
+fn extract_indices(py: Python, inp: PyObject) -> PyResult<Vec<u32>> {
+    inp.extract::<u32>(py)
+        .map(|i| vec![i])
+        .or_else(|_| inp.extract::<Vec<u32>>(py))
+}
+
+/// Negative axes count from the last dimension
+fn normalize_axis(axis: i64, ndims: usize) -> PyResult<u32> {
+    let n = ndims as i64;
+    let res = if axis < 0 { axis + n } else { axis };
+    if res < 0 || res >= n {
+        return Err(PyValueError::new_err(format!(
+            "axis {} is out of bounds for an array of {} dimensions",
+            axis, ndims
+        )));
+    }
+    Ok(res as u32)
+}
+
+/// Copy the array to operate on along `axis`. `None` operates on the flattened array, like numpy
+fn array_along_axis(arr: &NdArray<f32>, axis: Option<i64>) -> PyResult<(NdArray<f32>, u32)> {
+    let mut arr = arr.clone();
+    let axis = match axis {
+        Some(axis) => normalize_axis(axis, arr.shape().as_slice().len())?,
+        None => {
+            arr.reshape(arr.len() as u32);
+            0
+        }
+    };
+    Ok((arr, axis))
+}
+
+/// If `keepdims` is set, restore the reduced `axis` of `shape` with length 1, so the result
+/// broadcasts against the input of the reduction
+fn keep_dims_at<T: Default + Clone>(
+    mut res: NdArray<T>,
+    shape: &Shape,
+    axis: u32,
+    keepdims: Option<bool>,
+) -> NdArray<T> {
+    if keepdims.unwrap_or(false) && !shape.as_slice().is_empty() {
+        let mut dims = shape.as_slice().to_vec();
+        dims[axis as usize] = 1;
+        res.reshape(dims);
+    }
+    res
+}
+
+/// Reduce every lane along `axis` of `arr` to a single value with `f`. `axis` defaults to the last
+/// axis, scalars are reduced to themselves. The axis is removed unless `keepdims` is set.
+fn reduce_axis<T: Copy, U: Default + Clone>(
+    arr: &NdArray<T>,
+    axis: Option<i64>,
+    keepdims: Option<bool>,
+    mut f: impl FnMut(&[T]) -> U,
+) -> PyResult<NdArray<U>> {
+    let shape = arr.shape();
+    let ndims = shape.as_slice().len();
+    if ndims == 0 && axis.is_none() {
+        return Ok(arr.map(|x| f(std::slice::from_ref(x))));
+    }
+    let axis = normalize_axis(axis.unwrap_or(-1), ndims)?;
+    let mut res = arr
+        .map_axis(axis, |lane| vec![f(lane)])
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+    if !keepdims.unwrap_or(false) {
+        let mut dims = shape.as_slice().to_vec();
+        dims.remove(axis as usize);
+        res.reshape(dims);
+    }
+    Ok(res)
+}
+
+/// [keep_dims_at] for reductions of the last axis
+fn keep_dims<T: Default + Clone>(
+    res: NdArray<T>,
+    shape: &Shape,
+    keepdims: Option<bool>,
+) -> NdArray<T> {
+    let axis = shape.as_slice().len().saturating_sub(1) as u32;
+    keep_dims_at(res, shape, axis, keepdims)
+}
+
 /// Collapses the last colun into a single index. The index of the largest item
 #[pyfunction]
-pub fn argmax(py: Python, inp: PyObject) -> PyResult<NdArrayI> {
+pub fn argmax(py: Python, inp: PyObject, keepdims: Option<bool>) -> PyResult<NdArrayI> {
     unwrap_obj!(py, inp);
 
-    let res: Vec<i64> = inp
-        .inner
-        .par_iter_rows()
-        .map(|row| {
-            row.iter()
-                .enumerate()
-                .fold(0, |mi, (i, x)| if &row[mi] < x { i } else { mi }) as i64
-        })
-        .collect();
+    let inner = &inp.inner;
+    let res: Vec<i64> = thread_pool::install(|| {
+        inner
+            .par_iter_rows()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .fold(0, |mi, (i, x)| if &row[mi] < x { i } else { mi }) as i64
+            })
+            .collect()
+    });
 
     let shape = inp.inner.shape();
 
     let mut res = NdArray::new_vector(res);
     res.reshape(shape.truncate());
 
-    Ok(NdArrayI { inner: res })
+    Ok(NdArrayI {
+        inner: keep_dims(res, shape, keepdims),
+    })
 }
 
 /// Collapses the last colun into a single index. The index of the largest item
 #[pyfunction]
-pub fn argmin(py: Python, inp: PyObject) -> PyResult<NdArrayI> {
+pub fn argmin(py: Python, inp: PyObject, keepdims: Option<bool>) -> PyResult<NdArrayI> {
     unwrap_obj!(py, inp);
 
-    let res: Vec<i64> = inp
-        .inner
-        .par_iter_rows()
-        .map(|col| {
-            col.iter()
-                .enumerate()
-                .fold(0, |mi, (i, x)| if &col[mi] > x { i } else { mi }) as i64
-        })
-        .collect();
+    let inner = &inp.inner;
+    let res: Vec<i64> = thread_pool::install(|| {
+        inner
+            .par_iter_rows()
+            .map(|col| {
+                col.iter()
+                    .enumerate()
+                    .fold(0, |mi, (i, x)| if &col[mi] > x { i } else { mi }) as i64
+            })
+            .collect()
+    });
 
     let shape = inp.inner.shape();
 
     let mut res = NdArray::new_vector(res);
     res.reshape(shape.truncate());
 
-    Ok(NdArrayI { inner: res })
+    Ok(NdArrayI {
+        inner: keep_dims(res, shape, keepdims),
+    })
 }
 
 #[pyfunction]
@@ -97,7 +237,8 @@ pub fn zeros(py: Python, inp: PyObject) -> PyResult<NdArrayD> {
 
     let shape = Shape::from(inp.inner);
 
-    let res = NdArray::new_default(shape);
+    let res =
+        NdArray::try_new_default(shape).map_err(|err| PyValueError::new_err(format!("{}", err)))?;
 
     Ok(NdArrayD { inner: res })
 }
@@ -110,8 +251,9 @@ pub fn ones(py: Python, inp: PyObject) -> PyResult<NdArrayD> {
 
     let shape = Shape::from(inp.inner);
 
-    let values = (0..shape.span()).map(|_| 1.0).collect();
-    let res = NdArray::new_with_values(shape, values).unwrap();
+    let mut res =
+        NdArray::try_new_default(shape).map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+    res.as_mut_slice().iter_mut().for_each(|x| *x = 1.0);
 
     Ok(NdArrayD { inner: res })
 }
@@ -135,12 +277,268 @@ pub fn diagflat(py: Python, inp: PyObject) -> PyResult<NdArrayD> {
     Ok(NdArrayD { inner: res })
 }
 
+/// Creates an `[n, m]` matrix with ones at and below the `k`-th diagonal and zeros elsewhere.
+/// Like numpy's `tri`.
+///
+/// `m` defaults to `n`. `k = 0` is the main diagonal, `k > 0` is above it, `k < 0` is below it.
+#[pyfunction]
+pub fn tri(n: u32, m: Option<u32>, k: Option<i64>) -> PyResult<NdArrayD> {
+    let m = m.unwrap_or(n);
+    let values = (0..n as usize * m as usize).map(|_| 1.0).collect();
+    let ones = NdArray::new_with_values([n, m], values)
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+
+    ones.tril(k.unwrap_or(0))
+        .map(|inner| NdArrayD { inner })
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Return a copy of `inp` with the elements above the `k`-th diagonal zeroed. Like numpy's
+/// `tril`.
+///
+/// Tensors are treated as a stack of matrices. `NdArrayI` inputs return an `NdArrayI`.
 #[pyfunction]
-pub fn sum(py: Python, inp: PyObject) -> PyResult<NdArrayD> {
+pub fn tril(py: Python, inp: PyObject, k: Option<i64>) -> PyResult<PyObject> {
+    let k = k.unwrap_or(0);
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        return inp
+            .inner
+            .tril(k)
+            .map(|inner| NdArrayI { inner }.into_py(py))
+            .map_err(|err| PyValueError::new_err(format!("{}", err)));
+    }
     unwrap_obj!(py, inp);
+    inp.inner
+        .tril(k)
+        .map(|inner| NdArrayD { inner }.into_py(py))
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
 
-    let res = facet_core::sum(&inp.inner);
-    Ok(NdArrayD { inner: res })
+/// Return a copy of `inp` with the elements below the `k`-th diagonal zeroed. Like numpy's
+/// `triu`.
+///
+/// Tensors are treated as a stack of matrices. `NdArrayI` inputs return an `NdArrayI`.
+#[pyfunction]
+pub fn triu(py: Python, inp: PyObject, k: Option<i64>) -> PyResult<PyObject> {
+    let k = k.unwrap_or(0);
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        return inp
+            .inner
+            .triu(k)
+            .map(|inner| NdArrayI { inner }.into_py(py))
+            .map_err(|err| PyValueError::new_err(format!("{}", err)));
+    }
+    unwrap_obj!(py, inp);
+    inp.inner
+        .triu(k)
+        .map(|inner| NdArrayD { inner }.into_py(py))
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Sum of `lane`, or `None` if it does not fit into i64
+fn checked_sum(lane: &[i64]) -> Option<i64> {
+    lane.iter().try_fold(0i64, |sum, x| sum.checked_add(*x))
+}
+
+/// Sum the rows, or the lanes along `axis` if given.
+///
+/// `NdArrayI` inputs return an `NdArrayI` and raise `OverflowError` if a sum does not fit into
+/// i64, everything else returns an `NdArrayD`.
+#[pyfunction(keepdims = "None", "*", axis = "None")]
+pub fn sum(
+    py: Python,
+    inp: PyObject,
+    keepdims: Option<bool>,
+    axis: Option<i64>,
+) -> PyResult<PyObject> {
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        let overflow = || PyOverflowError::new_err("sum does not fit into i64");
+        let sums = reduce_axis(&inp.inner, axis, keepdims, checked_sum)?;
+        if axis.is_some() {
+            let res = sums.try_map(|x| x.ok_or(())).map_err(|_| overflow())?;
+            return Ok(NdArrayI { inner: res }.into_py(py));
+        }
+        // the rows are checked above, facet_core::sum can not overflow
+        if sums.as_slice().iter().any(Option::is_none) {
+            return Err(overflow());
+        }
+        let res = facet_core::sum(&inp.inner);
+        let res = keep_dims(res, inp.inner.shape(), keepdims);
+        return Ok(NdArrayI { inner: res }.into_py(py));
+    }
+    unwrap_obj!(py, inp);
+
+    let res = match axis {
+        Some(_) => reduce_axis(&inp.inner, axis, keepdims, |lane| lane.iter().sum())?,
+        None => keep_dims(facet_core::sum(&inp.inner), inp.inner.shape(), keepdims),
+    };
+    debug::check_finite("sum", &res)?;
+    Ok(NdArrayD { inner: res }.into_py(py))
+}
+
+/// Lane-wise extremum with `pick`, NaN values propagate. `NdArrayI` inputs keep their type.
+fn extremum_axis(
+    py: Python,
+    inp: PyObject,
+    axis: Option<i64>,
+    keepdims: Option<bool>,
+    pick: fn(f32, f32) -> f32,
+    pick_int: fn(i64, i64) -> i64,
+) -> PyResult<PyObject> {
+    let empty = || PyValueError::new_err("Can not reduce an empty lane");
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        let res = reduce_axis(&inp.inner, axis, keepdims, |lane| {
+            lane.iter().copied().reduce(pick_int)
+        })?;
+        let res = res.try_map(|x| x.ok_or(())).map_err(|_| empty())?;
+        return Ok(NdArrayI { inner: res }.into_py(py));
+    }
+    unwrap_obj!(py, inp);
+
+    let res = reduce_axis(&inp.inner, axis, keepdims, |lane| {
+        lane.iter().copied().reduce(|a, b| {
+            if a.is_nan() || b.is_nan() {
+                f32::NAN
+            } else {
+                pick(a, b)
+            }
+        })
+    })?;
+    let res = res.try_map(|x| x.ok_or(())).map_err(|_| empty())?;
+    Ok(NdArrayD { inner: res }.into_py(py))
+}
+
+/// Largest value of the rows, or of the lanes along `axis` if given. NaN values propagate.
+///
+/// `NdArrayI` inputs return an `NdArrayI`, everything else returns an `NdArrayD`.
+#[pyfunction(keepdims = "None", "*", axis = "None")]
+pub fn amax(
+    py: Python,
+    inp: PyObject,
+    keepdims: Option<bool>,
+    axis: Option<i64>,
+) -> PyResult<PyObject> {
+    extremum_axis(py, inp, axis, keepdims, f32::max, i64::max)
+}
+
+/// Smallest value of the rows, or of the lanes along `axis` if given. NaN values propagate.
+///
+/// `NdArrayI` inputs return an `NdArrayI`, everything else returns an `NdArrayD`.
+#[pyfunction(keepdims = "None", "*", axis = "None")]
+pub fn amin(
+    py: Python,
+    inp: PyObject,
+    keepdims: Option<bool>,
+    axis: Option<i64>,
+) -> PyResult<PyObject> {
+    extremum_axis(py, inp, axis, keepdims, f32::min, i64::min)
+}
+
+/// Restore the reduced axes of `shape` with length 1 if `keepdims` is set. `axis` None means all
+/// axes were reduced.
+fn keep_reduced_dims<T: Default + Clone>(
+    mut res: NdArray<T>,
+    shape: &Shape,
+    axis: Option<u32>,
+    keepdims: Option<bool>,
+) -> NdArray<T> {
+    match axis {
+        Some(axis) => keep_dims_at(res, shape, axis, keepdims),
+        None => {
+            if keepdims.unwrap_or(false) && !shape.as_slice().is_empty() {
+                res.reshape(vec![1; shape.as_slice().len()]);
+            }
+            res
+        }
+    }
+}
+
+/// Product of the elements along `axis`. If `axis` is None the product of all elements is
+/// returned, like numpy. The product of an empty array is 1.
+///
+/// `NdArrayI` inputs return an `NdArrayI` and raise `OverflowError` if a product does not fit
+/// into i64, everything else returns an `NdArrayD`.
+#[pyfunction]
+pub fn prod(
+    py: Python,
+    inp: PyObject,
+    axis: Option<i64>,
+    keepdims: Option<bool>,
+) -> PyResult<PyObject> {
+    let overflow = || PyOverflowError::new_err("prod does not fit into i64");
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        let shape = inp.inner.shape();
+        let (res, axis) = match axis {
+            None => {
+                let res = facet_core::prod_wide(&inp.inner)
+                    .and_then(|x| i64::try_from(x).ok())
+                    .ok_or_else(overflow)?;
+                (NdArray::new_scalar(res), None)
+            }
+            Some(axis) => {
+                let axis = normalize_axis(axis, shape.as_slice().len())?;
+                let res = facet_core::prod_wide_axis(&inp.inner, axis)
+                    .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+                let values = res
+                    .as_slice()
+                    .iter()
+                    .map(|x| x.and_then(|x| i64::try_from(x).ok()))
+                    .collect::<Option<_>>()
+                    .ok_or_else(overflow)?;
+                let res = NdArray::new_with_values(res.shape().clone(), values)
+                    .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+                (res, Some(axis))
+            }
+        };
+        let res = keep_reduced_dims(res, shape, axis, keepdims);
+        return Ok(NdArrayI { inner: res }.into_py(py));
+    }
+    unwrap_obj!(py, inp);
+    let shape = inp.inner.shape();
+    let (res, axis) = match axis {
+        None => (NdArray::new_scalar(facet_core::prod(&inp.inner)), None),
+        Some(axis) => {
+            let axis = normalize_axis(axis, shape.as_slice().len())?;
+            let res = facet_core::prod_axis(&inp.inner, axis)
+                .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+            (res, Some(axis))
+        }
+    };
+    debug::check_finite("prod", &res)?;
+    let res = keep_reduced_dims(res, shape, axis, keepdims);
+    Ok(NdArrayD { inner: res }.into_py(py))
+}
+
+/// Return an array with the shape and type of `inp` filled with `value`.
+///
+/// `NdArrayI` inputs return an `NdArrayI`, everything else returns an `NdArrayD`.
+#[pyfunction]
+pub fn full_like(py: Python, inp: PyObject, value: PyObject) -> PyResult<PyObject> {
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        let value: i64 = value.extract(py)?;
+        let res = NdArrayI {
+            inner: inp.inner.map(|_| value),
+        };
+        return Ok(res.into_py(py));
+    }
+    unwrap_obj!(py, inp);
+    let value: f32 = value.extract(py)?;
+    let res = NdArrayD {
+        inner: inp.inner.map(|_| value),
+    };
+    Ok(res.into_py(py))
+}
+
+/// Return an array of zeros with the shape and type of `inp`
+#[pyfunction]
+pub fn zeros_like(py: Python, inp: PyObject) -> PyResult<PyObject> {
+    full_like(py, inp, 0.into_py(py))
+}
+
+/// Return an array of ones with the shape and type of `inp`
+#[pyfunction]
+pub fn ones_like(py: Python, inp: PyObject) -> PyResult<PyObject> {
+    full_like(py, inp, 1.into_py(py))
 }
 
 /// Scrate a single-value nd-array
@@ -151,12 +549,281 @@ pub fn scalar(s: f32) -> NdArrayD {
     }
 }
 
+/// Insert leading length-1 axes until `arr` has at least `ndim` dimensions
+fn with_min_ndim<T: Default + Clone>(arr: &NdArray<T>, ndim: usize) -> NdArray<T> {
+    let dims = arr.shape().as_slice();
+    let mut res = arr.clone();
+    if dims.len() < ndim {
+        let mut shape = vec![1; ndim - dims.len()];
+        shape.extend_from_slice(dims);
+        res.reshape(shape);
+    }
+    res
+}
+
+fn atleast_nd(py: Python, inp: PyObject, ndim: usize) -> PyResult<PyObject> {
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        let inner = with_min_ndim(&inp.inner, ndim);
+        return Ok(NdArrayI { inner }.into_py(py));
+    }
+    unwrap_obj!(py, inp);
+    let inner = with_min_ndim(&inp.inner, ndim);
+    Ok(NdArrayD { inner }.into_py(py))
+}
+
+/// Return `inp` with at least one dimension, scalars become `[1]` vectors. Like numpy's
+/// `atleast_1d`.
 #[pyfunction]
-pub fn mean(py: Python, inp: PyObject) -> PyResult<NdArrayD> {
+pub fn atleast_1d(py: Python, inp: PyObject) -> PyResult<PyObject> {
+    atleast_nd(py, inp, 1)
+}
+
+/// Return `inp` with at least two dimensions by inserting leading length-1 axes, e.g. `[3]` becomes
+/// `[1, 3]`. Like numpy's `atleast_2d`.
+#[pyfunction]
+pub fn atleast_2d(py: Python, inp: PyObject) -> PyResult<PyObject> {
+    atleast_nd(py, inp, 2)
+}
+
+/// Fold the leading dimensions of `inp` into one, returning the matrix and the folded dimensions
+fn fold_batch<T: Default + Clone>(inp: &NdArray<T>) -> PyResult<(NdArray<T>, Vec<u32>)> {
+    let dims = inp.shape().as_slice();
+    let (last, leading) = dims.split_last().ok_or_else(|| {
+        PyValueError::new_err("flatten_batch expects at least 1 dimension, got a scalar")
+    })?;
+    let rows = leading.iter().product::<u32>();
+    let mut res = inp.clone();
+    res.reshape([rows, *last]);
+    Ok((res, leading.to_vec()))
+}
+
+/// Inverse of [fold_batch]
+fn unfold_batch<T: Default + Clone>(inp: &NdArray<T>, leading: Vec<u32>) -> PyResult<NdArray<T>> {
+    let rows = leading.iter().product::<u32>();
+    let last = match inp.shape().as_slice() {
+        [n, m] if *n == rows => *m,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "Expected a matrix of {} rows, got shape {:?}",
+                rows,
+                inp.shape()
+            )))
+        }
+    };
+    let mut shape = leading;
+    shape.push(last);
+    let mut res = inp.clone();
+    res.reshape(shape);
+    Ok(res)
+}
+
+/// Fold the leading, batch dimensions of `inp` into one, e.g. `[N, T, D]` becomes `[N * T, D]`,
+/// so matrix operations like `matmul` apply to every vector of the batch.
+///
+/// Returns the matrix and the original leading dimensions, to restore them with
+/// `unflatten_batch`. `NdArrayI` inputs return an `NdArrayI`.
+#[pyfunction]
+pub fn flatten_batch(py: Python, inp: PyObject) -> PyResult<(PyObject, Vec<u32>)> {
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        let (inner, leading) = fold_batch(&inp.inner)?;
+        return Ok((NdArrayI { inner }.into_py(py), leading));
+    }
     unwrap_obj!(py, inp);
-    facet_core::mean(&inp.inner)
-        .map(|inner| NdArrayD { inner })
-        .map_err(|err| PyValueError::new_err::<String>(format!("{}", err)))
+    let (inner, leading) = fold_batch(&inp.inner)?;
+    Ok((NdArrayD { inner }.into_py(py), leading))
+}
+
+/// Restore the `leading` dimensions returned by `flatten_batch`, e.g. `[N * T, K]` becomes
+/// `[N, T, K]`. The last dimension of `inp` is kept, so it may differ from the flattened input's.
+#[pyfunction]
+pub fn unflatten_batch(py: Python, inp: PyObject, leading: Vec<u32>) -> PyResult<PyObject> {
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        let inner = unfold_batch(&inp.inner, leading)?;
+        return Ok(NdArrayI { inner }.into_py(py));
+    }
+    unwrap_obj!(py, inp);
+    let inner = unfold_batch(&inp.inner, leading)?;
+    Ok(NdArrayD { inner }.into_py(py))
+}
+
+/// The inner arrays if all of `objs` are `NdArrayI`s
+fn int_arrays(py: Python, objs: &[PyObject]) -> Option<Vec<NdArray<i64>>> {
+    objs.iter()
+        .map(|obj| Some(obj.extract::<PyRef<NdArrayI>>(py).ok()?.inner.clone()))
+        .collect()
+}
+
+fn float_arrays(py: Python, objs: Vec<PyObject>) -> PyResult<Vec<NdArray<f32>>> {
+    objs.into_iter()
+        .map(|obj| Ok(pyobj_to_arrayd(py, obj)?.borrow(py).inner.clone()))
+        .collect()
+}
+
+/// Join a grid of blocks into a matrix. Blocks are promoted to 2 dimensions like `atleast_2d`,
+/// blocks in a row must have the same number of rows and the rows the same number of columns.
+fn assemble_blocks<T: Default + Clone>(grid: &[Vec<NdArray<T>>]) -> PyResult<NdArray<T>> {
+    let mut values = Vec::new();
+    let mut height = 0;
+    let mut width = None;
+    for row in grid {
+        let row: Vec<_> = row.iter().map(|block| with_min_ndim(block, 2)).collect();
+        let dims: Vec<[u32; 2]> = row
+            .iter()
+            .map(|block| match block.shape().as_slice() {
+                [n, m] => Ok([*n, *m]),
+                _ => Err(PyValueError::new_err(format!(
+                    "Blocks may have at most 2 dimensions, got shape {:?}",
+                    block.shape()
+                ))),
+            })
+            .collect::<PyResult<_>>()?;
+        let h = match dims.first() {
+            Some([h, _]) => *h,
+            None => return Err(PyValueError::new_err("Rows of blocks may not be empty")),
+        };
+        if let Some([n, _]) = dims.iter().find(|[n, _]| *n != h) {
+            return Err(PyValueError::new_err(format!(
+                "Blocks in a row must have the same number of rows, got {} and {}",
+                h, n
+            )));
+        }
+        let w = dims.iter().map(|[_, m]| *m).sum::<u32>();
+        if *width.get_or_insert(w) != w {
+            return Err(PyValueError::new_err(format!(
+                "Rows of blocks must have the same number of columns, got {} and {}",
+                width.unwrap(),
+                w
+            )));
+        }
+        for r in 0..h as usize {
+            for (block, [_, m]) in row.iter().zip(dims.iter()) {
+                let m = *m as usize;
+                values.extend_from_slice(&block.as_slice()[r * m..(r + 1) * m]);
+            }
+        }
+        height += h;
+    }
+    let width = width.ok_or_else(|| PyValueError::new_err("Expected at least one block"))?;
+    NdArray::new_with_values([height, width], values.into())
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Assemble a matrix from a nested list of blocks, e.g. `block([[a, b], [c, d]])`. Like numpy's
+/// `block`, limited to 2 dimensions.
+///
+/// A flat list is a single row of blocks. If all blocks are `NdArrayI`s the result is an
+/// `NdArrayI`.
+#[pyfunction]
+pub fn block(py: Python, blocks: PyObject) -> PyResult<PyObject> {
+    let grid: Vec<Vec<PyObject>> = blocks
+        .extract(py)
+        .or_else(|_| blocks.extract(py).map(|row| vec![row]))?;
+    let objs: Vec<PyObject> = grid.iter().flatten().cloned().collect();
+    if let Some(ints) = int_arrays(py, &objs) {
+        let inner = assemble_blocks(&regroup(&grid, ints))?;
+        return Ok(NdArrayI { inner }.into_py(py));
+    }
+    let floats = float_arrays(py, objs)?;
+    let inner = assemble_blocks(&regroup(&grid, floats))?;
+    Ok(NdArrayD { inner }.into_py(py))
+}
+
+/// Split `arrays` into rows of the lengths of the rows of `grid`
+fn regroup<T, U>(grid: &[Vec<T>], arrays: Vec<U>) -> Vec<Vec<U>> {
+    let mut arrays = arrays.into_iter();
+    grid.iter()
+        .map(|row| arrays.by_ref().take(row.len()).collect())
+        .collect()
+}
+
+/// Place `arrays` along the diagonal of a matrix, filling the rest with zeros. Like scipy's
+/// `block_diag`.
+///
+/// If all arrays are `NdArrayI`s the result is an `NdArrayI`.
+#[pyfunction(arrays = "*")]
+pub fn block_diag(py: Python, arrays: &PyTuple) -> PyResult<PyObject> {
+    let objs: Vec<PyObject> = arrays.iter().map(|obj| obj.into()).collect();
+    if let Some(ints) = int_arrays(py, &objs) {
+        let inner = assemble_blocks(&diagonal_grid(ints))?;
+        return Ok(NdArrayI { inner }.into_py(py));
+    }
+    let inner = assemble_blocks(&diagonal_grid(float_arrays(py, objs)?))?;
+    Ok(NdArrayD { inner }.into_py(py))
+}
+
+/// Grid of blocks with `arrays` on the diagonal and zero blocks elsewhere
+fn diagonal_grid<T: Default + Clone>(arrays: Vec<NdArray<T>>) -> Vec<Vec<NdArray<T>>> {
+    let arrays: Vec<_> = arrays.iter().map(|a| with_min_ndim(a, 2)).collect();
+    let dims: Vec<&[u32]> = arrays.iter().map(|a| a.shape().as_slice()).collect();
+    arrays
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            (0..arrays.len())
+                .map(|j| {
+                    if i == j {
+                        a.clone()
+                    } else {
+                        let (h, w) = (dims[i][0], dims[j][dims[j].len() - 1]);
+                        NdArray::new_default([h, w])
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Mean and population variance of the lanes of the integer `inp` along `axis`, the rows if
+/// `axis` is None. Computed in f64, so values above 2^24 stay exact until the results are narrowed
+/// to f32.
+fn int_mean_var(
+    inp: &NdArray<i64>,
+    axis: Option<i64>,
+    keepdims: Option<bool>,
+) -> PyResult<(NdArray<f32>, NdArray<f32>)> {
+    // the float reductions of the rows keep the reduced axis
+    let keepdims = if axis.is_none() { Some(true) } else { keepdims };
+    let res = reduce_axis(inp, axis, keepdims, |lane| {
+        let n = lane.len() as f64;
+        let mean = lane.iter().map(|x| *x as f64).sum::<f64>() / n;
+        let var = lane.iter().map(|x| (*x as f64 - mean).powi(2)).sum::<f64>() / n;
+        (mean as f32, var as f32)
+    })?;
+    Ok((res.map(|x| x.0), res.map(|x| x.1)))
+}
+
+/// Mean of the rows, or of the lanes along `axis` if given.
+///
+/// `NdArrayI` inputs are reduced in f64 before the result is narrowed to f32.
+#[pyfunction(keepdims = "None", "*", axis = "None")]
+pub fn mean(
+    py: Python,
+    inp: PyObject,
+    keepdims: Option<bool>,
+    axis: Option<i64>,
+) -> PyResult<NdArrayD> {
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        let (mut inner, _) = int_mean_var(&inp.inner, axis, keepdims)?;
+        if axis.is_none() && !keepdims.unwrap_or(false) && inp.inner.shape().as_slice().len() == 1 {
+            // like facet_core::mean, the mean of a vector is a scalar
+            inner.reshape(0);
+        }
+        return Ok(NdArrayD { inner });
+    }
+    unwrap_obj!(py, inp);
+    if axis.is_some() {
+        let inner = reduce_axis(&inp.inner, axis, keepdims, |lane| {
+            lane.iter().sum::<f32>() / lane.len() as f32
+        })?;
+        debug::check_finite("mean", &inner)?;
+        return Ok(NdArrayD { inner });
+    }
+    let res = facet_core::mean(&inp.inner)
+        .map_err(|err| PyValueError::new_err::<String>(format!("{}", err)))?;
+    debug::check_finite("mean", &res)?;
+    Ok(NdArrayD {
+        inner: keep_dims(res, inp.inner.shape(), keepdims),
+    })
 }
 
 #[pyfunction]
@@ -169,10 +836,180 @@ pub fn sqrt(py: Python, inp: PyObject) -> PyResult<NdArrayD> {
         .as_mut_slice()
         .iter_mut()
         .for_each(|v| *v = v.sqrt());
+    debug::check_finite("sqrt", &res.inner)?;
+
+    Ok(res)
+}
+
+/// Element-wise `1 / inp`. Zeros map to `inf`.
+#[pyfunction]
+pub fn reciprocal(py: Python, inp: PyObject) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+
+    let mut res = inp.clone();
+
+    res.inner
+        .as_mut_slice()
+        .iter_mut()
+        .for_each(|v| *v = v.recip());
+    debug::check_finite("reciprocal", &res.inner)?;
+
+    Ok(res)
+}
+
+/// Element-wise `-inp`. `NdArrayI` inputs return an `NdArrayI`, with `-i64::MIN` wrapping to
+/// `i64::MIN`.
+#[pyfunction]
+pub fn negative(py: Python, inp: PyObject) -> PyResult<PyObject> {
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        let res = NdArrayI {
+            inner: inp.inner.map(|v| v.wrapping_neg()),
+        };
+        return Ok(res.into_py(py));
+    }
+    unwrap_obj!(py, inp);
+
+    let mut res = inp.clone();
+
+    res.inner.as_mut_slice().iter_mut().for_each(|v| *v = -*v);
+
+    Ok(res.into_py(py))
+}
+
+/// Integer operand of a binary function, an `NdArrayI` or an `int`
+fn extract_int_operand(py: Python, inp: &PyObject) -> Option<NdArray<i64>> {
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        return Some(inp.inner.clone());
+    }
+    inp.extract::<i64>(py).ok().map(NdArray::new_scalar)
+}
+
+/// Apply `fi` if both operands are integers, returning an `NdArrayI`, otherwise apply `ff` to the
+/// operands converted to floats. Integer division by zero raises `ZeroDivisionError`.
+fn int_or_float_division(
+    py: Python,
+    op: &str,
+    a: PyObject,
+    b: PyObject,
+    fi: fn(i64, i64) -> i64,
+    ff: fn(f32, f32) -> f32,
+) -> PyResult<PyObject> {
+    if let (Some(a), Some(b)) = (extract_int_operand(py, &a), extract_int_operand(py, &b)) {
+        if b.as_slice().contains(&0) {
+            return Err(PyZeroDivisionError::new_err("integer division by zero"));
+        }
+        return a
+            .zip_with(&b, fi)
+            .map(|inner| NdArrayI { inner }.into_py(py))
+            .map_err(|err| PyValueError::new_err(format!("{}", err)));
+    }
+    let res = float_zip_with(py, a, b, ff)?;
+    debug::check_finite(op, &res.inner)?;
+    Ok(res.into_py(py))
+}
 
+/// Apply `f` element-wise to the operands converted to floats, broadcasting plain numbers as
+/// scalars
+fn float_zip_with(
+    py: Python,
+    a: PyObject,
+    b: PyObject,
+    f: fn(f32, f32) -> f32,
+) -> PyResult<NdArrayD> {
+    let a = a
+        .extract::<f32>(py)
+        .map(|x| scalar(x).into_py(py))
+        .unwrap_or(a);
+    let b = b
+        .extract::<f32>(py)
+        .map(|x| scalar(x).into_py(py))
+        .unwrap_or(b);
+    unwrap_obj!(py, a);
+    unwrap_obj!(py, b);
+    a.inner
+        .zip_with(&b.inner, f)
+        .map(|inner| NdArrayD { inner })
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Element-wise magnitude of `a` with the sign of `b`
+#[pyfunction]
+pub fn copysign(py: Python, a: PyObject, b: PyObject) -> PyResult<NdArrayD> {
+    float_zip_with(py, a, b, f32::copysign)
+}
+
+/// Element-wise `sqrt(a^2 + b^2)`, without overflowing in the intermediate squares
+#[pyfunction]
+pub fn hypot(py: Python, a: PyObject, b: PyObject) -> PyResult<NdArrayD> {
+    let res = float_zip_with(py, a, b, f32::hypot)?;
+    debug::check_finite("hypot", &res.inner)?;
     Ok(res)
 }
 
+/// Element-wise maximum. NaN propagates, like numpy's `maximum`.
+#[pyfunction]
+pub fn maximum(py: Python, a: PyObject, b: PyObject) -> PyResult<NdArrayD> {
+    float_zip_with(py, a, b, |a, b| if a.is_nan() || a >= b { a } else { b })
+}
+
+/// Element-wise minimum. NaN propagates, like numpy's `minimum`.
+#[pyfunction]
+pub fn minimum(py: Python, a: PyObject, b: PyObject) -> PyResult<NdArrayD> {
+    float_zip_with(py, a, b, |a, b| if a.is_nan() || a <= b { a } else { b })
+}
+
+/// Element-wise remainder of `a / b` with the sign of the divisor, like Python's and numpy's `%`,
+/// e.g. `-1 % 3 == 2`.
+///
+/// Integer operands (`NdArrayI` or `int`) return an `NdArrayI`, everything else an `NdArrayD`.
+#[pyfunction]
+pub fn mod_(py: Python, a: PyObject, b: PyObject) -> PyResult<PyObject> {
+    int_or_float_division(
+        py,
+        "mod",
+        a,
+        b,
+        |a, b| {
+            let r = a.wrapping_rem(b);
+            if r != 0 && (r < 0) != (b < 0) {
+                r + b
+            } else {
+                r
+            }
+        },
+        |a, b| {
+            let r = a % b;
+            if r != 0.0 && (r < 0.0) != (b < 0.0) {
+                r + b
+            } else {
+                r
+            }
+        },
+    )
+}
+
+/// Element-wise `a / b` rounded towards negative infinity, like Python's and numpy's `//`.
+///
+/// Integer operands (`NdArrayI` or `int`) return an `NdArrayI`, everything else an `NdArrayD`.
+#[pyfunction]
+pub fn floor_divide(py: Python, a: PyObject, b: PyObject) -> PyResult<PyObject> {
+    int_or_float_division(
+        py,
+        "floor_divide",
+        a,
+        b,
+        |a, b| {
+            let q = a.wrapping_div(b);
+            if a.wrapping_rem(b) != 0 && (a < 0) != (b < 0) {
+                q - 1
+            } else {
+                q
+            }
+        },
+        |a, b| (a / b).floor(),
+    )
+}
+
 #[pyfunction]
 pub fn binomial(py: Python, n: u64, p: f32, size: Option<PyObject>) -> PyResult<NdArrayD> {
     use rand::prelude::*;
@@ -199,11 +1036,12 @@ pub fn binomial(py: Python, n: u64, p: f32, size: Option<PyObject>) -> PyResult<
     let len = shape.span();
     let mut res = NdArray::<f32>::new(shape);
 
-    let mut rng = rand::thread_rng();
-    for i in 0..len {
-        let x = dist.sample(&mut rng);
-        res.as_mut_slice()[i as usize] = x as f32;
-    }
+    random::with_rng(|rng| {
+        for i in 0..len {
+            let x = dist.sample(rng);
+            res.as_mut_slice()[i as usize] = x as f32;
+        }
+    });
 
     let res = NdArrayD { inner: res };
     Ok(res)
@@ -230,36 +1068,132 @@ pub fn log(py: Python, inp: PyObject, base: Option<f32>) -> PyResult<NdArrayD> {
     let res = inp.inner.as_slice().iter().map(|x| x.log(base)).collect();
 
     let inner = NdArray::new_with_values(inp.shape(), res).unwrap();
+    debug::check_finite("log", &inner)?;
 
     Ok(NdArrayD { inner })
 }
 
-#[pyfunction]
-pub fn std_squared(py: Python, inp: PyObject, mean: Option<PyObject>) -> PyResult<NdArrayD> {
+/// Population variance of the lanes along `axis` of `inp`, see [facet_core::mean_var_axis]
+fn var_axis(
+    inp: &NdArray<f32>,
+    mean: &Option<PyObject>,
+    axis: i64,
+    keepdims: Option<bool>,
+) -> PyResult<NdArray<f32>> {
+    if mean.is_some() {
+        return Err(PyValueError::new_err(
+            "mean is only supported when reducing the last axis",
+        ));
+    }
+    let axis = normalize_axis(axis, inp.shape().as_slice().len())?;
+    let (_, var) = facet_core::mean_var_axis(inp, axis)
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+    Ok(keep_dims_at(var, inp.shape(), axis, keepdims))
+}
+
+/// Variance of the rows, or of the lanes along `axis` if given.
+///
+/// Pass the precomputed `mean` of the rows to skip computing it, it is not supported together
+/// with `axis`. `NdArrayI` inputs without `mean` are reduced in f64 before the result is narrowed
+/// to f32.
+#[pyfunction(mean = "None", keepdims = "None", "*", axis = "None")]
+pub fn std_squared(
+    py: Python,
+    inp: PyObject,
+    mean: Option<PyObject>,
+    keepdims: Option<bool>,
+    axis: Option<i64>,
+) -> PyResult<NdArrayD> {
+    if let (Ok(inp), None) = (inp.extract::<PyRef<NdArrayI>>(py), &mean) {
+        let (_, inner) = int_mean_var(&inp.inner, axis, keepdims)?;
+        return Ok(NdArrayD { inner });
+    }
     unwrap_obj!(py, inp);
+    if let Some(axis) = axis {
+        let inner = var_axis(&inp.inner, &mean, axis, keepdims)?;
+        debug::check_finite("std_squared", &inner)?;
+        return Ok(NdArrayD { inner });
+    }
 
     let mean: Option<Py<NdArrayD>> = mean.and_then(|m| m.extract(py).ok());
     let mean = mean.as_ref().map(|m| m.borrow(py));
 
     let res =
-        facet_core::std_squared(&inp.inner, mean.as_ref().map(|m| &(&*m).inner)).map_err(|e| {
+        facet_core::std_squared(&inp.inner, mean.as_ref().map(|m| &m.inner)).map_err(|e| {
             PyValueError::new_err(format!("Failed to perform std squared calculation {:?}", e))
         })?;
+    debug::check_finite("std_squared", &res)?;
 
-    Ok(NdArrayD { inner: res })
+    Ok(NdArrayD {
+        inner: keep_dims(res, inp.inner.shape(), keepdims),
+    })
 }
 
-#[pyfunction]
-pub fn std(py: Python, inp: PyObject, mean: Option<PyObject>) -> PyResult<NdArrayD> {
+/// Standard deviation of the rows, or of the lanes along `axis` if given.
+///
+/// Pass the precomputed `mean` of the rows to skip computing it, it is not supported together
+/// with `axis`. `NdArrayI` inputs without `mean` are reduced in f64 before the result is narrowed
+/// to f32.
+#[pyfunction(mean = "None", keepdims = "None", "*", axis = "None")]
+pub fn std(
+    py: Python,
+    inp: PyObject,
+    mean: Option<PyObject>,
+    keepdims: Option<bool>,
+    axis: Option<i64>,
+) -> PyResult<NdArrayD> {
+    if let (Ok(inp), None) = (inp.extract::<PyRef<NdArrayI>>(py), &mean) {
+        let (_, mut inner) = int_mean_var(&inp.inner, axis, keepdims)?;
+        inner.as_mut_slice().iter_mut().for_each(|x| *x = x.sqrt());
+        return Ok(NdArrayD { inner });
+    }
     unwrap_obj!(py, inp);
+    if let Some(axis) = axis {
+        let mut inner = var_axis(&inp.inner, &mean, axis, keepdims)?;
+        inner.as_mut_slice().iter_mut().for_each(|x| *x = x.sqrt());
+        debug::check_finite("std", &inner)?;
+        return Ok(NdArrayD { inner });
+    }
 
     let mean: Option<Py<NdArrayD>> = mean.and_then(|m| m.extract(py).ok());
     let mean = mean.as_ref().map(|m| m.borrow(py));
 
-    let res = facet_core::std(&inp.inner, mean.as_ref().map(|m| &(&*m).inner))
+    let res = facet_core::std(&inp.inner, mean.as_ref().map(|m| &m.inner))
         .map_err(|e| PyValueError::new_err(format!("Failed to perform std calculation {:?}", e)))?;
+    debug::check_finite("std", &res)?;
 
-    Ok(NdArrayD { inner: res })
+    Ok(NdArrayD {
+        inner: keep_dims(res, inp.inner.shape(), keepdims),
+    })
+}
+
+/// Variance of the rows, or of the lanes along `axis` if given. Same as `std_squared`.
+#[pyfunction(keepdims = "None", "*", axis = "None")]
+pub fn var(
+    py: Python,
+    inp: PyObject,
+    keepdims: Option<bool>,
+    axis: Option<i64>,
+) -> PyResult<NdArrayD> {
+    std_squared(py, inp, None, keepdims, axis)
+}
+
+#[pyfunction]
+pub fn cov(py: Python, inp: PyObject) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+
+    facet_core::cov(&inp.inner)
+        .map_err(|err| PyValueError::new_err::<String>(format!("{}", err)))
+        .map(|inner| NdArrayD { inner })
+}
+
+#[pyfunction]
+pub fn corrcoef(py: Python, inp: PyObject) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+
+    facet_core::corrcoef(&inp.inner)
+        .map_err(|err| PyValueError::new_err::<String>(format!("{}", err)))
+        .map(|inner| NdArrayD { inner })
 }
 
 #[pyfunction]
@@ -272,24 +1206,30 @@ pub fn moving_average(py: Python, inp: PyObject, window: u64) -> PyResult<NdArra
 }
 
 #[pyfunction]
-pub fn veclen(py: Python, inp: PyObject) -> PyResult<NdArrayD> {
+pub fn veclen(py: Python, inp: PyObject, keepdims: Option<bool>) -> PyResult<NdArrayD> {
     unwrap_obj!(py, inp);
 
     let mut out = NdArray::new(0);
 
     facet_core::veclen(&inp.inner, &mut out);
+    debug::check_finite("veclen", &out)?;
 
-    Ok(NdArrayD { inner: out })
+    Ok(NdArrayD {
+        inner: keep_dims(out, inp.inner.shape(), keepdims),
+    })
 }
 
 #[pyfunction]
-pub fn veclen_squared(py: Python, inp: PyObject) -> PyResult<NdArrayD> {
+pub fn veclen_squared(py: Python, inp: PyObject, keepdims: Option<bool>) -> PyResult<NdArrayD> {
     unwrap_obj!(py, inp);
 
     let mut out = NdArray::new(0);
     facet_core::veclen_squared(&inp.inner, &mut out);
+    debug::check_finite("veclen_squared", &out)?;
 
-    Ok(NdArrayD { inner: out })
+    Ok(NdArrayD {
+        inner: keep_dims(out, inp.inner.shape(), keepdims),
+    })
 }
 
 #[pyfunction]
@@ -302,6 +1242,124 @@ pub fn normalize_vectors(py: Python, inp: PyObject) -> PyResult<NdArrayD> {
     Ok(NdArrayD { inner: out })
 }
 
+/// Scale each lane along `axis` to unit norm.
+///
+/// `axis` defaults to the last axis, `ord` to 2. Pass `math.inf` as `ord` for the max norm.
+/// All-zero lanes stay zero.
+#[pyfunction]
+pub fn normalize(
+    py: Python,
+    inp: PyObject,
+    axis: Option<i64>,
+    ord: Option<f32>,
+) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+    let axis = normalize_axis(axis.unwrap_or(-1), inp.inner.shape().as_slice().len())?;
+
+    let res = facet_core::normalize_f32(&inp.inner, axis, ord.unwrap_or(2.0))
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+    debug::check_finite("normalize", &res)?;
+    Ok(NdArrayD { inner: res })
+}
+
+/// Scale down each lane along `axis` whose L2 norm exceeds `max_norm` to a norm of `max_norm`,
+/// in place. Lanes within the limit are left untouched.
+///
+/// `axis` defaults to 1, constraining the rows of a weight matrix.
+#[pyfunction]
+pub fn clamp_norm(mut inp: PyRefMut<NdArrayD>, max_norm: f32, axis: Option<i64>) -> PyResult<()> {
+    let axis = normalize_axis(axis.unwrap_or(1), inp.inner.shape().as_slice().len())?;
+
+    facet_core::clamp_norm_f32(&mut inp.inner, axis, max_norm)
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Calculate `log(sum(exp(inp)))` along `axis`, without overflowing for large inputs.
+///
+/// `axis` defaults to the last axis, which is removed from the output shape unless `keepdims` is
+/// set.
+#[pyfunction]
+pub fn logsumexp(
+    py: Python,
+    inp: PyObject,
+    axis: Option<i64>,
+    keepdims: Option<bool>,
+) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+    let axis = normalize_axis(axis.unwrap_or(-1), inp.inner.shape().as_slice().len())?;
+
+    let res = facet_core::logsumexp_f32(&inp.inner, axis)
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+    debug::check_finite("logsumexp", &res)?;
+    Ok(NdArrayD {
+        inner: keep_dims_at(res, inp.inner.shape(), axis, keepdims),
+    })
+}
+
+/// Calculate the mean and the population variance along `axis` in a single pass, returning
+/// `(mean, var)`.
+///
+/// `axis` defaults to 0, the batch axis, which is removed from the output shapes.
+#[pyfunction]
+pub fn mean_var(py: Python, inp: PyObject, axis: Option<i64>) -> PyResult<(NdArrayD, NdArrayD)> {
+    unwrap_obj!(py, inp);
+    let axis = normalize_axis(axis.unwrap_or(0), inp.inner.shape().as_slice().len())?;
+
+    let (mean, var) = facet_core::mean_var_axis(&inp.inner, axis)
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+    Ok((NdArrayD { inner: mean }, NdArrayD { inner: var }))
+}
+
+/// Call `func` on each 1-D lane of `inp` along `axis` and assemble the results. Like numpy's
+/// `apply_along_axis`.
+///
+/// `func` receives the lanes as vectors and may return a vector of any length, which replaces the
+/// size of `axis` in the result. If it returns numbers `axis` is removed from the result.
+#[pyfunction]
+pub fn apply_along_axis(
+    py: Python,
+    func: PyObject,
+    axis: i64,
+    inp: PyObject,
+) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+    let axis = normalize_axis(axis, inp.inner.shape().as_slice().len())?;
+
+    let mut err = None;
+    let mut scalar_results = None;
+    let res = inp.inner.map_axis(axis, |lane| {
+        let lane = NdArrayD {
+            inner: NdArray::new_vector(lane.to_vec()),
+        };
+        match func.call1(py, (lane,)).and_then(|out| {
+            let is_number = {
+                let out = out.as_ref(py);
+                out.downcast::<PyFloat>().is_ok() || out.downcast::<PyLong>().is_ok()
+            };
+            scalar_results.get_or_insert(is_number);
+            pyobj_to_arrayd(py, out)
+        }) {
+            Ok(out) => out.borrow(py).inner.as_slice().to_vec(),
+            Err(e) => {
+                // an empty result stops the iteration
+                err = Some(e);
+                Vec::new()
+            }
+        }
+    });
+    if let Some(err) = err {
+        return Err(err);
+    }
+    let mut res = res.map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+
+    if scalar_results == Some(true) {
+        let mut shape = res.shape().as_slice().to_vec();
+        shape.remove(axis as usize);
+        res.reshape(shape);
+    }
+    Ok(NdArrayD { inner: res })
+}
+
 #[pyfunction]
 pub fn fast_inverse_sqrt(py: Python, inp: PyObject) -> PyResult<NdArrayD> {
     unwrap_obj!(py, inp);
@@ -329,35 +1387,1024 @@ pub fn abs(py: Python, inp: PyObject) -> PyResult<NdArrayD> {
     Ok(NdArrayD { inner: out })
 }
 
+/// 0/1 mask of the elements of `inp` satisfying `pred`
+fn predicate_mask(py: Python, inp: PyObject, pred: impl Fn(f32) -> bool) -> PyResult<NdArrayI> {
+    unwrap_obj!(py, inp);
+
+    let inner = inp.inner.map(|x| pred(*x) as i64);
+    Ok(NdArrayI { inner })
+}
+
+/// 1 where the element is NaN, 0 otherwise
+#[pyfunction]
+pub fn isnan(py: Python, inp: PyObject) -> PyResult<NdArrayI> {
+    predicate_mask(py, inp, f32::is_nan)
+}
+
+/// 1 where the element is positive or negative infinity, 0 otherwise
+#[pyfunction]
+pub fn isinf(py: Python, inp: PyObject) -> PyResult<NdArrayI> {
+    predicate_mask(py, inp, f32::is_infinite)
+}
+
+/// 1 where the element is neither NaN nor infinite, 0 otherwise
+#[pyfunction]
+pub fn isfinite(py: Python, inp: PyObject) -> PyResult<NdArrayI> {
+    predicate_mask(py, inp, f32::is_finite)
+}
+
+/// Collect values along `dim` using the indices in `index`. Like PyTorch's `gather`.
+///
+/// `index` must have the same number of dimensions as `inp`. The result has the shape of `index`.
+#[pyfunction]
+pub fn gather(py: Python, inp: PyObject, dim: u32, index: PyObject) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+    let index = pyobj_to_arrayi(py, index)?;
+
+    inp.inner
+        .gather(dim, &index)
+        .map(|inner| NdArrayD { inner })
+        .map_err(index_error_to_py)
+}
+
+/// Select values along `axis` using the indices in `indices`, e.g. the result of `argsort`. Like
+/// numpy's `take_along_axis`.
+///
+/// `indices` must have the same number of dimensions as `inp`, negative indices count from the
+/// end of `axis`. `NdArrayI` inputs return an `NdArrayI`.
+#[pyfunction]
+pub fn take_along_axis(
+    py: Python,
+    inp: PyObject,
+    indices: PyObject,
+    axis: i64,
+) -> PyResult<PyObject> {
+    let indices = pyobj_to_arrayi(py, indices)?;
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        let axis = normalize_axis(axis, inp.inner.shape().as_slice().len())?;
+        return inp
+            .inner
+            .take_along_axis(axis, &indices)
+            .map(|inner| NdArrayI { inner }.into_py(py))
+            .map_err(index_error_to_py);
+    }
+    unwrap_obj!(py, inp);
+    let axis = normalize_axis(axis, inp.inner.shape().as_slice().len())?;
+    inp.inner
+        .take_along_axis(axis, &indices)
+        .map(|inner| NdArrayD { inner }.into_py(py))
+        .map_err(index_error_to_py)
+}
+
+/// Return the indices that sort `inp` along `axis`. Like numpy's `argsort`.
+///
+/// `axis` defaults to the last axis. The sort is stable, NaN values are sorted to the end.
+#[pyfunction]
+pub fn argsort(py: Python, inp: PyObject, axis: Option<i64>) -> PyResult<NdArrayI> {
+    unwrap_obj!(py, inp);
+    let axis = normalize_axis(axis.unwrap_or(-1), inp.inner.shape().as_slice().len())?;
+
+    inp.inner
+        .map_axis(axis, |lane| {
+            let mut indices: Vec<i64> = (0..lane.len() as i64).collect();
+            indices.sort_by(|a, b| lane[*a as usize].total_cmp(&lane[*b as usize]));
+            indices
+        })
+        .map(|inner| NdArrayI { inner })
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Normalize the `kth` position of a partition along `axis` of `inp`
+fn partition_kth(inp: &NdArray<f32>, kth: i64, axis: Option<i64>) -> PyResult<(u32, usize)> {
+    let shape = inp.shape().as_slice();
+    let axis = normalize_axis(axis.unwrap_or(-1), shape.len())?;
+    let n = shape.get(axis as usize).copied().unwrap_or(1) as i64;
+    let k = if kth < 0 { kth + n } else { kth };
+    if k < 0 || k >= n {
+        return Err(PyIndexError::new_err(format!(
+            "kth {} is out of bounds for an axis of length {}",
+            kth, n
+        )));
+    }
+    Ok((axis, k as usize))
+}
+
+/// Return the indices that partition `inp` along `axis`, see `partition`. Like numpy's
+/// `argpartition`.
+#[pyfunction]
+pub fn argpartition(py: Python, inp: PyObject, kth: i64, axis: Option<i64>) -> PyResult<NdArrayI> {
+    unwrap_obj!(py, inp);
+    let (axis, k) = partition_kth(&inp.inner, kth, axis)?;
+
+    inp.inner
+        .map_axis(axis, |lane| {
+            let mut indices: Vec<i64> = (0..lane.len() as i64).collect();
+            indices
+                .select_nth_unstable_by(k, |a, b| lane[*a as usize].total_cmp(&lane[*b as usize]));
+            indices
+        })
+        .map(|inner| NdArrayI { inner })
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Rearrange each lane along `axis` so the element at `kth` is the one that would be there if the
+/// lane was sorted, with no greater elements before and no smaller ones after it. Like numpy's
+/// `partition`.
+///
+/// Each lane is partitioned by quickselect, in linear time, without sorting. `axis` defaults to
+/// the last axis. Values are ordered by `total_cmp`, so positive NaN values are sorted to the end
+/// and negative NaN values to the start.
+#[pyfunction]
+pub fn partition(py: Python, inp: PyObject, kth: i64, axis: Option<i64>) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+    let (axis, k) = partition_kth(&inp.inner, kth, axis)?;
+
+    inp.inner
+        .map_axis(axis, |lane| {
+            let mut lane = lane.to_vec();
+            lane.select_nth_unstable_by(k, f32::total_cmp);
+            lane
+        })
+        .map(|inner| NdArrayD { inner })
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Return the `k` largest values along `axis` and their indices as `(values, indices)`. Like
+/// PyTorch's `topk`.
+///
+/// `axis` defaults to the last axis, which has size `k` in the results. `largest=False` returns
+/// the `k` smallest values instead. The selection is a quickselect like `argpartition`, only the
+/// `k` selected values are sorted, in descending order for `largest` and ascending otherwise,
+/// unless `sorted` is false. NaN values compare greater than every number.
+#[pyfunction(k, axis = "None", largest = "true", sorted = "true")]
+pub fn top_k(
+    py: Python,
+    inp: PyObject,
+    k: u32,
+    axis: Option<i64>,
+    largest: bool,
+    sorted: bool,
+) -> PyResult<(NdArrayD, NdArrayI)> {
+    unwrap_obj!(py, inp);
+    let shape = inp.inner.shape().as_slice();
+    let axis = normalize_axis(axis.unwrap_or(-1), shape.len())?;
+    let n = shape[axis as usize];
+    if k == 0 || k > n {
+        return Err(PyValueError::new_err(format!(
+            "k must be in [1, {}] for an axis of length {}, got {}",
+            n, n, k
+        )));
+    }
+    let k = k as usize;
+
+    let indices = inp
+        .inner
+        .map_axis(axis, |lane| {
+            let order = |a: &i64, b: &i64| {
+                let ord = lane[*a as usize].total_cmp(&lane[*b as usize]);
+                let ord = if largest { ord.reverse() } else { ord };
+                ord.then(a.cmp(b))
+            };
+            let mut indices: Vec<i64> = (0..lane.len() as i64).collect();
+            indices.select_nth_unstable_by(k - 1, &order);
+            indices.truncate(k);
+            if sorted {
+                indices.sort_unstable_by(&order);
+            }
+            indices
+        })
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+    let values = inp
+        .inner
+        .take_along_axis(axis, &indices)
+        .map_err(index_error_to_py)?;
+    Ok((NdArrayD { inner: values }, NdArrayI { inner: indices }))
+}
+
+/// Broadcast `inp` and `mask` against each other and keep the elements of `inp` where `mask` is
+/// nonzero
+fn masked_values<T>(inp: &NdArray<T>, mask: &NdArray<f32>, one: T) -> PyResult<NdArray<T>>
+where
+    T: Copy + Default + PartialEq + Send + Sync,
+{
+    let mask = mask.map(|m| if *m != 0.0 { one } else { T::default() });
+    let broadcast = |f: fn(T, T) -> T| {
+        inp.zip_with(&mask, f)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    };
+    let values = broadcast(|x, _| x)?;
+    let keep = broadcast(|_, m| m)?;
+    let res: Vec<T> = values
+        .as_slice()
+        .iter()
+        .zip(keep.as_slice())
+        .filter(|(_, m)| **m != T::default())
+        .map(|(x, _)| *x)
+        .collect();
+    // `new_vector` would treat an empty selection as a scalar
+    NdArray::new_with_values(Shape::Vector([res.len() as u32]), res.into())
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Return a vector of the elements of `inp` where the broadcast `mask` is nonzero, in row-major
+/// order. Like PyTorch's `masked_select`.
+///
+/// `mask` may be an `NdArrayB`, e.g. the result of a comparison. `NdArrayI` inputs return an
+/// `NdArrayI`.
+#[pyfunction]
+pub fn masked_select(py: Python, inp: PyObject, mask: PyObject) -> PyResult<PyObject> {
+    let bools = mask
+        .extract::<PyRef<NdArrayB>>(py)
+        .map(|mask| mask.as_f32().inner);
+    let mask = match bools {
+        Ok(mask) => mask,
+        Err(_) => pyobj_to_arrayd(py, mask)?.borrow(py).inner.clone(),
+    };
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        let inner = masked_values(&inp.inner, &mask, 1)?;
+        return Ok(NdArrayI { inner }.into_py(py));
+    }
+    unwrap_obj!(py, inp);
+    let inner = masked_values(&inp.inner, &mask, 1.0)?;
+    Ok(NdArrayD { inner }.into_py(py))
+}
+
+fn choose_values<T: Clone>(indices: &NdArray<i64>, choices: &[NdArray<T>]) -> PyResult<NdArray<T>> {
+    for choice in choices {
+        if choice.shape() != indices.shape() {
+            return Err(PyValueError::new_err(format!(
+                "Expected choices of shape {:?}, got {:?}",
+                indices.shape(),
+                choice.shape()
+            )));
+        }
+    }
+    let values = indices
+        .as_slice()
+        .iter()
+        .enumerate()
+        .map(|(i, j)| {
+            usize::try_from(*j)
+                .ok()
+                .and_then(|j| choices.get(j))
+                .map(|choice| choice.as_slice()[i].clone())
+                .ok_or_else(|| {
+                    PyIndexError::new_err(format!(
+                        "index {} is out of bounds for {} choices",
+                        j,
+                        choices.len()
+                    ))
+                })
+        })
+        .collect::<PyResult<_>>()?;
+    Ok(NdArray::new_with_values(indices.shape().clone(), values).unwrap())
+}
+
+/// Construct an array taking each element from the choice its index in `indices` selects,
+/// `choices[indices[i]][i]`. Like numpy's `choose`.
+///
+/// The `choices` must have the shape of `indices`. If all of them are `NdArrayI`s the result is an
+/// `NdArrayI`.
+#[pyfunction]
+pub fn choose(py: Python, indices: PyObject, choices: Vec<PyObject>) -> PyResult<PyObject> {
+    let indices = pyobj_to_arrayi(py, indices)?;
+    if let Some(choices) = int_arrays(py, &choices) {
+        let inner = choose_values(&indices, &choices)?;
+        return Ok(NdArrayI { inner }.into_py(py));
+    }
+    let inner = choose_values(&indices, &float_arrays(py, choices)?)?;
+    Ok(NdArrayD { inner }.into_py(py))
+}
+
+/// Inverse of `gather`. Returns a copy of `inp` where the values of `src` are written to the
+/// positions selected by `index` along `dim`.
+///
+/// `src` must have the same shape as `index`.
+#[pyfunction]
+pub fn scatter(
+    py: Python,
+    inp: PyObject,
+    dim: u32,
+    index: PyObject,
+    src: PyObject,
+) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+    unwrap_obj!(py, src);
+    let index = pyobj_to_arrayi(py, index)?;
+
+    let mut res = inp.inner.clone();
+    res.scatter(dim, &index, &src.inner)
+        .map_err(index_error_to_py)?;
+
+    Ok(NdArrayD { inner: res })
+}
+
+/// Return a new array with `values` inserted before `index` along `axis`. Like numpy's `insert`.
+///
+/// `index` may be a single position or a list of positions. If `axis` is None `inp` is flattened
+/// first.
+#[pyfunction]
+pub fn insert(
+    py: Python,
+    inp: PyObject,
+    index: PyObject,
+    values: PyObject,
+    axis: Option<i64>,
+) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+    unwrap_obj!(py, values);
+    let index = extract_indices(py, index)?;
+    let (arr, axis) = array_along_axis(&inp.inner, axis)?;
+
+    arr.insert(axis, &index, &values.inner)
+        .map(|inner| NdArrayD { inner })
+        .map_err(index_error_to_py)
+}
+
+/// Return a new array with the entries at `index` along `axis` removed. Like numpy's `delete`.
+///
+/// `index` may be a single position or a list of positions. If `axis` is None `inp` is flattened
+/// first.
+#[pyfunction]
+pub fn delete(py: Python, inp: PyObject, index: PyObject, axis: Option<i64>) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+    let index = extract_indices(py, index)?;
+    let (arr, axis) = array_along_axis(&inp.inner, axis)?;
+
+    arr.delete(axis, &index)
+        .map(|inner| NdArrayD { inner })
+        .map_err(index_error_to_py)
+}
+
+/// Split `inp` into two copies along `axis`, before `index`. Handy for train/validation splits.
+///
+/// `axis` defaults to 0.
+#[pyfunction]
+pub fn split_at(
+    py: Python,
+    inp: PyObject,
+    index: u32,
+    axis: Option<i64>,
+) -> PyResult<(NdArrayD, NdArrayD)> {
+    unwrap_obj!(py, inp);
+    let axis = normalize_axis(axis.unwrap_or(0), inp.inner.shape().as_slice().len())?;
+
+    inp.inner
+        .split_at(axis, index)
+        .map(|(a, b)| (NdArrayD { inner: a }, NdArrayD { inner: b }))
+        .map_err(index_error_to_py)
+}
+
+/// Rotate `inp` by 90 degrees `k` times in the plane of `axes`, from the first axis towards the
+/// second. Like numpy's `rot90`.
+///
+/// `k` defaults to 1 and `axes` to `(0, 1)`. `NdArrayI` inputs return an `NdArrayI`, everything
+/// else returns an `NdArrayD`.
+#[pyfunction]
+pub fn rot90(
+    py: Python,
+    inp: PyObject,
+    k: Option<i32>,
+    axes: Option<(i64, i64)>,
+) -> PyResult<PyObject> {
+    let k = k.unwrap_or(1);
+    let (a, b) = axes.unwrap_or((0, 1));
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        let ndims = inp.inner.shape().as_slice().len();
+        let axes = [normalize_axis(a, ndims)?, normalize_axis(b, ndims)?];
+        let res = inp
+            .inner
+            .rot90(k, axes)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+        return Ok(NdArrayI { inner: res }.into_py(py));
+    }
+    unwrap_obj!(py, inp);
+    let ndims = inp.inner.shape().as_slice().len();
+    let axes = [normalize_axis(a, ndims)?, normalize_axis(b, ndims)?];
+    let res = inp
+        .inner
+        .rot90(k, axes)
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+    Ok(NdArrayD { inner: res }.into_py(py))
+}
+
+/// Normalize an axis or a sequence of axes
+fn axes_arg(py: Python, axes: &PyObject, ndims: usize) -> PyResult<Vec<u32>> {
+    let axes = match axes.extract::<i64>(py) {
+        Ok(axis) => vec![axis],
+        Err(_) => axes.extract::<Vec<i64>>(py)?,
+    };
+    axes.into_iter()
+        .map(|axis| normalize_axis(axis, ndims))
+        .collect()
+}
+
+/// Move the axes `source` to the positions `destination`, the other axes keep their relative
+/// order. Like numpy's `moveaxis`, e.g. `moveaxis(a, 1, -1)` turns NCHW into NHWC.
+///
+/// `source` and `destination` are either axes or sequences of the same number of axes.
+/// `NdArrayI` inputs return an `NdArrayI`, everything else returns an `NdArrayD`.
+#[pyfunction]
+pub fn moveaxis(
+    py: Python,
+    inp: PyObject,
+    source: PyObject,
+    destination: PyObject,
+) -> PyResult<PyObject> {
+    if let Ok(inp) = inp.extract::<PyRef<NdArrayI>>(py) {
+        let ndims = inp.inner.shape().as_slice().len();
+        let res = inp
+            .inner
+            .move_axes(
+                &axes_arg(py, &source, ndims)?,
+                &axes_arg(py, &destination, ndims)?,
+            )
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+        return Ok(NdArrayI { inner: res }.into_py(py));
+    }
+    unwrap_obj!(py, inp);
+    let ndims = inp.inner.shape().as_slice().len();
+    let res = inp
+        .inner
+        .move_axes(
+            &axes_arg(py, &source, ndims)?,
+            &axes_arg(py, &destination, ndims)?,
+        )
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+    Ok(NdArrayD { inner: res }.into_py(py))
+}
+
+fn check_bmm_shapes(a: &Shape, b: &Shape) -> PyResult<()> {
+    match (a.as_slice(), b.as_slice()) {
+        ([batch_a, _, k_a], [batch_b, k_b, _]) => {
+            if batch_a != batch_b {
+                return Err(PyValueError::new_err(format!(
+                    "bmm expects equal batch sizes, got shapes {:?} and {:?}",
+                    a, b
+                )));
+            }
+            if k_a != k_b {
+                return Err(PyValueError::new_err(format!(
+                    "bmm expects [batch, m, k] and [batch, k, n] shapes, got {:?} and {:?}",
+                    a, b
+                )));
+            }
+            Ok(())
+        }
+        (a, b) => Err(PyValueError::new_err(format!(
+            "bmm expects 3-D inputs, got {}-D and {}-D arrays",
+            a.len(),
+            b.len()
+        ))),
+    }
+}
+
+/// Batched matrix multiplication of `[batch, m, k]` and `[batch, k, n]` arrays, returning a
+/// `[batch, m, n]` array. Like `torch.bmm`.
+///
+/// Unlike `matmul` no broadcasting takes place, both inputs must be 3-D with equal batch sizes.
+/// Two `NdArrayI` inputs return an `NdArrayI`, everything else returns an `NdArrayD`.
+#[pyfunction]
+pub fn bmm(py: Python, a: PyObject, b: PyObject) -> PyResult<PyObject> {
+    if let (Ok(a), Ok(b)) = (
+        a.extract::<PyRef<NdArrayI>>(py),
+        b.extract::<PyRef<NdArrayI>>(py),
+    ) {
+        check_bmm_shapes(a.inner.shape(), b.inner.shape())?;
+        let mut res = NdArray::new(0);
+        a.inner
+            .matmul(&b.inner, &mut res)
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+        return Ok(NdArrayI { inner: res }.into_py(py));
+    }
+    unwrap_obj!(py, a);
+    unwrap_obj!(py, b);
+    check_bmm_shapes(a.inner.shape(), b.inner.shape())?;
+    let mut res = NdArray::new(0);
+    a.inner
+        .matmul_f32(&b.inner, &mut res)
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+    Ok(NdArrayD { inner: res }.into_py(py))
+}
+
+/// Calculate the distances between each row of `a` and each row of `b`, returning an
+/// `[n_a, n_b]` matrix. `b` defaults to `a`.
+///
+/// `metric` is either `"euclidean"` (the default) or `"manhattan"`.
+#[pyfunction]
+pub fn pairwise_distances(
+    py: Python,
+    a: PyObject,
+    b: Option<PyObject>,
+    metric: Option<&str>,
+) -> PyResult<NdArrayD> {
+    let metric = match metric.unwrap_or("euclidean") {
+        "euclidean" => facet_core::DistanceMetric::Euclidean,
+        "manhattan" => facet_core::DistanceMetric::Manhattan,
+        metric => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown metric {}, expected one of euclidean, manhattan",
+                metric
+            )))
+        }
+    };
+    unwrap_obj!(py, a);
+    let res = match b {
+        Some(b) => {
+            unwrap_obj!(py, b);
+            facet_core::pairwise_distances_f32(&a.inner, &b.inner, metric)
+        }
+        None => facet_core::pairwise_distances_f32(&a.inner, &a.inner, metric),
+    };
+    res.map(|inner| NdArrayD { inner })
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Calculate the `n`-th discrete difference along `axis`. Like numpy's `diff`.
+///
+/// `n` defaults to 1 and `axis` to the last axis.
+#[pyfunction]
+pub fn diff(py: Python, inp: PyObject, n: Option<u32>, axis: Option<i64>) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+    let axis = normalize_axis(axis.unwrap_or(-1), inp.inner.shape().as_slice().len())?;
+
+    inp.inner
+        .diff(axis, n.unwrap_or(1))
+        .map(|inner| NdArrayD { inner })
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Estimate the gradient of the samples in `inp` along `axis` with central differences, and
+/// one-sided differences at the boundaries. Like numpy's `gradient`.
+///
+/// `axis` defaults to the last axis, `spacing`, the distance between the samples, to 1. The
+/// result has the shape of `inp`. `axis` must hold at least 2 samples.
+#[pyfunction]
+pub fn gradient(
+    py: Python,
+    inp: PyObject,
+    axis: Option<i64>,
+    spacing: Option<f32>,
+) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+    let axis = normalize_axis(axis.unwrap_or(-1), inp.inner.shape().as_slice().len())?;
+    let size = inp.inner.shape().as_slice()[axis as usize];
+    if size < 2 {
+        return Err(PyValueError::new_err(format!(
+            "gradient needs at least 2 samples along axis {}, got {}",
+            axis, size
+        )));
+    }
+    let h = spacing.unwrap_or(1.0);
+
+    inp.inner
+        .map_axis(axis, |lane| {
+            let n = lane.len();
+            (0..n)
+                .map(|i| match i {
+                    0 => (lane[1] - lane[0]) / h,
+                    i if i == n - 1 => (lane[i] - lane[i - 1]) / h,
+                    i => (lane[i + 1] - lane[i - 1]) / (2.0 * h),
+                })
+                .collect()
+        })
+        .map(|inner| NdArrayD { inner })
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Accumulate each lane along `axis` of `inp` with `f`, keeping the running value of every step
+fn accumulate_axis(
+    py: Python,
+    inp: PyObject,
+    axis: Option<i64>,
+    f: fn(f32, f32) -> f32,
+) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+    let axis = normalize_axis(axis.unwrap_or(-1), inp.inner.shape().as_slice().len())?;
+
+    inp.inner
+        .map_axis(axis, |lane| {
+            lane.iter()
+                .scan(None, |acc: &mut Option<f32>, x| {
+                    let y = acc.map_or(*x, |acc| f(acc, *x));
+                    *acc = Some(y);
+                    Some(y)
+                })
+                .collect()
+        })
+        .map(|inner| NdArrayD { inner })
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Running maximum along `axis`. Like numpy's `maximum.accumulate`.
+///
+/// `axis` defaults to the last axis. NaN values propagate.
+#[pyfunction]
+pub fn cummax(py: Python, inp: PyObject, axis: Option<i64>) -> PyResult<NdArrayD> {
+    accumulate_axis(
+        py,
+        inp,
+        axis,
+        |a, b| if a.is_nan() || a >= b { a } else { b },
+    )
+}
+
+/// Running minimum along `axis`. Like numpy's `minimum.accumulate`.
+///
+/// `axis` defaults to the last axis. NaN values propagate.
+#[pyfunction]
+pub fn cummin(py: Python, inp: PyObject, axis: Option<i64>) -> PyResult<NdArrayD> {
+    accumulate_axis(
+        py,
+        inp,
+        axis,
+        |a, b| if a.is_nan() || a <= b { a } else { b },
+    )
+}
+
+/// Return the index of the bin each value of `inp` falls into. Like numpy's `digitize`.
+///
+/// `bins` must be monotonically increasing or decreasing. For increasing bins the index `i`
+/// satisfies `bins[i-1] <= x < bins[i]`, or `bins[i-1] < x <= bins[i]` if `right` is true. Values
+/// below the first edge get index 0, values past the last edge `len(bins)`.
+#[pyfunction]
+pub fn digitize(
+    py: Python,
+    inp: PyObject,
+    bins: Vec<f32>,
+    right: Option<bool>,
+) -> PyResult<NdArrayI> {
+    unwrap_obj!(py, inp);
+    let right = right.unwrap_or(false);
+
+    let increasing = bins.windows(2).all(|w| w[0] <= w[1]);
+    let decreasing = bins.windows(2).all(|w| w[0] >= w[1]);
+    if !increasing && !decreasing {
+        return Err(PyValueError::new_err(
+            "bins must be monotonically increasing or decreasing",
+        ));
+    }
+
+    let res = inp.inner.map(|x| {
+        let i = match (increasing, right) {
+            (true, false) => bins.partition_point(|b| b <= x),
+            (true, true) => bins.partition_point(|b| b < x),
+            (false, false) => bins.partition_point(|b| b > x),
+            (false, true) => bins.partition_point(|b| b >= x),
+        };
+        i as i64
+    });
+    Ok(NdArrayI { inner: res })
+}
+
+/// Generate the Vandermonde matrix of the vector `inp`, where column `j` holds `inp ** (n - 1 - j)`.
+/// Like numpy's `vander`.
+///
+/// `n`, the number of columns, defaults to the length of `inp`.
+#[pyfunction]
+pub fn vander(py: Python, inp: PyObject, n: Option<u32>) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+    if !matches!(inp.inner.shape(), Shape::Vector(_)) {
+        return Err(PyValueError::new_err(format!(
+            "vander expects a vector, got shape {:?}",
+            inp.inner.shape()
+        )));
+    }
+    let x = inp.inner.as_slice();
+    let n = n.unwrap_or(x.len() as u32);
+    let values = x
+        .iter()
+        .flat_map(|x| (0..n).rev().map(move |p| x.powi(p as i32)))
+        .collect();
+    NdArray::new_with_values([x.len() as u32, n], values)
+        .map(|inner| NdArrayD { inner })
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Evaluate the polynomial with the coefficients `coeffs`, highest degree first, at `x` using
+/// Horner's method. Like numpy's `polyval`.
+///
+/// Returns a number if `x` is a number, an array of the shape of `x` otherwise.
+#[pyfunction]
+pub fn polyval(py: Python, coeffs: Vec<f32>, x: PyObject) -> PyResult<PyObject> {
+    let horner = |x: f32| coeffs.iter().fold(0.0, |acc, c| acc * x + c);
+    if let Ok(x) = x.extract::<f32>(py) {
+        return Ok(horner(x).into_py(py));
+    }
+    unwrap_obj!(py, x);
+    let inner = x.inner.map(|x| horner(*x));
+    Ok(NdArrayD { inner }.into_py(py))
+}
+
+/// Piecewise linear interpolation of the data points `(xp, fp)` at `inp`. Like numpy's `interp`.
+///
+/// `xp` must be increasing. Values outside of the range of `xp` are clamped to the first or last
+/// value of `fp`.
+#[pyfunction]
+pub fn interp(py: Python, inp: PyObject, xp: Vec<f32>, fp: Vec<f32>) -> PyResult<NdArrayD> {
+    unwrap_obj!(py, inp);
+    if xp.is_empty() || xp.len() != fp.len() {
+        return Err(PyValueError::new_err(format!(
+            "xp and fp must be non-empty and of equal length, got {} and {}",
+            xp.len(),
+            fp.len()
+        )));
+    }
+    if !xp.windows(2).all(|w| w[0] <= w[1]) {
+        return Err(PyValueError::new_err("xp must be increasing"));
+    }
+
+    let res = inp.inner.map(|x| {
+        if x.is_nan() {
+            return *x;
+        }
+        // xp[i - 1] <= x < xp[i]
+        let i = xp.partition_point(|p| p <= x);
+        if i == 0 {
+            fp[0]
+        } else if i == xp.len() {
+            fp[i - 1]
+        } else {
+            let t = (x - xp[i - 1]) / (xp[i] - xp[i - 1]);
+            fp[i - 1] + t * (fp[i] - fp[i - 1])
+        }
+    });
+    Ok(NdArrayD { inner: res })
+}
+
+/// Return the indices where the values of `v` would have to be inserted into the sorted vector
+/// `a` to keep it sorted. Like numpy's `searchsorted`.
+///
+/// `side` selects the index for values equal to an element of `a`: `"left"` (the default) returns
+/// the first suitable index, `"right"` the last.
+#[pyfunction]
+pub fn searchsorted(
+    py: Python,
+    a: PyObject,
+    v: PyObject,
+    side: Option<&str>,
+) -> PyResult<NdArrayI> {
+    unwrap_obj!(py, a);
+    unwrap_obj!(py, v);
+    if a.inner.shape().as_slice().len() > 1 {
+        return Err(PyValueError::new_err(format!(
+            "a must be a vector, got shape {:?}",
+            a.inner.shape()
+        )));
+    }
+    let a = a.inner.as_slice();
+    if !a.windows(2).all(|w| w[0] <= w[1]) {
+        return Err(PyValueError::new_err(
+            "a must be sorted in increasing order",
+        ));
+    }
+    let right = match side.unwrap_or("left") {
+        "left" => false,
+        "right" => true,
+        side => {
+            return Err(PyValueError::new_err(format!(
+                "side must be either 'left' or 'right', got {:?}",
+                side
+            )))
+        }
+    };
+
+    let res = v.inner.map(|x| {
+        let i = if right {
+            a.partition_point(|y| y <= x)
+        } else {
+            a.partition_point(|y| y < x)
+        };
+        i as i64
+    });
+    Ok(NdArrayI { inner: res })
+}
+
+/// Return the sorted distinct values of `inp` as a vector. Like numpy's `unique`.
+///
+/// `inp` is flattened first. Values are compared with exact equality, so values differing only
+/// in rounding error are kept as distinct entries.
+///
+/// If `return_counts` is true return a tuple of `(values, counts)`, where `counts[i]` is the
+/// number of occurrences of `values[i]`.
+#[pyfunction]
+pub fn unique(py: Python, inp: PyObject, return_counts: Option<bool>) -> PyResult<PyObject> {
+    unwrap_obj!(py, inp);
+
+    let mut sorted = inp.inner.as_slice().to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    let mut values = Vec::with_capacity(sorted.len());
+    let mut counts = Vec::with_capacity(sorted.len());
+    for x in sorted {
+        match values.last() {
+            Some(last) if *last == x => *counts.last_mut().unwrap() += 1,
+            _ => {
+                values.push(x);
+                counts.push(1i64);
+            }
+        }
+    }
+
+    let values = NdArrayD {
+        inner: NdArray::new_vector(values),
+    };
+    if return_counts.unwrap_or(false) {
+        let counts = NdArrayI {
+            inner: NdArray::new_vector(counts),
+        };
+        Ok((values, counts).into_py(py))
+    } else {
+        Ok(values.into_py(py))
+    }
+}
+
+/// Convert the flat, row-major `index` into the multi-dimensional index of an array of `shape`,
+/// returned as a tuple. Like numpy's `unravel_index`.
+#[pyfunction]
+pub fn unravel_index(py: Python, index: usize, shape: Vec<u32>) -> PyResult<Py<PyTuple>> {
+    let res = Shape::from(shape.as_slice())
+        .unravel_index(index)
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "index {} is out of bounds for an array of shape {:?}",
+                index, shape
+            ))
+        })?;
+    Ok(PyTuple::new(py, res).into())
+}
+
+/// Convert the multi-dimensional `indices` into the flat, row-major index of an array of `shape`.
+/// Inverse of `unravel_index`, like numpy's `ravel_multi_index`.
+#[pyfunction]
+pub fn ravel_multi_index(indices: Vec<u32>, shape: Vec<u32>) -> PyResult<usize> {
+    Shape::from(shape.as_slice())
+        .ravel_index(&indices)
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "index {:?} is invalid for an array of shape {:?}",
+                indices, shape
+            ))
+        })
+}
+
+/// Iterate over `(index, value)` pairs in row-major order, where `index` is a tuple. Like numpy's
+/// `ndenumerate`.
+#[pyfunction]
+pub fn ndenumerate(py: Python, inp: PyObject) -> PyResult<NdEnumerate> {
+    unwrap_obj!(py, inp);
+    Ok(NdEnumerate::new(&inp.inner))
+}
+
+/// Call `f` `repeats` times, returning a dict of the `mean`, `min` and `max` duration of a call in
+/// seconds.
+///
+/// The GIL can not be released around the calls, as `f` is a Python callable. Operations that
+/// run on the crate's thread pool still use all of its threads.
+#[pyfunction]
+pub fn bench(py: Python, f: PyObject, repeats: Option<u32>) -> PyResult<PyObject> {
+    use std::time::Instant;
+
+    let repeats = repeats.unwrap_or(100);
+    if repeats == 0 {
+        return Err(PyValueError::new_err("repeats must be positive"));
+    }
+    let mut total = 0.0;
+    let mut min = f64::INFINITY;
+    let mut max = 0.0f64;
+    for _ in 0..repeats {
+        let start = Instant::now();
+        f.call0(py)?;
+        let elapsed = start.elapsed().as_secs_f64();
+        total += elapsed;
+        min = min.min(elapsed);
+        max = max.max(elapsed);
+    }
+
+    let res = PyDict::new(py);
+    res.set_item("mean", total / repeats as f64)?;
+    res.set_item("min", min)?;
+    res.set_item("max", max)?;
+    Ok(res.into())
+}
+
+/// Set the number of threads used by parallel operations.
+///
+/// `0` uses the number of logical CPUs.
+#[pyfunction]
+pub fn set_num_threads(n: usize) -> PyResult<()> {
+    thread_pool::set_num_threads(n)
+        .map_err(|err| PyValueError::new_err(format!("Failed to build thread pool {}", err)))
+}
+
+/// Number of threads used by parallel operations
+#[pyfunction]
+pub fn get_num_threads() -> usize {
+    thread_pool::num_threads()
+}
+
 #[pymodule]
 fn pyfacet(py: Python, m: &PyModule) -> PyResult<()> {
-    pyndarray::setup_module(py, &m)?;
-    activation::setup_module(py, &m)?;
-    io::setup_module(py, &m)?;
-    loss::setup_module(py, &m)?;
-    layer::setup_module(py, &m)?;
+    pyndarray::setup_module(py, m)?;
+    activation::setup_module(py, m)?;
+    autograd::setup_module(py, m)?;
+    dlpack::setup_module(py, m)?;
+    fft::setup_module(py, m)?;
+    io::setup_module(py, m)?;
+    loss::setup_module(py, m)?;
+    layer::setup_module(py, m)?;
+    random::setup_module(py, m)?;
+    sparse::setup_module(py, m)?;
+    debug::setup_module(py, m)?;
+    stats::setup_module(py, m)?;
 
     m.add_function(wrap_pyfunction!(eye, m)?)?;
     m.add_function(wrap_pyfunction!(diagflat, m)?)?;
+    m.add_function(wrap_pyfunction!(tri, m)?)?;
+    m.add_function(wrap_pyfunction!(tril, m)?)?;
+    m.add_function(wrap_pyfunction!(triu, m)?)?;
     m.add_function(wrap_pyfunction!(sum, m)?)?;
+    m.add_function(wrap_pyfunction!(amax, m)?)?;
+    m.add_function(wrap_pyfunction!(amin, m)?)?;
+    m.add_function(wrap_pyfunction!(prod, m)?)?;
     m.add_function(wrap_pyfunction!(scalar, m)?)?;
+    m.add_function(wrap_pyfunction!(atleast_1d, m)?)?;
+    m.add_function(wrap_pyfunction!(atleast_2d, m)?)?;
+    m.add_function(wrap_pyfunction!(block, m)?)?;
+    m.add_function(wrap_pyfunction!(block_diag, m)?)?;
+    m.add_function(wrap_pyfunction!(flatten_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(unflatten_batch, m)?)?;
     m.add_function(wrap_pyfunction!(zeros, m)?)?;
     m.add_function(wrap_pyfunction!(sqrt, m)?)?;
+    m.add_function(wrap_pyfunction!(reciprocal, m)?)?;
+    m.add_function(wrap_pyfunction!(negative, m)?)?;
+    m.add_function(wrap_pyfunction!(mod_, m)?)?;
+    m.add_function(wrap_pyfunction!(floor_divide, m)?)?;
+    m.add_function(wrap_pyfunction!(copysign, m)?)?;
+    m.add_function(wrap_pyfunction!(hypot, m)?)?;
+    m.add_function(wrap_pyfunction!(maximum, m)?)?;
+    m.add_function(wrap_pyfunction!(minimum, m)?)?;
     m.add_function(wrap_pyfunction!(argmax, m)?)?;
     m.add_function(wrap_pyfunction!(argmin, m)?)?;
     m.add_function(wrap_pyfunction!(ones, m)?)?;
+    m.add_function(wrap_pyfunction!(zeros_like, m)?)?;
+    m.add_function(wrap_pyfunction!(ones_like, m)?)?;
+    m.add_function(wrap_pyfunction!(full_like, m)?)?;
     m.add_function(wrap_pyfunction!(binomial, m)?)?;
     m.add_function(wrap_pyfunction!(mean, m)?)?;
     m.add_function(wrap_pyfunction!(clip, m)?)?;
     m.add_function(wrap_pyfunction!(log, m)?)?;
     m.add_function(wrap_pyfunction!(std_squared, m)?)?;
     m.add_function(wrap_pyfunction!(std, m)?)?;
+    m.add_function(wrap_pyfunction!(var, m)?)?;
     m.add_function(wrap_pyfunction!(moving_average, m)?)?;
+    m.add_function(wrap_pyfunction!(cov, m)?)?;
+    m.add_function(wrap_pyfunction!(corrcoef, m)?)?;
     m.add_function(wrap_pyfunction!(veclen, m)?)?;
     m.add_function(wrap_pyfunction!(veclen_squared, m)?)?;
     m.add_function(wrap_pyfunction!(normalize_vectors, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize, m)?)?;
+    m.add_function(wrap_pyfunction!(clamp_norm, m)?)?;
+    m.add_function(wrap_pyfunction!(logsumexp, m)?)?;
+    m.add_function(wrap_pyfunction!(mean_var, m)?)?;
+    m.add_function(wrap_pyfunction!(apply_along_axis, m)?)?;
     m.add_function(wrap_pyfunction!(fast_inverse_sqrt, m)?)?;
     m.add_function(wrap_pyfunction!(abs, m)?)?;
+    m.add_function(wrap_pyfunction!(isnan, m)?)?;
+    m.add_function(wrap_pyfunction!(isinf, m)?)?;
+    m.add_function(wrap_pyfunction!(isfinite, m)?)?;
+    m.add_function(wrap_pyfunction!(gather, m)?)?;
+    m.add_function(wrap_pyfunction!(scatter, m)?)?;
+    m.add_function(wrap_pyfunction!(take_along_axis, m)?)?;
+    m.add_function(wrap_pyfunction!(argsort, m)?)?;
+    m.add_function(wrap_pyfunction!(partition, m)?)?;
+    m.add_function(wrap_pyfunction!(argpartition, m)?)?;
+    m.add_function(wrap_pyfunction!(top_k, m)?)?;
+    m.add_function(wrap_pyfunction!(masked_select, m)?)?;
+    m.add_function(wrap_pyfunction!(choose, m)?)?;
+    m.add_function(wrap_pyfunction!(insert, m)?)?;
+    m.add_function(wrap_pyfunction!(delete, m)?)?;
+    m.add_function(wrap_pyfunction!(split_at, m)?)?;
+    m.add_function(wrap_pyfunction!(bmm, m)?)?;
+    m.add_function(wrap_pyfunction!(pairwise_distances, m)?)?;
+    m.add_function(wrap_pyfunction!(rot90, m)?)?;
+    m.add_function(wrap_pyfunction!(moveaxis, m)?)?;
+    m.add_function(wrap_pyfunction!(unique, m)?)?;
+    m.add_function(wrap_pyfunction!(digitize, m)?)?;
+    m.add_function(wrap_pyfunction!(interp, m)?)?;
+    m.add_function(wrap_pyfunction!(vander, m)?)?;
+    m.add_function(wrap_pyfunction!(polyval, m)?)?;
+    m.add_function(wrap_pyfunction!(searchsorted, m)?)?;
+    m.add_function(wrap_pyfunction!(diff, m)?)?;
+    m.add_function(wrap_pyfunction!(gradient, m)?)?;
+    m.add_function(wrap_pyfunction!(cummax, m)?)?;
+    m.add_function(wrap_pyfunction!(cummin, m)?)?;
+    m.add_function(wrap_pyfunction!(ndenumerate, m)?)?;
+    m.add_function(wrap_pyfunction!(unravel_index, m)?)?;
+    m.add_function(wrap_pyfunction!(ravel_multi_index, m)?)?;
+    m.add_function(wrap_pyfunction!(set_num_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(get_num_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(bench, m)?)?;
 
     Ok(())
 }
@@ -1,4 +1,5 @@
 use crate::pyndarray::NdArrayD;
+use facet_core::loss::{BinaryCrossEntropy, Loss, MeanSquaredError};
 use pyo3::{exceptions::PyValueError, prelude::*, wrap_pyfunction};
 
 /// The author recommends running `softmax` on the output before calling this function
@@ -9,7 +10,58 @@ pub fn categorical_cross_entropy(predictions: &NdArrayD, targets: &NdArrayD) ->
         .map(|inner| NdArrayD { inner })
 }
 
+fn loss_forward(
+    loss: impl Loss,
+    name: &str,
+    predictions: &NdArrayD,
+    targets: &NdArrayD,
+) -> PyResult<NdArrayD> {
+    loss.loss(&predictions.inner, &targets.inner)
+        .map_err(|err| PyValueError::new_err(format!("Failed to perform {}: {}", name, err)))
+        .map(|inner| NdArrayD { inner })
+}
+
+fn loss_backward(
+    loss: impl Loss,
+    name: &str,
+    predictions: &NdArrayD,
+    targets: &NdArrayD,
+) -> PyResult<NdArrayD> {
+    loss.backward(&predictions.inner, &targets.inner)
+        .map_err(|err| PyValueError::new_err(format!("Failed to perform d{}: {}", name, err)))
+        .map(|inner| NdArrayD { inner })
+}
+
+/// Mean of the binary cross entropy of the outputs of each sample. Expects the predictions to be
+/// in the interval [0, 1], e.g. the output of `sigmoid`
+#[pyfunction]
+pub fn binary_cross_entropy(predictions: &NdArrayD, targets: &NdArrayD) -> PyResult<NdArrayD> {
+    loss_forward(BinaryCrossEntropy, "BCE", predictions, targets)
+}
+
+/// Gradient of the mean `binary_cross_entropy` with respect to the predictions
+#[pyfunction]
+pub fn dbinary_cross_entropy(predictions: &NdArrayD, targets: &NdArrayD) -> PyResult<NdArrayD> {
+    loss_backward(BinaryCrossEntropy, "BCE", predictions, targets)
+}
+
+/// Mean of the squared errors of the outputs of each sample
+#[pyfunction]
+pub fn mean_squared_error(predictions: &NdArrayD, targets: &NdArrayD) -> PyResult<NdArrayD> {
+    loss_forward(MeanSquaredError, "MSE", predictions, targets)
+}
+
+/// Gradient of the mean `mean_squared_error` with respect to the predictions
+#[pyfunction]
+pub fn dmean_squared_error(predictions: &NdArrayD, targets: &NdArrayD) -> PyResult<NdArrayD> {
+    loss_backward(MeanSquaredError, "MSE", predictions, targets)
+}
+
 pub fn setup_module(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(categorical_cross_entropy, m)?)?;
+    m.add_function(wrap_pyfunction!(binary_cross_entropy, m)?)?;
+    m.add_function(wrap_pyfunction!(dbinary_cross_entropy, m)?)?;
+    m.add_function(wrap_pyfunction!(mean_squared_error, m)?)?;
+    m.add_function(wrap_pyfunction!(dmean_squared_error, m)?)?;
     Ok(())
 }
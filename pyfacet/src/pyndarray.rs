@@ -1,20 +1,33 @@
 mod arrayimpl;
 mod factory;
+mod view;
 
 pub use self::arrayimpl::*;
+pub use self::view::*;
 
+use facet_core::ndarray::{shape::Shape, NdArray};
 use pyo3::{
-    exceptions::PyNotImplementedError, exceptions::PyValueError, prelude::*, types::PyList,
-    wrap_pyfunction,
+    exceptions::PyNotImplementedError,
+    exceptions::PyTypeError,
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyList, PyTuple},
+    wrap_pyfunction, PyIterProtocol,
 };
 use std::convert::TryFrom;
 
 pub fn setup_module(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(array, m)?)?;
+    m.add_function(wrap_pyfunction!(array_from, m)?)?;
     m.add_class::<NdArrayD>()?;
     m.add_class::<NdArrayB>()?;
     m.add_class::<NdArrayI>()?;
+    m.add_class::<NdArrayI32>()?;
+    m.add_class::<NdArrayU8>()?;
+    m.add_class::<NdArrayC>()?;
+    m.add_class::<NdArrayView>()?;
     m.add_class::<PyNdIndex>()?;
+    m.add_class::<NdEnumerate>()?;
     Ok(())
 }
 
@@ -37,16 +50,67 @@ impl PyNdIndex {
                 vec![n]
             }
         } else {
-            todo!()
+            return Err(PyTypeError::new_err(format!(
+                "Expected a shape as an integer or a list of integers, got {}",
+                inp.get_type().name()?
+            )));
         };
         Ok(Self { inner: shape })
     }
 }
 
+/// Parse the `order` argument of numpy style functions. Returns true for column-major (`"F"`),
+/// false for row-major (`"C"`, the default) order.
+pub fn is_fortran_order(order: Option<&str>) -> PyResult<bool> {
+    match order.unwrap_or("C") {
+        "C" => Ok(false),
+        "F" => Ok(true),
+        order => Err(PyValueError::new_err(format!(
+            "order must be either 'C' or 'F', got {:?}",
+            order
+        ))),
+    }
+}
+
+/// Iterator over `(index, value)` pairs of an array, see `ndenumerate`
+#[pyclass]
+pub struct NdEnumerate {
+    iter: std::vec::IntoIter<(Vec<u32>, f32)>,
+}
+
+impl NdEnumerate {
+    pub fn new(arr: &NdArray<f32>) -> Self {
+        let items: Vec<_> = arr
+            .iter_indexed()
+            .map(|(index, x)| (index.to_vec(), *x))
+            .collect();
+        Self {
+            iter: items.into_iter(),
+        }
+    }
+}
+
+#[pyproto]
+impl PyIterProtocol for NdEnumerate {
+    fn __iter__(this: PyRef<Self>) -> PyRef<Self> {
+        this
+    }
+
+    fn __next__(mut this: PyRefMut<Self>) -> Option<(PyObject, f32)> {
+        let (index, x) = this.iter.next()?;
+        let py = this.py();
+        Some((PyTuple::new(py, index).into(), x))
+    }
+}
+
 type Factory = fn(Python, Vec<u32>, &PyList) -> Result<Py<PyAny>, PyErr>;
 
 #[pyfunction]
 pub fn array(py: Python, shape: PyObject) -> PyResult<PyObject> {
+    // numpy arrays, torch tensors etc. are copied in one go instead of going through lists
+    if shape.as_ref(py).hasattr("__dlpack__")? {
+        return crate::dlpack::from_dlpack(py, shape.as_ref(py));
+    }
     let mut dims = Vec::new();
     let shape: &PyList = shape.extract(py).or_else(|_| {
         shape
@@ -60,6 +124,12 @@ pub fn array(py: Python, shape: PyObject) -> PyResult<PyObject> {
         let mut shape = shape;
         loop {
             dims.push(u32::try_from(shape.len()).expect("expected dimensions to fit into 32 bits"));
+            if shape.is_empty() {
+                // a 0 long dimension would be interpreted as a scalar
+                return Err(PyValueError::new_err(
+                    "Can not create an array from an empty list",
+                ));
+            }
             let i = shape.get_item(0);
             if let Ok(i) = i.downcast() {
                 shape = i;
@@ -78,3 +148,25 @@ pub fn array(py: Python, shape: PyObject) -> PyResult<PyObject> {
 
     factory(py, dims, shape)
 }
+
+/// Create an array of the given `shape` from a flat list of values.
+///
+/// The length of `data` must equal the number of elements spanned by `shape`.
+#[pyfunction]
+pub fn array_from(data: Vec<f32>, shape: Vec<u32>) -> PyResult<NdArrayD> {
+    let shape = Shape::from(shape);
+    let span = shape.checked_span().ok_or_else(|| {
+        PyValueError::new_err(format!("Shape {:?} is too large", shape.as_slice()))
+    })?;
+    if data.len() != span {
+        return Err(PyValueError::new_err(format!(
+            "Shape {:?} requires {} values, got {}",
+            shape.as_slice(),
+            span,
+            data.len()
+        )));
+    }
+    NdArray::new_with_values(shape, data.into())
+        .map(|inner| NdArrayD { inner })
+        .map_err(|err| PyValueError::new_err(format!("Failed to create nd-array: {}", err)))
+}
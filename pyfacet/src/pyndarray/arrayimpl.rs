@@ -1,11 +1,17 @@
 mod ndbool;
+mod ndc64;
 mod ndf32;
+mod ndi32;
 mod ndi64;
+mod ndu8;
 
 use facet_core::ndarray::NdArray;
 pub use ndbool::*;
+pub use ndc64::*;
 pub use ndf32::*;
+pub use ndi32::*;
 pub use ndi64::*;
+pub use ndu8::*;
 
 use pyo3::{exceptions::PyValueError, prelude::*, PyClass};
 use std::{
@@ -33,19 +39,10 @@ trait AsNumArray: PyClass {
     where
         F: Fn(&Self::T, &Self::T) -> bool,
     {
-        // TODO: check shape and raise error on uncomparable shapes
-        // TODO: support different shapes e.g. compare each element to a scalar
-        let a = self.cast();
-        let b = other.cast();
-        let values: Vec<_> = a
-            .as_slice()
-            .iter()
-            .zip(b.as_slice().iter())
-            .map(|(a, b)| op(a, b))
-            .collect();
-        let mut res = NdArray::<bool>::new_vector(values);
-        res.reshape(a.shape().clone());
-        Ok(NdArrayB { inner: res })
+        self.cast()
+            .broadcast_with(other.cast(), |a, b| op(&a, &b))
+            .map(|inner| NdArrayB { inner })
+            .map_err(|err| PyValueError::new_err::<String>(format!("{}", err)))
     }
 
     fn add(lhs: PyRef<Self>, rhs: PyRef<Self>) -> PyResult<NdArray<Self::T>> {
@@ -79,7 +76,112 @@ trait AsNumArray: PyClass {
     fn pow(lhs: PyRef<Self>, rhs: Self::T) -> PyResult<NdArray<Self::T>>;
 }
 
-#[macro_export(internal_macros)]
+/// Element types `astype` converts between
+///
+/// Integer conversions saturate at the bounds of the target type, float to int conversions
+/// truncate and NaN becomes 0.
+pub trait Cast: Copy {
+    fn to_f32(self) -> f32;
+    fn to_i64(self) -> i64;
+    fn to_bool(self) -> bool;
+
+    fn to_i32(self) -> i32 {
+        self.to_i64().clamp(i32::MIN as i64, i32::MAX as i64) as i32
+    }
+
+    fn to_u8(self) -> u8 {
+        self.to_i64().clamp(0, u8::MAX as i64) as u8
+    }
+}
+
+impl Cast for f32 {
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn to_i64(self) -> i64 {
+        self as i64
+    }
+
+    fn to_bool(self) -> bool {
+        self != 0.0
+    }
+}
+
+impl Cast for bool {
+    fn to_f32(self) -> f32 {
+        if self {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn to_i64(self) -> i64 {
+        self as i64
+    }
+
+    fn to_bool(self) -> bool {
+        self
+    }
+}
+
+macro_rules! impl_cast_int {
+    ($($ty: ty),*) => {
+        $(
+            impl Cast for $ty {
+                fn to_f32(self) -> f32 {
+                    self as f32
+                }
+
+                fn to_i64(self) -> i64 {
+                    self as i64
+                }
+
+                fn to_bool(self) -> bool {
+                    self != 0
+                }
+            }
+        )*
+    };
+}
+
+impl_cast_int!(i64, i32, u8);
+
+/// Convert `arr` to the array type of `dtype`, one of `"f32"`, `"i64"`, `"i32"`, `"u8"` or `"bool"`
+pub fn astype<T: Cast>(py: Python, arr: &NdArray<T>, dtype: &str) -> PyResult<PyObject> {
+    let res = match dtype {
+        "f32" => NdArrayD {
+            inner: arr.map(|x| x.to_f32()),
+        }
+        .into_py(py),
+        "i64" => NdArrayI {
+            inner: arr.map(|x| x.to_i64()),
+        }
+        .into_py(py),
+        "i32" => NdArrayI32 {
+            inner: arr.map(|x| x.to_i32()),
+        }
+        .into_py(py),
+        "u8" => NdArrayU8 {
+            inner: arr.map(|x| x.to_u8()),
+        }
+        .into_py(py),
+        "bool" => NdArrayB {
+            inner: arr.map(|x| x.to_bool()),
+        }
+        .into_py(py),
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown dtype {}, expected one of f32, i64, i32, u8, bool",
+                dtype
+            )))
+        }
+    };
+    Ok(res)
+}
+
+#[macro_export]
 macro_rules! impl_ndarray {
     ($ty: ty, $name: ident, $inner: ident, $mod: ident) => {
         mod $mod {
@@ -89,7 +191,7 @@ macro_rules! impl_ndarray {
             use pyo3::{
                 exceptions::{PyIndexError, PyValueError},
                 prelude::*,
-                PyGCProtocol, PyIterProtocol, PyMappingProtocol,
+                PyGCProtocol, PyIterProtocol, PyMappingProtocol, PyNativeType,
             };
 
             impl From<NdArray<$ty>> for $name {
@@ -175,7 +277,12 @@ macro_rules! impl_ndarray {
                     // transmute the lifetime, we know this is safe because the iterator will hold
                     // a reference to this array, and Python is single threaded, so no mutations
                     // _should_ occur during iteration
-                    let iter = unsafe { std::mem::transmute(iter) };
+                    let iter = unsafe {
+                        std::mem::transmute::<
+                            Box<dyn Iterator<Item = $ty> + Send + '_>,
+                            Box<dyn Iterator<Item = $ty> + Send + 'static>,
+                        >(iter)
+                    };
                     let iter = ItemIter {
                         iter,
                         arr: Some(this.into()),
@@ -195,7 +302,9 @@ macro_rules! impl_ndarray {
                                 PyValueError::new_err::<String>(format!("{}", err).into())
                             })?
                         }
-                        None => NdArray::new_default(shape.inner),
+                        None => NdArray::try_new_default(shape.inner).map_err(|err| {
+                            PyValueError::new_err::<String>(format!("{}", err).into())
+                        })?,
                     };
                     Ok(Self { inner })
                 }
@@ -216,14 +325,37 @@ macro_rules! impl_ndarray {
                     self.transpose()
                 }
 
+                /// Reshape in place. `order` selects the order the values are read and written
+                /// in, `"C"` (the default) for row-major and `"F"` for column-major order.
                 pub fn reshape(
                     mut this: PyRefMut<Self>,
                     new_shape: Vec<u32>,
+                    order: Option<String>,
                 ) -> PyResult<PyRefMut<Self>> {
-                    this.inner.reshape(new_shape);
+                    if crate::pyndarray::is_fortran_order(order.as_deref())? {
+                        // reshape the column-major values into the reversed shape
+                        let mut res = this.inner.reverse_axes();
+                        res.reshape(new_shape.into_iter().rev().collect::<Vec<_>>());
+                        this.inner = res.reverse_axes();
+                    } else {
+                        this.inner.reshape(new_shape);
+                    }
                     Ok(this)
                 }
 
+                /// Return a copy collapsed into a vector. `order` is `"C"` (the default) for
+                /// row-major and `"F"` for column-major order.
+                pub fn flatten(&self, order: Option<&str>) -> PyResult<Self> {
+                    let mut inner = if crate::pyndarray::is_fortran_order(order)? {
+                        self.inner.reverse_axes()
+                    } else {
+                        self.inner.clone()
+                    };
+                    let n = inner.len() as u32;
+                    inner.reshape(Shape::Vector([n]));
+                    Ok(Self { inner })
+                }
+
                 pub fn get(&self, index: Vec<u32>) -> Option<$ty> {
                     self.inner.get(&index).cloned()
                 }
@@ -322,10 +454,13 @@ macro_rules! impl_ndarray {
                     Ok(self.inner.shape().span())
                 }
 
-                fn __getitem__(&self, shape: &PyAny) -> PyResult<$ty> {
-                    // TODO: not just single items.
-                    // Shape could possibly hold fewer items than our shape, meaning they want
-                    // vectors or matrices or sub-tensors returned...
+                /// Keys containing slices, e.g. `a[1:5, :, 2]`, return an `NdArrayView` sharing the
+                /// values of this array. Otherwise `shape` is the index of a single item.
+                fn __getitem__(&self, shape: &PyAny) -> PyResult<PyObject> {
+                    let py = shape.py();
+                    if crate::pyndarray::is_slice_key(shape) {
+                        return crate::pyndarray::getitem(py, &self.inner.view(), shape);
+                    }
                     let shape = PyNdIndex::new(shape)?;
                     self.inner
                         .get(&shape.inner[..])
@@ -335,7 +470,7 @@ macro_rules! impl_ndarray {
                                 shape.inner
                             ))
                         })
-                        .map(|x| x.clone())
+                        .map(|x| x.clone().into_py(py))
                 }
             }
         }
@@ -28,6 +28,12 @@ impl NdArrayB {
         self.inner.as_slice().iter().any(|x| *x)
     }
 
+    /// Convert the array to the given `dtype`, one of `"f32"`, `"i64"`, `"i32"`, `"u8"` or
+    /// `"bool"`. True becomes 1 and False becomes 0.
+    pub fn astype(&self, py: Python, dtype: &str) -> PyResult<PyObject> {
+        super::astype(py, &self.inner, dtype)
+    }
+
     /// Convert self into float representation, where True becomes 1.0 and False becomes 0.0
     pub fn as_f32(&self) -> NdArrayD {
         let values = self
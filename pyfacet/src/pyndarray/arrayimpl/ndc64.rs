@@ -0,0 +1,170 @@
+use facet_core::ndarray::{shape::Shape, NdArray};
+use num_complex::Complex;
+
+use pyo3::{
+    exceptions::{PyNotImplementedError, PyValueError},
+    prelude::*,
+    PyMappingProtocol, PyNumberProtocol, PyObjectProtocol,
+};
+
+use super::{AsNumArray, NdArrayD};
+
+/// Complex array
+///
+/// Values are stored as `Complex<f64>`. Conversions to `NdArrayD` (`real`, `imag`, `abs`) round
+/// the parts to f32.
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct NdArrayC {
+    pub inner: NdArray<Complex<f64>>,
+}
+
+impl From<NdArray<Complex<f64>>> for NdArrayC {
+    fn from(inner: NdArray<Complex<f64>>) -> Self {
+        Self { inner }
+    }
+}
+
+#[pyproto]
+impl<T> PyNumberProtocol for NdArrayC {
+    fn __add__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Self> {
+        <Self as AsNumArray>::add(lhs, rhs).map(|inner| Self { inner })
+    }
+
+    fn __sub__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Self> {
+        <Self as AsNumArray>::sub(lhs, rhs).map(|inner| Self { inner })
+    }
+
+    fn __mul__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Self> {
+        <Self as AsNumArray>::mul(lhs, rhs).map(|inner| Self { inner })
+    }
+
+    fn __truediv__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Self> {
+        <Self as AsNumArray>::truediv(lhs, rhs).map(|inner| Self { inner })
+    }
+}
+
+#[pyproto]
+impl PyObjectProtocol for NdArrayC {
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "NdArray of c64, shape: {:?}, data:\n{}",
+            self.inner.shape(),
+            self.inner
+        )
+    }
+
+    fn __bool__(&'p self) -> PyResult<bool> {
+        Err(PyNotImplementedError::new_err::<String>(
+            "Array to bool conversion is ambigous! Use .any or .all".to_string(),
+        ))
+    }
+}
+
+#[pyproto]
+impl PyMappingProtocol for NdArrayC {
+    fn __len__(&self) -> PyResult<usize> {
+        Ok(self.inner.shape().span())
+    }
+}
+
+impl AsNumArray for NdArrayC {
+    type T = Complex<f64>;
+
+    fn cast(&self) -> &NdArray<Self::T> {
+        &self.inner
+    }
+
+    fn pow(lhs: PyRef<Self>, rhs: Self::T) -> PyResult<NdArray<Complex<f64>>> {
+        let lhs: &NdArray<Self::T> = lhs.cast();
+        let res = lhs.map(|x| x.powc(rhs));
+        Ok(res)
+    }
+}
+
+impl NdArrayC {
+    fn map_real(&self, f: impl Fn(&Complex<f64>) -> f64) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.map(|x| f(x) as f32),
+        }
+    }
+}
+
+#[pymethods]
+impl NdArrayC {
+    /// Create a complex array from the real and imaginary parts. The parts must have the same
+    /// shape.
+    #[new]
+    pub fn new(real: &NdArrayD, imag: &NdArrayD) -> PyResult<Self> {
+        if real.inner.shape() != imag.inner.shape() {
+            return Err(PyValueError::new_err(format!(
+                "The real and imaginary parts must have the same shape, got {:?} and {:?}",
+                real.inner.shape(),
+                imag.inner.shape()
+            )));
+        }
+        let values: Vec<_> = real
+            .inner
+            .as_slice()
+            .iter()
+            .zip(imag.inner.as_slice().iter())
+            .map(|(re, im)| Complex::new(*re as f64, *im as f64))
+            .collect();
+        let inner = NdArray::new_with_values(real.inner.shape().clone(), values.into())
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    pub fn shape(&self) -> Vec<u32> {
+        match self.inner.shape() {
+            Shape::Scalar(_) => vec![],
+            Shape::Vector([n]) => vec![*n],
+            Shape::Matrix([n, m]) => vec![*n, *m],
+            Shape::Tensor(s) => s.clone().into_vec(),
+        }
+    }
+
+    /// Return the `(real, imag)` pair at the given index
+    pub fn get(&self, index: Vec<u32>) -> Option<(f64, f64)> {
+        self.inner.get(&index).map(|x| (x.re, x.im))
+    }
+
+    /// Complex conjugate of each element
+    pub fn conj(&self) -> Self {
+        Self {
+            inner: self.inner.map(|x| x.conj()),
+        }
+    }
+
+    /// Copies the output.
+    ///
+    /// TODO: return view
+    pub fn real(&self) -> NdArrayD {
+        self.map_real(|x| x.re)
+    }
+
+    /// Copies the output.
+    ///
+    /// TODO: return view
+    pub fn imag(&self) -> NdArrayD {
+        self.map_real(|x| x.im)
+    }
+
+    /// Magnitude of each element
+    pub fn abs(&self) -> NdArrayD {
+        self.map_real(|x| x.norm())
+    }
+
+    /// Deep-copy this instance
+    #[allow(clippy::should_implement_trait)] // this clone method is bridged to python
+    pub fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
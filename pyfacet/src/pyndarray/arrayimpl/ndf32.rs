@@ -1,4 +1,4 @@
-use facet_core::ndarray::NdArray;
+use facet_core::ndarray::{NdArray, NdArrayError};
 pub use ndarraydimpl::ItemIter as ItemIterD;
 pub use ndarraydimpl::RowIter as RowIterD;
 pub use ndarraydimpl::*;
@@ -9,40 +9,86 @@ use pyo3::{
     basic::CompareOp,
     exceptions::{PyNotImplementedError, PyValueError},
     prelude::*,
-    PyNumberProtocol, PyObjectProtocol,
+    types::{PyBytes, PyDict, PyTuple},
+    PyNativeType, PyNumberProtocol, PyObjectProtocol,
 };
 
 use super::AsNumArray;
-use super::NdArrayB;
+use super::{NdArrayB, NdArrayI};
 
 impl_ndarray!(f32, NdArrayD, inner, ndarraydimpl);
 
+/// Float array
+///
+/// Arithmetic with an `NdArrayI` operand promotes the integers to f32 before computing and
+/// returns an `NdArrayD`, regardless of the order of the operands. Operations between two
+/// `NdArrayI` stay integer.
 #[pyclass]
 #[derive(Debug, Clone)]
 pub struct NdArrayD {
     pub inner: NdArray<f32>,
 }
 
+/// Operand of a mixed arithmetic operation, promoted to f32
+enum Promoted<'a> {
+    D(PyRef<'a, NdArrayD>),
+    I(NdArray<f32>),
+}
+
+impl<'a> Promoted<'a> {
+    fn extract(obj: &'a PyAny) -> Option<Self> {
+        if let Ok(d) = obj.extract::<PyRef<NdArrayD>>() {
+            return Some(Promoted::D(d));
+        }
+        obj.extract::<PyRef<NdArrayI>>()
+            .ok()
+            .map(|i| Promoted::I(i.inner.map(|x| *x as f32)))
+    }
+
+    fn get(&self) -> &NdArray<f32> {
+        match self {
+            Promoted::D(d) => &d.inner,
+            Promoted::I(i) => i,
+        }
+    }
+}
+
+type BinaryOp = fn(&NdArray<f32>, &NdArray<f32>) -> Result<NdArray<f32>, NdArrayError>;
+
+fn mixed_op(lhs: &PyAny, rhs: &PyAny, name: &str, op: BinaryOp) -> PyResult<PyObject> {
+    let py = lhs.py();
+    let (lhs, rhs) = match (Promoted::extract(lhs), Promoted::extract(rhs)) {
+        (Some(lhs), Some(rhs)) => (lhs, rhs),
+        _ => return Ok(py.NotImplemented()),
+    };
+    let inner = op(lhs.get(), rhs.get())
+        .map_err(|err| PyValueError::new_err::<String>(format!("{}", err)))?;
+    crate::debug::check_finite(name, &inner)?;
+    Ok(NdArrayD { inner }.into_py(py))
+}
+
 #[pyproto]
 impl<T> PyNumberProtocol for NdArrayD {
-    fn __add__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Self> {
-        <Self as AsNumArray>::add(lhs, rhs).map(|inner| Self { inner })
+    fn __add__(lhs: &PyAny, rhs: &PyAny) -> PyResult<PyObject> {
+        mixed_op(lhs, rhs, "add", |a, b| a.add(b))
     }
 
-    fn __sub__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Self> {
-        <Self as AsNumArray>::sub(lhs, rhs).map(|inner| Self { inner })
+    fn __sub__(lhs: &PyAny, rhs: &PyAny) -> PyResult<PyObject> {
+        mixed_op(lhs, rhs, "sub", |a, b| a.sub(b))
     }
 
-    fn __mul__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Self> {
-        <Self as AsNumArray>::mul(lhs, rhs).map(|inner| Self { inner })
+    fn __mul__(lhs: &PyAny, rhs: &PyAny) -> PyResult<PyObject> {
+        mixed_op(lhs, rhs, "mul", |a, b| a.mul(b))
     }
 
-    fn __truediv__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Self> {
-        <Self as AsNumArray>::truediv(lhs, rhs).map(|inner| Self { inner })
+    fn __truediv__(lhs: &PyAny, rhs: &PyAny) -> PyResult<PyObject> {
+        mixed_op(lhs, rhs, "truediv", |a, b| a.div(b))
     }
 
     fn __pow__(lhs: PyRef<'p, Self>, rhs: f32, _modulo: Option<f32>) -> PyResult<Self> {
-        <Self as AsNumArray>::pow(lhs, rhs).map(|inner| Self { inner })
+        let inner = <Self as AsNumArray>::pow(lhs, rhs)?;
+        crate::debug::check_finite("pow", &inner)?;
+        Ok(Self { inner })
     }
 }
 
@@ -97,6 +143,64 @@ impl AsNumArray for NdArrayD {
 
 #[pymethods]
 impl NdArrayD {
+    /// Export this array as a DLPack capsule without copying, e.g. for `torch.from_dlpack`. The
+    /// consumer must not write to the tensor, it shares the buffer of this array.
+    #[args(stream = "None")]
+    pub fn __dlpack__(&self, py: Python, stream: Option<PyObject>) -> PyResult<PyObject> {
+        let _ = stream;
+        crate::dlpack::to_dlpack(py, &self.inner)
+    }
+
+    /// `(device type, device id)` of the DLPack export, always the CPU
+    pub fn __dlpack_device__(&self) -> (i32, i32) {
+        (crate::dlpack::DEVICE_CPU, 0)
+    }
+
+    /// numpy array protocol, `np.asarray(a)` is a read-only view sharing the buffer of this array
+    /// through DLPack
+    #[args(dtype = "None", copy = "None")]
+    pub fn __array__(
+        slf: &PyCell<Self>,
+        py: Python,
+        dtype: Option<PyObject>,
+        copy: Option<bool>,
+    ) -> PyResult<PyObject> {
+        crate::dlpack::to_numpy(py, slf, dtype, copy)
+    }
+
+    /// numpy ufunc protocol, `np.add(a, b)` etc. return our arrays, see `crate::ufunc`
+    #[args(inputs = "*", kwargs = "**")]
+    pub fn __array_ufunc__(
+        &self,
+        py: Python,
+        ufunc: &PyAny,
+        method: &str,
+        inputs: &PyTuple,
+        kwargs: Option<&PyDict>,
+    ) -> PyResult<PyObject> {
+        crate::ufunc::array_ufunc(py, ufunc, method, inputs, kwargs)
+    }
+
+    /// Raw little-endian bytes of the values in row-major order, see `frombuffer`
+    pub fn tobytes<'py>(&self, py: Python<'py>) -> &'py PyBytes {
+        PyBytes::new(py, &self.inner.as_bytes())
+    }
+
+    /// Reverse the byte order of each value, e.g. to read the values of a big-endian system. Like
+    /// numpy's `byteswap`.
+    ///
+    /// Returns a swapped copy, or swaps the values of this array and returns it if `inplace` is
+    /// set.
+    pub fn byteswap(slf: &PyCell<Self>, py: Python, inplace: Option<bool>) -> PyResult<Py<Self>> {
+        if inplace.unwrap_or(false) {
+            slf.borrow_mut().inner.byteswap();
+            return Ok(slf.into());
+        }
+        let mut inner = slf.borrow().inner.clone();
+        inner.byteswap();
+        Py::new(py, Self { inner })
+    }
+
     pub fn matmul(
         this: PyRef<Self>,
         other: &Self,
@@ -116,6 +220,56 @@ impl NdArrayD {
         Ok(out)
     }
 
+    /// Inner product, see `facet_core::ndarray::NdArray::inner` for the compatible shapes
+    pub fn inner(&self, other: &Self) -> PyResult<f32> {
+        self.inner.inner(&other.inner).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "Can not take the inner product of shapes {:?} and {:?}",
+                self.inner.shape(),
+                other.inner.shape()
+            ))
+        })
+    }
+
+    /// Outer product of the flattened arrays
+    pub fn outer(&self, other: &Self) -> Self {
+        Self {
+            inner: self.inner.outer(&other.inner),
+        }
+    }
+
+    /// Convert the array to the given `dtype`, one of `"f32"`, `"i64"`, `"i32"`, `"u8"` or
+    /// `"bool"`.
+    ///
+    /// `mode` selects the rounding of float to int conversions, one of `"trunc"` (default),
+    /// `"floor"`, `"ceil"` or `"round"`. `"round"` rounds half away from zero. Out of range values
+    /// saturate and NaN becomes 0.
+    pub fn astype(&self, py: Python, dtype: &str, mode: Option<&str>) -> PyResult<PyObject> {
+        let integral = matches!(dtype, "i64" | "i32" | "u8");
+        if mode.is_some() && !integral {
+            return Err(PyValueError::new_err(format!(
+                "Rounding mode is only supported when casting to integers, got dtype {}",
+                dtype
+            )));
+        }
+        if !integral {
+            return super::astype(py, &self.inner, dtype);
+        }
+        let round: fn(f32) -> f32 = match mode.unwrap_or("trunc") {
+            "trunc" => f32::trunc,
+            "floor" => f32::floor,
+            "ceil" => f32::ceil,
+            "round" => f32::round,
+            mode => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown rounding mode {}, expected one of trunc, floor, ceil, round",
+                    mode
+                )))
+            }
+        };
+        super::astype(py, &self.inner.map(|x| round(*x)), dtype)
+    }
+
     pub fn clip(mut this: PyRefMut<Self>, min: f32, max: f32) -> PyResult<PyRefMut<Self>> {
         this.inner
             .as_mut_slice()
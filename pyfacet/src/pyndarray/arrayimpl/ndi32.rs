@@ -0,0 +1,56 @@
+use facet_core::ndarray::NdArray;
+pub use ndarrayi32impl::ItemIter as ItemIterI32;
+pub use ndarrayi32impl::RowIter as RowIterI32;
+
+use crate::impl_ndarray;
+use crate::pyndarray::NdArrayD;
+
+use pyo3::{exceptions::PyNotImplementedError, prelude::*, PyObjectProtocol};
+
+impl_ndarray!(i32, NdArrayI32, inner, ndarrayi32impl);
+
+/// 32 bit integer array
+///
+/// Uses half the memory of `NdArrayI`, e.g. for class labels.
+#[pyclass]
+#[derive(Debug)]
+pub struct NdArrayI32 {
+    pub inner: NdArray<i32>,
+}
+
+#[pymethods]
+impl NdArrayI32 {
+    /// Convert the array to the given `dtype`, one of `"f32"`, `"i64"`, `"i32"`, `"u8"` or
+    /// `"bool"`. Out of range values saturate.
+    pub fn astype(&self, py: Python, dtype: &str) -> PyResult<PyObject> {
+        super::astype(py, &self.inner, dtype)
+    }
+
+    /// Convert self into float representation
+    pub fn as_f32(&self) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.map(|x| *x as f32),
+        }
+    }
+}
+
+#[pyproto]
+impl<T> PyObjectProtocol for NdArrayI32 {
+    fn __str__(&self) -> String {
+        self.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "NdArray of i32s, shape: {:?}, data:\n{}",
+            self.inner.shape(),
+            self.to_string()
+        )
+    }
+
+    fn __bool__(&'p self) -> PyResult<bool> {
+        Err(PyNotImplementedError::new_err::<String>(
+            "Array to bool conversion is ambigous!".to_string(),
+        ))
+    }
+}
@@ -3,10 +3,14 @@ use facet_core::ndarray::NdArray;
 pub use implmod::ItemIter as ItemIterI;
 pub use implmod::RowIter as RowIterI;
 pub use implmod::*;
+use std::convert::TryFrom;
 
 use pyo3::{
-    basic::CompareOp, exceptions::PyNotImplementedError, prelude::*, PyNumberProtocol,
-    PyObjectProtocol,
+    basic::CompareOp,
+    exceptions::{PyNotImplementedError, PyOverflowError, PyValueError},
+    prelude::*,
+    types::{PyBytes, PyDict, PyTuple},
+    PyNumberProtocol, PyObjectProtocol,
 };
 
 use super::{AsNumArray, NdArrayB, NdArrayD};
@@ -14,6 +18,9 @@ use super::{AsNumArray, NdArrayB, NdArrayD};
 impl_ndarray!(i64, NdArrayI, inner, implmod);
 
 /// Index array
+///
+/// The arithmetic operators `+`, `-` and `*` wrap around on overflow. Use `add_sat`, `sub_sat` and
+/// `mul_sat` to clamp the results to the bounds of i64 instead.
 #[pyclass]
 #[derive(Debug)]
 pub struct NdArrayI {
@@ -23,15 +30,24 @@ pub struct NdArrayI {
 #[pyproto]
 impl<T> PyNumberProtocol for NdArrayI {
     fn __add__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Self> {
-        <Self as AsNumArray>::add(lhs, rhs).map(|inner| Self { inner })
+        lhs.inner
+            .add_wrap(&rhs.inner)
+            .map(|inner| Self { inner })
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
     }
 
     fn __sub__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Self> {
-        <Self as AsNumArray>::sub(lhs, rhs).map(|inner| Self { inner })
+        lhs.inner
+            .sub_wrap(&rhs.inner)
+            .map(|inner| Self { inner })
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
     }
 
     fn __mul__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Self> {
-        <Self as AsNumArray>::mul(lhs, rhs).map(|inner| Self { inner })
+        lhs.inner
+            .mul_wrap(&rhs.inner)
+            .map(|inner| Self { inner })
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
     }
 
     fn __truediv__(lhs: PyRef<'p, Self>, rhs: PyRef<'p, Self>) -> PyResult<Self> {
@@ -90,10 +106,145 @@ impl AsNumArray for NdArrayI {
 
 #[pymethods]
 impl NdArrayI {
+    /// Export this array as a DLPack capsule without copying, e.g. for `torch.from_dlpack`. The
+    /// consumer must not write to the tensor, it shares the buffer of this array.
+    #[args(stream = "None")]
+    pub fn __dlpack__(&self, py: Python, stream: Option<PyObject>) -> PyResult<PyObject> {
+        let _ = stream;
+        crate::dlpack::to_dlpack(py, &self.inner)
+    }
+
+    /// `(device type, device id)` of the DLPack export, always the CPU
+    pub fn __dlpack_device__(&self) -> (i32, i32) {
+        (crate::dlpack::DEVICE_CPU, 0)
+    }
+
+    /// numpy array protocol, `np.asarray(a)` is a read-only view sharing the buffer of this array
+    /// through DLPack
+    #[args(dtype = "None", copy = "None")]
+    pub fn __array__(
+        slf: &PyCell<Self>,
+        py: Python,
+        dtype: Option<PyObject>,
+        copy: Option<bool>,
+    ) -> PyResult<PyObject> {
+        crate::dlpack::to_numpy(py, slf, dtype, copy)
+    }
+
+    /// numpy ufunc protocol, `np.add(a, b)` etc. return our arrays, see `crate::ufunc`
+    #[args(inputs = "*", kwargs = "**")]
+    pub fn __array_ufunc__(
+        &self,
+        py: Python,
+        ufunc: &PyAny,
+        method: &str,
+        inputs: &PyTuple,
+        kwargs: Option<&PyDict>,
+    ) -> PyResult<PyObject> {
+        crate::ufunc::array_ufunc(py, ufunc, method, inputs, kwargs)
+    }
+
+    /// Raw little-endian bytes of the values in row-major order, see `frombuffer`
+    pub fn tobytes<'py>(&self, py: Python<'py>) -> &'py PyBytes {
+        PyBytes::new(py, &self.inner.as_bytes())
+    }
+
+    /// Reverse the byte order of each value, e.g. to read the values of a big-endian system. Like
+    /// numpy's `byteswap`.
+    ///
+    /// Returns a swapped copy, or swaps the values of this array and returns it if `inplace` is
+    /// set.
+    pub fn byteswap(slf: &PyCell<Self>, py: Python, inplace: Option<bool>) -> PyResult<Py<Self>> {
+        if inplace.unwrap_or(false) {
+            slf.borrow_mut().inner.byteswap();
+            return Ok(slf.into());
+        }
+        let mut inner = slf.borrow().inner.clone();
+        inner.byteswap();
+        Py::new(py, Self { inner })
+    }
+
+    pub fn matmul(
+        this: PyRef<Self>,
+        other: &Self,
+        mut out: Option<PyRefMut<Self>>,
+    ) -> PyResult<PyObject> {
+        let mut _out = NdArray::new(0);
+        let outref = out.as_mut().map(|m| &mut m.inner).unwrap_or(&mut _out);
+        this.inner
+            .matmul(&other.inner, outref)
+            .map_err(|err| PyValueError::new_err::<String>(format!("{}", err)))?;
+        let py = this.py();
+        let out = out.map(|m| m.into_py(py)).unwrap_or_else(|| {
+            let res = NdArrayI { inner: _out };
+            let res = Py::new(py, res).unwrap();
+            res.into_py(py)
+        });
+        Ok(out)
+    }
+
+    /// Inner product, see `facet_core::ndarray::NdArray::inner` for the compatible shapes
+    pub fn inner(&self, other: &Self) -> PyResult<i64> {
+        self.inner.inner(&other.inner).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "Can not take the inner product of shapes {:?} and {:?}",
+                self.inner.shape(),
+                other.inner.shape()
+            ))
+        })
+    }
+
+    /// Outer product of the flattened arrays
+    pub fn outer(&self, other: &Self) -> Self {
+        Self {
+            inner: self.inner.outer(&other.inner),
+        }
+    }
+
+    /// Convert the array to the given `dtype`, one of `"f32"`, `"i64"`, `"i32"`, `"u8"` or
+    /// `"bool"`. Out of range values saturate.
+    pub fn astype(&self, py: Python, dtype: &str) -> PyResult<PyObject> {
+        super::astype(py, &self.inner, dtype)
+    }
+
     /// Convert self into float representation
     pub fn as_f32(&self) -> NdArrayD {
         let values = self.inner.as_slice().iter().map(|x| *x as f32).collect();
         let res = NdArray::new_with_values(self.inner.shape().clone(), values).unwrap();
         NdArrayD { inner: res }
     }
+
+    /// Element-wise addition, saturating at the bounds of i64 instead of overflowing
+    pub fn add_sat(&self, other: &Self) -> PyResult<Self> {
+        self.inner
+            .add_sat(&other.inner)
+            .map(|inner| Self { inner })
+            .map_err(|err| PyValueError::new_err::<String>(format!("{}", err)))
+    }
+
+    /// Element-wise subtraction, saturating at the bounds of i64 instead of overflowing
+    pub fn sub_sat(&self, other: &Self) -> PyResult<Self> {
+        self.inner
+            .sub_sat(&other.inner)
+            .map(|inner| Self { inner })
+            .map_err(|err| PyValueError::new_err::<String>(format!("{}", err)))
+    }
+
+    /// Element-wise multiplication, saturating at the bounds of i64 instead of overflowing
+    pub fn mul_sat(&self, other: &Self) -> PyResult<Self> {
+        self.inner
+            .mul_sat(&other.inner)
+            .map(|inner| Self { inner })
+            .map_err(|err| PyValueError::new_err::<String>(format!("{}", err)))
+    }
+
+    /// Sum the rows, accumulating into 128 bit integers.
+    ///
+    /// Raises `OverflowError` if a sum does not fit into i64, instead of wrapping around.
+    pub fn sum_wide(&self) -> PyResult<Self> {
+        facet_core::sum_wide(&self.inner)
+            .try_map(|x| i64::try_from(*x).map_err(|_| *x))
+            .map(|inner| Self { inner })
+            .map_err(|x| PyOverflowError::new_err(format!("Sum {} does not fit into i64", x)))
+    }
 }
@@ -0,0 +1,56 @@
+use facet_core::ndarray::NdArray;
+pub use ndarrayu8impl::ItemIter as ItemIterU8;
+pub use ndarrayu8impl::RowIter as RowIterU8;
+
+use crate::impl_ndarray;
+use crate::pyndarray::NdArrayD;
+
+use pyo3::{exceptions::PyNotImplementedError, prelude::*, PyObjectProtocol};
+
+impl_ndarray!(u8, NdArrayU8, inner, ndarrayu8impl);
+
+/// Byte array
+///
+/// Uses a quarter of the memory of `NdArrayD`, e.g. for 8 bit image data.
+#[pyclass]
+#[derive(Debug)]
+pub struct NdArrayU8 {
+    pub inner: NdArray<u8>,
+}
+
+#[pymethods]
+impl NdArrayU8 {
+    /// Convert the array to the given `dtype`, one of `"f32"`, `"i64"`, `"i32"`, `"u8"` or
+    /// `"bool"`. Out of range values saturate.
+    pub fn astype(&self, py: Python, dtype: &str) -> PyResult<PyObject> {
+        super::astype(py, &self.inner, dtype)
+    }
+
+    /// Convert self into float representation
+    pub fn as_f32(&self) -> NdArrayD {
+        NdArrayD {
+            inner: self.inner.map(|x| *x as f32),
+        }
+    }
+}
+
+#[pyproto]
+impl<T> PyObjectProtocol for NdArrayU8 {
+    fn __str__(&self) -> String {
+        self.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "NdArray of u8s, shape: {:?}, data:\n{}",
+            self.inner.shape(),
+            self.to_string()
+        )
+    }
+
+    fn __bool__(&'p self) -> PyResult<bool> {
+        Err(PyNotImplementedError::new_err::<String>(
+            "Array to bool conversion is ambigous!".to_string(),
+        ))
+    }
+}
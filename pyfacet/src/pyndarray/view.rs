@@ -0,0 +1,189 @@
+use facet_core::ndarray::view::{ArrayView, AxisSlice};
+use pyo3::{
+    exceptions::PyIndexError,
+    prelude::*,
+    types::{PySlice, PyTuple},
+    PyIterProtocol, PyMappingProtocol, PyNativeType, PyObjectProtocol,
+};
+use std::os::raw::c_long;
+
+use super::{NdArrayB, NdArrayD, NdArrayI, NdArrayI32, NdArrayU8};
+
+pub enum ViewInner {
+    F32(ArrayView<f32>),
+    I64(ArrayView<i64>),
+    I32(ArrayView<i32>),
+    U8(ArrayView<u8>),
+    Bool(ArrayView<bool>),
+}
+
+/// Element types of the arrays that can be sliced into an [NdArrayView]
+pub trait Viewable: Copy + IntoPy<PyObject> {
+    fn wrap(view: ArrayView<Self>) -> ViewInner;
+}
+
+macro_rules! impl_viewable {
+    ($($ty: ty => $variant: ident),*) => {
+        $(
+            impl Viewable for $ty {
+                fn wrap(view: ArrayView<Self>) -> ViewInner {
+                    ViewInner::$variant(view)
+                }
+            }
+        )*
+    };
+}
+
+impl_viewable!(f32 => F32, i64 => I64, i32 => I32, u8 => U8, bool => Bool);
+
+/// Run `$body` with `$view` bound to the typed view inside `$inner`
+macro_rules! with_view {
+    ($inner: expr, $view: ident => $body: expr) => {
+        match $inner {
+            ViewInner::F32($view) => $body,
+            ViewInner::I64($view) => $body,
+            ViewInner::I32($view) => $body,
+            ViewInner::U8($view) => $body,
+            ViewInner::Bool($view) => $body,
+        }
+    };
+}
+
+/// Strided view into an array, returned by indexing arrays with slices, e.g. `a[1:5, :, 2]`
+///
+/// The view shares the values of the array it was created from without copying them. Writing to
+/// that array afterwards does not change the view. Use `copy` to turn the view into an array.
+#[pyclass]
+pub struct NdArrayView {
+    pub inner: ViewInner,
+}
+
+/// True if `key` selects a view rather than a single item, i.e. it contains a slice
+pub fn is_slice_key(key: &PyAny) -> bool {
+    if let Ok(key) = key.downcast::<PyTuple>() {
+        key.iter().any(|k| k.downcast::<PySlice>().is_ok())
+    } else {
+        key.downcast::<PySlice>().is_ok()
+    }
+}
+
+fn axis_slices(key: &PyAny, shape: &[u32]) -> PyResult<Vec<AxisSlice>> {
+    let keys: Vec<&PyAny> = match key.downcast::<PyTuple>() {
+        Ok(key) => key.iter().collect(),
+        Err(_) => vec![key],
+    };
+    if keys.len() > shape.len() {
+        return Err(PyIndexError::new_err(format!(
+            "Too many indices, got {} for an array of {} dimensions",
+            keys.len(),
+            shape.len()
+        )));
+    }
+    keys.into_iter()
+        .zip(shape)
+        .map(|(key, n)| {
+            let n = *n;
+            if let Ok(slice) = key.downcast::<PySlice>() {
+                let indices = slice.indices(n as c_long)?;
+                let len = indices.slicelength as u32;
+                return Ok(AxisSlice::Range {
+                    start: if len > 0 { indices.start as u32 } else { 0 },
+                    step: indices.step as i64,
+                    len,
+                });
+            }
+            let i: i64 = key.extract()?;
+            let j = if i < 0 { i + n as i64 } else { i };
+            if j < 0 || j >= n as i64 {
+                return Err(PyIndexError::new_err(format!(
+                    "index {} is out of bounds for an axis of size {}",
+                    i, n
+                )));
+            }
+            Ok(AxisSlice::Index(j as u32))
+        })
+        .collect()
+}
+
+/// Index `view` with `key`, returning the item if every axis is indexed and a new view otherwise
+pub fn getitem<T: Viewable>(py: Python, view: &ArrayView<T>, key: &PyAny) -> PyResult<PyObject> {
+    let slices = axis_slices(key, view.shape())?;
+    let res = view
+        .slice(&slices)
+        .map_err(|err| PyIndexError::new_err(format!("{}", err)))?;
+    if res.shape().is_empty() {
+        return Ok((*res.iter().next().unwrap()).into_py(py));
+    }
+    let res = NdArrayView {
+        inner: T::wrap(res),
+    };
+    Ok(res.into_py(py))
+}
+
+#[pymethods]
+impl NdArrayView {
+    #[getter]
+    pub fn shape(&self) -> Vec<u32> {
+        with_view!(&self.inner, v => v.shape().to_vec())
+    }
+
+    /// Copy the viewed values into a new array of the type the view was created from
+    pub fn copy(&self, py: Python) -> PyObject {
+        match &self.inner {
+            ViewInner::F32(v) => NdArrayD {
+                inner: v.to_array(),
+            }
+            .into_py(py),
+            ViewInner::I64(v) => NdArrayI {
+                inner: v.to_array(),
+            }
+            .into_py(py),
+            ViewInner::I32(v) => NdArrayI32 {
+                inner: v.to_array(),
+            }
+            .into_py(py),
+            ViewInner::U8(v) => NdArrayU8 {
+                inner: v.to_array(),
+            }
+            .into_py(py),
+            ViewInner::Bool(v) => NdArrayB {
+                inner: v.to_array(),
+            }
+            .into_py(py),
+        }
+    }
+}
+
+#[pyproto]
+impl PyMappingProtocol for NdArrayView {
+    fn __len__(&self) -> PyResult<usize> {
+        Ok(with_view!(&self.inner, v => v.len()))
+    }
+
+    fn __getitem__(&self, key: &PyAny) -> PyResult<PyObject> {
+        with_view!(&self.inner, v => getitem(key.py(), v, key))
+    }
+}
+
+#[pyproto]
+impl PyIterProtocol for NdArrayView {
+    /// Iterates over a copy of the values in row-major order
+    fn __iter__(this: PyRef<Self>) -> PyResult<PyObject> {
+        let py = this.py();
+        let values = this.copy(py);
+        values.as_ref(py).iter().map(|it| it.into())
+    }
+}
+
+#[pyproto]
+impl PyObjectProtocol for NdArrayView {
+    fn __repr__(&self) -> PyResult<String> {
+        Python::with_gil(|py| {
+            Ok(format!(
+                "NdArrayView, shape: {:?}, data:\n{}",
+                self.shape(),
+                self.copy(py).as_ref(py).str()?
+            ))
+        })
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::Mutex;
+
+use facet_core::ndarray::NdArray;
+use pyo3::{exceptions::PyValueError, prelude::*, wrap_pyfunction};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::pyndarray::{NdArrayD, NdArrayI};
+
+/// Global generator used by the random functions, seeded from entropy on first use
+static RNG: Mutex<Option<StdRng>> = Mutex::new(None);
+
+/// Run `f` with the global generator
+pub fn with_rng<R>(f: impl FnOnce(&mut StdRng) -> R) -> R {
+    let mut rng = RNG.lock().unwrap_or_else(|err| err.into_inner());
+    f(rng.get_or_insert_with(StdRng::from_entropy))
+}
+
+/// Seed the global generator, making the random functions reproducible
+#[pyfunction]
+pub fn seed(seed: u64) {
+    with_rng(|rng| *rng = StdRng::seed_from_u64(seed));
+}
+
+/// Return a random permutation of `0..n`.
+///
+/// Index arrays with the permutation to shuffle paired arrays in the same order.
+#[pyfunction]
+pub fn permutation(n: u32) -> NdArrayI {
+    let mut values: Vec<i64> = (0..n as i64).collect();
+    with_rng(|rng| values.shuffle(rng));
+    NdArrayI {
+        inner: NdArray::new_vector(values),
+    }
+}
+
+/// Randomly permute the slices of `inp` along `axis` in place. `axis` defaults to 0.
+#[pyfunction]
+pub fn shuffle(py: Python, inp: PyObject, axis: Option<u32>) -> PyResult<()> {
+    let axis = axis.unwrap_or(0);
+    let res = if let Ok(mut inp) = inp.extract::<PyRefMut<NdArrayD>>(py) {
+        with_rng(|rng| inp.inner.shuffle_axis(axis, rng).map(|_| ()))
+    } else {
+        let mut inp: PyRefMut<NdArrayI> = inp.extract(py)?;
+        with_rng(|rng| inp.inner.shuffle_axis(axis, rng).map(|_| ()))
+    };
+    res.map_err(|err| PyValueError::new_err(format!("{}", err)))
+}
+
+/// Zero each element of `inp` with probability `p` and scale the others by `1 / (1 - p)`,
+/// returning `(output, mask)`. Functional version of `DropoutLayer`.
+///
+/// The mask holds `0` for the dropped elements and the scale for the kept ones, so the gradient of
+/// the inputs is `dvalues * mask`. With `training=False` the inputs are returned unchanged, with a
+/// mask of ones. `seed` draws the mask from its own generator, leaving the global one untouched.
+#[pyfunction(p, training = "true", seed = "None")]
+pub fn dropout(
+    py: Python,
+    inp: PyObject,
+    p: f32,
+    training: bool,
+    seed: Option<u64>,
+) -> PyResult<(NdArrayD, NdArrayD)> {
+    if !(0.0..=1.0).contains(&p) {
+        return Err(PyValueError::new_err(format!(
+            "dropout probability must be in [0, 1], got {}",
+            p
+        )));
+    }
+    let inp = crate::pyobj_to_arrayd(py, inp)?;
+    let inp = &inp.borrow(py).inner;
+    if !training {
+        let mask = inp.map(|_| 1.0);
+        return Ok((NdArrayD { inner: inp.clone() }, NdArrayD { inner: mask }));
+    }
+
+    let scale = if p < 1.0 { 1.0 / (1.0 - p) } else { 0.0 };
+    let draw = |rng: &mut StdRng| inp.map(|_| if rng.gen::<f32>() < p { 0.0 } else { scale });
+    let mask = match seed {
+        Some(seed) => draw(&mut StdRng::seed_from_u64(seed)),
+        None => with_rng(draw),
+    };
+    let output = inp
+        .mul(&mask)
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+    Ok((NdArrayD { inner: output }, NdArrayD { inner: mask }))
+}
+
+pub fn setup_module(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(seed, m)?)?;
+    m.add_function(wrap_pyfunction!(permutation, m)?)?;
+    m.add_function(wrap_pyfunction!(shuffle, m)?)?;
+    m.add_function(wrap_pyfunction!(dropout, m)?)?;
+    Ok(())
+}
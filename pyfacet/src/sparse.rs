@@ -0,0 +1,105 @@
+//! Coordinate (COO) format for arrays that are mostly zeros, e.g. the gradients of embedding
+//! layers.
+//!
+//! An array with `nnz` nonzero values of an `ndim` dimensional shape is stored as an `[ndim, nnz]`
+//! `NdArrayI` of indices, column `j` holding the index of the value `j`, and a vector of the
+//! `nnz` values.
+use std::convert::TryFrom;
+
+use facet_core::ndarray::{shape::Shape, NdArray};
+use pyo3::{
+    exceptions::{PyIndexError, PyValueError},
+    prelude::*,
+    wrap_pyfunction,
+};
+
+use crate::pyndarray::{NdArrayD, NdArrayI};
+
+pub fn setup_module(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(to_coo, m)?)?;
+    m.add_function(wrap_pyfunction!(from_coo, m)?)?;
+    Ok(())
+}
+
+/// Return the `(indices, values)` of the nonzero elements of `inp` in row-major order, see the
+/// module documentation for the layout.
+#[pyfunction]
+pub fn to_coo(py: Python, inp: PyObject) -> PyResult<(NdArrayI, NdArrayD)> {
+    let inp = crate::pyobj_to_arrayd(py, inp)?;
+    let inp = &inp.borrow(py).inner;
+    let shape = inp.shape();
+    let ndim = shape.as_slice().len();
+    if ndim == 0 {
+        return Err(PyValueError::new_err(
+            "to_coo expects an array of at least 1 dimension",
+        ));
+    }
+
+    let nonzero: Vec<usize> = (0..inp.len())
+        .filter(|i| inp.as_slice()[*i] != 0.0)
+        .collect();
+    let nnz = nonzero.len();
+
+    let mut indices = vec![0i64; ndim * nnz];
+    for (j, flat) in nonzero.iter().enumerate() {
+        let index = shape.unravel_index(*flat).expect("flat index is in bounds");
+        for (d, i) in index.iter().enumerate() {
+            indices[d * nnz + j] = *i as i64;
+        }
+    }
+    let values: Vec<f32> = nonzero.iter().map(|i| inp.as_slice()[*i]).collect();
+
+    let indices = NdArray::new_with_values([ndim as u32, nnz as u32], indices.into())
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+    // `new_vector` would treat an empty array as a scalar
+    let values = NdArray::new_with_values(Shape::Vector([nnz as u32]), values.into())
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+    Ok((NdArrayI { inner: indices }, NdArrayD { inner: values }))
+}
+
+/// Build a dense array of `shape` from the `indices` and `values` returned by `to_coo`.
+///
+/// Values at repeated indices are summed, the other elements are zero.
+#[pyfunction]
+pub fn from_coo(
+    py: Python,
+    indices: PyObject,
+    values: PyObject,
+    shape: Vec<u32>,
+) -> PyResult<NdArrayD> {
+    let indices = crate::pyobj_to_arrayi(py, indices)?;
+    let values = crate::pyobj_to_arrayd(py, values)?;
+    let values = values.borrow(py).inner.as_slice().to_vec();
+    let shape = Shape::from(shape);
+    let ndim = shape.as_slice().len();
+    let nnz = values.len();
+
+    let expected = [ndim as u32, nnz as u32];
+    if indices.shape().as_slice() != expected || ndim == 0 {
+        return Err(PyValueError::new_err(format!(
+            "Expected indices of shape {:?} for {} values of shape {:?}, got {:?}",
+            expected,
+            nnz,
+            shape,
+            indices.shape()
+        )));
+    }
+
+    let mut res = NdArray::try_new_default(shape.clone())
+        .map_err(|err| PyValueError::new_err(format!("{}", err)))?;
+    let mut index = vec![0u32; ndim];
+    for (j, value) in values.iter().enumerate() {
+        for (d, i) in index.iter_mut().enumerate() {
+            let k = indices.as_slice()[d * nnz + j];
+            *i = u32::try_from(k).unwrap_or(u32::MAX);
+        }
+        let flat = shape.ravel_index(&index).ok_or_else(|| {
+            PyIndexError::new_err(format!(
+                "index {:?} of value {} is out of bounds for shape {:?}",
+                index, j, shape
+            ))
+        })?;
+        res.as_mut_slice()[flat] += value;
+    }
+    Ok(NdArrayD { inner: res })
+}
@@ -0,0 +1,157 @@
+//! Incremental statistics, for datasets that do not fit into memory.
+//!
+//! The accumulators fold in batches of samples along the first axis, so updating with several
+//! batches gives the same result as a single update with their concatenation.
+use facet_core::ndarray::{shape::Shape, NdArray};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::pyndarray::NdArrayD;
+
+pub fn setup_module(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<RunningMean>()?;
+    m.add_class::<RunningVar>()?;
+    Ok(())
+}
+
+/// Count, mean and sum of squared differences from the mean of each feature, accumulated with
+/// Welford's algorithm.
+#[derive(Clone, Default)]
+struct Moments {
+    count: u64,
+    /// Shape of a single sample, set by the first update
+    shape: Option<Shape>,
+    mean: Vec<f64>,
+    m2: Vec<f64>,
+}
+
+impl Moments {
+    fn update(&mut self, batch: &NdArray<f32>) -> PyResult<()> {
+        let (n, shape) = match batch.shape().as_slice() {
+            [] => (1, Shape::Scalar([0])),
+            [n, sample @ ..] => (*n as usize, Shape::from(sample)),
+        };
+        if n == 0 {
+            return Ok(());
+        }
+        match &self.shape {
+            Some(s) if s != &shape => {
+                return Err(PyValueError::new_err(format!(
+                    "Expected samples of shape {:?}, got {:?}",
+                    s, shape
+                )))
+            }
+            Some(_) => {}
+            None => {
+                self.mean = vec![0.0; shape.span()];
+                self.m2 = vec![0.0; shape.span()];
+                self.shape = Some(shape);
+            }
+        }
+
+        // merge the moments of the batch into the accumulated ones, see Chan et al. "Updating
+        // Formulae and a Pairwise Algorithm for Computing Sample Variances"
+        let values = batch.as_slice();
+        let features = self.mean.len();
+        let total = self.count as f64 + n as f64;
+        for (j, (mean, m2)) in self.mean.iter_mut().zip(self.m2.iter_mut()).enumerate() {
+            let samples = (0..n).map(|i| values[i * features + j] as f64);
+            let batch_mean = samples.clone().sum::<f64>() / n as f64;
+            let batch_m2 = samples.map(|x| (x - batch_mean).powi(2)).sum::<f64>();
+
+            let delta = batch_mean - *mean;
+            *mean += delta * n as f64 / total;
+            *m2 += batch_m2 + delta * delta * self.count as f64 * n as f64 / total;
+        }
+        self.count += n as u64;
+        Ok(())
+    }
+
+    fn to_array(&self, values: impl Iterator<Item = f64>) -> PyResult<NdArrayD> {
+        let shape = self
+            .shape
+            .clone()
+            .ok_or_else(|| PyValueError::new_err("No samples were accumulated"))?;
+        let values = values.map(|x| x as f32).collect();
+        NdArray::new_with_values(shape, values)
+            .map(|inner| NdArrayD { inner })
+            .map_err(|err| PyValueError::new_err(format!("{}", err)))
+    }
+}
+
+/// Mean of the samples of successive batches, per feature.
+///
+/// Batches hold samples along their first axis, the result has the shape of a single sample.
+#[pyclass]
+#[derive(Clone, Default)]
+pub struct RunningMean {
+    inner: Moments,
+}
+
+#[pymethods]
+impl RunningMean {
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of samples accumulated so far
+    #[getter]
+    pub fn count(&self) -> u64 {
+        self.inner.count
+    }
+
+    /// Fold the samples of `batch` into the statistic
+    pub fn update(&mut self, py: Python, batch: PyObject) -> PyResult<()> {
+        let batch = crate::pyobj_to_arrayd(py, batch)?;
+        let batch = batch.borrow(py);
+        self.inner.update(&batch.inner)
+    }
+
+    /// Mean of the accumulated samples. Raises `ValueError` if no samples were accumulated.
+    pub fn result(&self) -> PyResult<NdArrayD> {
+        self.inner.to_array(self.inner.mean.iter().copied())
+    }
+}
+
+/// Population variance of the samples of successive batches, per feature. Like `std_squared`
+/// over the concatenation of the batches.
+///
+/// Batches hold samples along their first axis, the result has the shape of a single sample.
+#[pyclass]
+#[derive(Clone, Default)]
+pub struct RunningVar {
+    inner: Moments,
+}
+
+#[pymethods]
+impl RunningVar {
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of samples accumulated so far
+    #[getter]
+    pub fn count(&self) -> u64 {
+        self.inner.count
+    }
+
+    /// Fold the samples of `batch` into the statistic
+    pub fn update(&mut self, py: Python, batch: PyObject) -> PyResult<()> {
+        let batch = crate::pyobj_to_arrayd(py, batch)?;
+        let batch = batch.borrow(py);
+        self.inner.update(&batch.inner)
+    }
+
+    /// Mean of the accumulated samples
+    pub fn mean(&self) -> PyResult<NdArrayD> {
+        self.inner.to_array(self.inner.mean.iter().copied())
+    }
+
+    /// Variance of the accumulated samples. Raises `ValueError` if no samples were accumulated.
+    pub fn result(&self) -> PyResult<NdArrayD> {
+        let count = self.inner.count as f64;
+        self.inner
+            .to_array(self.inner.m2.iter().map(|m2| m2 / count))
+    }
+}
@@ -0,0 +1,68 @@
+//! numpy's [`__array_ufunc__`](https://numpy.org/doc/stable/reference/arrays.classes.html#numpy.class.__array_ufunc__)
+//! protocol, so ufuncs like `np.add(a, b)` operate on our arrays and return our array types.
+//!
+//! Only plain calls of the supported ufuncs are dispatched, with our arrays or Python numbers as
+//! operands. Everything else returns `NotImplemented`, letting numpy fall back or raise.
+use pyo3::{
+    prelude::*,
+    types::{PyDict, PyFloat, PyLong, PyTuple},
+};
+
+use crate::pyndarray::{NdArrayD, NdArrayI};
+
+fn is_supported_operand(inp: &PyAny) -> bool {
+    inp.extract::<PyRef<NdArrayD>>().is_ok()
+        || inp.extract::<PyRef<NdArrayI>>().is_ok()
+        || inp.downcast::<PyFloat>().is_ok()
+        || inp.downcast::<PyLong>().is_ok()
+}
+
+/// Apply the Python operator `op`, numbers are converted to f32 scalars
+fn operator(py: Python, op: &str, a: &PyObject, b: &PyObject) -> PyResult<PyObject> {
+    let to_array = |x: &PyObject| {
+        x.extract::<f32>(py)
+            .map(|x| crate::scalar(x).into_py(py))
+            .unwrap_or_else(|_| x.clone_ref(py))
+    };
+    let res = py
+        .import("operator")?
+        .getattr(op)?
+        .call1((to_array(a), to_array(b)))?;
+    Ok(res.into())
+}
+
+pub fn array_ufunc(
+    py: Python,
+    ufunc: &PyAny,
+    method: &str,
+    inputs: &PyTuple,
+    kwargs: Option<&PyDict>,
+) -> PyResult<PyObject> {
+    if method != "__call__"
+        || kwargs.is_some_and(|kwargs| !kwargs.is_empty())
+        || !inputs.iter().all(is_supported_operand)
+    {
+        return Ok(py.NotImplemented());
+    }
+    let name: &str = ufunc.getattr("__name__")?.extract()?;
+    let inputs: Vec<PyObject> = inputs.iter().map(|x| x.into()).collect();
+    let res = match (name, inputs.as_slice()) {
+        ("add", [a, b]) => operator(py, "add", a, b)?,
+        ("subtract", [a, b]) => operator(py, "sub", a, b)?,
+        ("multiply", [a, b]) => operator(py, "mul", a, b)?,
+        ("true_divide", [a, b]) | ("divide", [a, b]) => operator(py, "truediv", a, b)?,
+        ("floor_divide", [a, b]) => crate::floor_divide(py, a.clone_ref(py), b.clone_ref(py))?,
+        ("remainder", [a, b]) => crate::mod_(py, a.clone_ref(py), b.clone_ref(py))?,
+        ("maximum", [a, b]) => crate::maximum(py, a.clone_ref(py), b.clone_ref(py))?.into_py(py),
+        ("minimum", [a, b]) => crate::minimum(py, a.clone_ref(py), b.clone_ref(py))?.into_py(py),
+        ("copysign", [a, b]) => crate::copysign(py, a.clone_ref(py), b.clone_ref(py))?.into_py(py),
+        ("hypot", [a, b]) => crate::hypot(py, a.clone_ref(py), b.clone_ref(py))?.into_py(py),
+        ("negative", [a]) => crate::negative(py, a.clone_ref(py))?,
+        ("absolute", [a]) => crate::abs(py, a.clone_ref(py))?.into_py(py),
+        ("sqrt", [a]) => crate::sqrt(py, a.clone_ref(py))?.into_py(py),
+        ("log", [a]) => crate::log(py, a.clone_ref(py), None)?.into_py(py),
+        ("reciprocal", [a]) => crate::reciprocal(py, a.clone_ref(py))?.into_py(py),
+        _ => py.NotImplemented(),
+    };
+    Ok(res)
+}
@@ -12,4 +12,6 @@ def test_accuracy():
 
     res = acc.calculate(pred, y)
 
-    assert 0.0 < res < 0.12
+    # only the first column of every row is within the precision of the exact match, so 3 of 9
+    # predictions are accurate
+    assert abs(res - 1 / 3) < 1e-6
@@ -52,3 +52,64 @@ def test_diagflat():
                 assert foo[[i, j]] == softmax_output[i]
             else:
                 assert foo[[i, j]] == 0
+
+
+def test_gelu_reference_values():
+    x = NdArrayD([4], [-1, 0, 1, 2])
+
+    exact = pyfacet.gelu(x)
+    approx = pyfacet.gelu(x, approximate="tanh")
+
+    for a, b in zip(exact, [-0.1586553, 0.0, 0.8413447, 1.9544997]):
+        assert abs(a - b) < 1e-5
+    for a, b in zip(approx, [-0.1588080, 0.0, 0.8411920, 1.9545977]):
+        assert abs(a - b) < 1e-5
+
+    with pytest.raises(ValueError):
+        pyfacet.gelu(x, approximate="sigmoid")
+
+
+def test_gelu_backward_finite_differences():
+    points = [-2.5, -1, -0.3, 0, 0.7, 1.5, 3]
+    x = NdArrayD([len(points)], points)
+    ones = NdArrayD([len(points)], [1] * len(points))
+    h = 1e-2
+
+    for approximate in ["none", "tanh"]:
+        grad = pyfacet.gelu_backward(x, ones, approximate=approximate)
+        for p, g in zip(points, grad):
+            lo = pyfacet.gelu(NdArrayD([1], [p - h]), approximate=approximate)
+            hi = pyfacet.gelu(NdArrayD([1], [p + h]), approximate=approximate)
+            numeric = (list(hi)[0] - list(lo)[0]) / (2 * h)
+            assert abs(g - numeric) < 1e-3
+
+
+def test_activation_classes_use_the_core_layers():
+    from pyfacet.activation import (
+        ActivationSigmoid,
+        ActivationSoftmax,
+        Activation_Softmax_Loss_CategoricalCrossentropy,
+    )
+
+    x = pyfacet.array([[0.0, 2.0]])
+
+    sigmoid = ActivationSigmoid()
+    out = sigmoid.forward(x)
+    assert list(out)[0] == 0.5
+    assert list(sigmoid.predictions()) == [False, True]
+    sigmoid.backward(pyfacet.ones([1, 2]))
+    assert list(sigmoid.dinputs)[0] == 0.25
+
+    softmax = ActivationSoftmax()
+    out = softmax.forward(x)
+    assert list(out) == list(pyfacet.softmax(x))
+    assert list(softmax.predictions()) == [1]
+    softmax.backward(pyfacet.ones([1, 2]))
+    assert softmax.dinputs.shape == [1, 2]
+
+    combined = Activation_Softmax_Loss_CategoricalCrossentropy()
+    combined.forward(x, pyfacet.array([[0.0, 1.0]]))
+    assert list(combined.predictions()) == [1]
+
+    model = pyfacet.Sequential(pyfacet.DenseLayer(2, 2), pyfacet.SoftmaxLayer())
+    assert model.forward(x).shape == [1, 2]
@@ -0,0 +1,86 @@
+import math
+
+import pytest
+import pyfacet
+from pyfacet import Tape
+
+
+def loss(x, w):
+    z = pyfacet.array(x).matmul(pyfacet.array(w))
+    return sum(v * v for v in z)
+
+
+def test_matmul_square_gradient_matches_finite_differences():
+    xs = [[1.0, -2.0, 0.5], [0.3, 0.8, -1.2]]
+    ws = [[0.2, -0.4], [1.1, 0.6], [-0.7, 0.9]]
+
+    tape = Tape()
+    x = tape.variable(pyfacet.array(xs))
+    w = tape.variable(pyfacet.array(ws))
+    z = x @ w
+    y = (z * z).sum()
+    y.backward()
+
+    assert list(y.value) == pytest.approx([loss(xs, ws)], rel=1e-5)
+
+    eps = 1e-2
+    expected = []
+    for i in range(3):
+        for j in range(2):
+            plus = [row[:] for row in ws]
+            minus = [row[:] for row in ws]
+            plus[i][j] += eps
+            minus[i][j] -= eps
+            expected.append((loss(xs, plus) - loss(xs, minus)) / (2 * eps))
+
+    assert w.grad.shape == [3, 2]
+    assert list(w.grad) == pytest.approx(expected, rel=1e-2, abs=1e-3)
+    assert x.grad.shape == [2, 3]
+
+
+def test_activations_and_unused_variables():
+    tape = Tape()
+    x = tape.variable([[-1.0, 2.0, 3.0]])
+    unused = tape.variable([1.0])
+
+    y = x.relu().softmax()
+    (y * tape.variable([[1.0, 0.0, 0.0]])).sum().backward()
+
+    assert unused.grad is None
+    # y0 = softmax(u)0  =>  du_j = y0 * ([j == 0] - y_j), relu blocks the negative input
+    e = [math.exp(u) for u in [0.0, 2.0, 3.0]]
+    s = [v / sum(e) for v in e]
+    assert list(x.grad) == pytest.approx([0, -s[0] * s[1], -s[0] * s[2]], abs=1e-5)
+
+
+def test_custom_loss():
+    tape = Tape()
+    logits = tape.variable([[1.0, 1.0], [0.0, 2.0]])
+    target = tape.variable([[1.0, 0.0], [0.0, 1.0]])
+
+    e = logits.exp()
+    p = e / e.sum(axis=-1)
+    loss = (p.log() * target).reshape([4]).mean()
+    loss.backward()
+
+    probs = list(p.value)
+    assert p.value.shape == [2, 2]
+    assert list(logits.grad) == pytest.approx(
+        [(t - q) / 4 for t, q in zip([1, 0, 0, 1], probs)], abs=1e-6
+    )
+
+    with pytest.raises(ValueError):
+        logits.reshape([3])
+    with pytest.raises(ValueError):
+        logits.sum(axis=2)
+
+
+def test_backward_errors():
+    tape = Tape()
+    x = tape.variable([1.0, 2.0])
+
+    with pytest.raises(ValueError):
+        x.backward()
+
+    with pytest.raises(ValueError):
+        x + Tape().variable([1.0, 2.0])
@@ -0,0 +1,98 @@
+import pytest
+from pyfacet import NdArrayD, NdArrayI, array, from_dlpack
+
+
+def test_dlpack_roundtrip():
+    a = NdArrayD([2, 3], [1, 2, 3, 4, 5, 6])
+
+    res = from_dlpack(a)
+
+    assert isinstance(res, NdArrayD)
+    assert res.shape == [2, 3]
+    assert list(res) == [1, 2, 3, 4, 5, 6]
+    assert a.__dlpack_device__() == (1, 0)
+
+
+def test_dlpack_roundtrip_int():
+    a = NdArrayI([3], [1, -2, 2 ** 40])
+
+    res = from_dlpack(a.__dlpack__())
+
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [1, -2, 2 ** 40]
+
+
+def test_dlpack_consumed_capsule_raises():
+    capsule = NdArrayD([1], [1]).__dlpack__()
+    from_dlpack(capsule)
+
+    with pytest.raises(ValueError):
+        from_dlpack(capsule)
+
+
+def test_dlpack_export_outlives_source_changes():
+    a = NdArrayD([3], [1, 2, 3])
+    capsule = a.__dlpack__()
+
+    # the export shares the buffer, writing to the source copies it instead
+    a.set([0], 10)
+    a.reshape([3, 1])
+    del a
+
+    assert list(from_dlpack(capsule)) == [1, 2, 3]
+
+
+def test_array_from_dlpack_producer():
+    a = NdArrayI([2, 2], [1, 2, 3, 4])
+
+    res = array(a)
+
+    assert isinstance(res, NdArrayI)
+    assert res.shape == [2, 2]
+    assert list(res) == [1, 2, 3, 4]
+
+
+def test_numpy_roundtrip():
+    np = pytest.importorskip("numpy")
+
+    a = NdArrayD([2, 3], [1, 2, 3, 4, 5, 6])
+    b = np.asarray(a)
+
+    assert b.dtype == np.float32
+    assert b.shape == (2, 3)
+    assert b.tolist() == [[1, 2, 3], [4, 5, 6]]
+
+    res = array(np.arange(6, dtype=np.float64).reshape(3, 2))
+    assert isinstance(res, NdArrayD)
+    assert res.shape == [3, 2]
+    assert list(res) == [0, 1, 2, 3, 4, 5]
+
+
+def test_torch_roundtrip():
+    torch = pytest.importorskip("torch")
+
+    a = NdArrayD([2, 2], [1, 2, 3, 4])
+    t = torch.from_dlpack(a)
+
+    assert list(t.shape) == [2, 2]
+    assert t.tolist() == [[1, 2], [3, 4]]
+
+    # transposed tensors are strided, not contiguous
+    res = from_dlpack(t.T)
+    assert res.shape == [2, 2]
+    assert list(res) == [1, 3, 2, 4]
+
+
+def test_numpy_view_is_read_only():
+    np = pytest.importorskip("numpy")
+
+    a = NdArrayD([3], [1, 2, 3])
+    b = np.asarray(a)
+
+    with pytest.raises(ValueError):
+        b[0] = 10
+    assert list(a) == [1, 2, 3]
+
+    c = np.array(a, copy=True)
+    c[0] = 10
+    assert list(a) == [1, 2, 3]
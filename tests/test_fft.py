@@ -0,0 +1,35 @@
+import pytest
+from pyfacet import NdArrayC, array, fft, ifft
+
+
+def test_fft_constant():
+    res = fft(array([1.0, 1.0, 1.0, 1.0]))
+
+    assert res.shape == [4]
+    assert (res.real() == array([4.0, 0.0, 0.0, 0.0])).all()
+    assert (res.imag() == array([0.0, 0.0, 0.0, 0.0])).all()
+
+
+def test_ifft_roundtrip():
+    # power of two lengths use the radix-2 path, the rest the DFT fallback
+    for values in ([1.0, 2.0, -3.0, 0.5, 4.0, 1.0, 0.0, -1.0], [1.0, 2.0, -3.0, 0.5, 4.0]):
+        x = array(values)
+        res = ifft(fft(x))
+
+        for i, v in enumerate(values):
+            re, im = res.get([i])
+            assert re == pytest.approx(v)
+            assert im == pytest.approx(0.0)
+
+
+def test_fft_non_power_of_two():
+    res = fft(array([1.0, 2.0, 3.0]))
+
+    re, im = res.get([1])
+    assert re == pytest.approx(-1.5)
+    assert im == pytest.approx(0.8660254)
+
+
+def test_fft_of_matrix_raises():
+    with pytest.raises(ValueError):
+        fft(array([[1.0, 2.0], [3.0, 4.0]]))
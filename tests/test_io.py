@@ -0,0 +1,90 @@
+import os
+import struct
+import tempfile
+
+import pytest
+import pyfacet as pf
+from pyfacet import NdArrayD, NdArrayI, frombuffer
+
+
+def test_bytes_roundtrip_matrix():
+    a = NdArrayD([2, 3], [1.5, -2, 0, 4, 0.25, 6])
+
+    buffer = a.tobytes()
+    assert buffer == struct.pack("<6f", 1.5, -2, 0, 4, 0.25, 6)
+
+    res = frombuffer(buffer, "f32", [2, 3])
+
+    assert isinstance(res, NdArrayD)
+    assert res.shape == [2, 3]
+    assert (res == a).all()
+
+
+def test_bytes_roundtrip_int():
+    a = NdArrayI([2, 2], [1, -2, 2 ** 40, 0])
+
+    buffer = a.tobytes()
+    assert buffer == struct.pack("<4q", 1, -2, 2 ** 40, 0)
+
+    res = frombuffer(buffer, "i64", [2, 2])
+
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [1, -2, 2 ** 40, 0]
+
+
+def test_frombuffer_errors():
+    buffer = NdArrayD([4], [1, 2, 3, 4]).tobytes()
+
+    with pytest.raises(ValueError):
+        frombuffer(buffer, "f32", [2, 3])
+    with pytest.raises(ValueError):
+        frombuffer(buffer, "f64", [4])
+
+
+def test_byteswap():
+    a = NdArrayD([2], [1.5, -2])
+
+    swapped = a.byteswap()
+    assert swapped.tobytes() == struct.pack(">2f", 1.5, -2)
+    assert list(a) == [1.5, -2]
+    assert (swapped.byteswap() == a).all()
+
+    # values read from a big-endian buffer
+    big = frombuffer(struct.pack(">2f", 0.25, 6), "f32", [2])
+    assert big.byteswap(inplace=True) is big
+    assert list(big) == [0.25, 6]
+
+
+def test_byteswap_int():
+    a = NdArrayI([2], [1, 0x0102030405060708])
+
+    swapped = a.byteswap()
+    assert isinstance(swapped, NdArrayI)
+    assert list(swapped) == [1 << 56, 0x0807060504030201]
+    assert swapped.tobytes() == struct.pack(">2q", 1, 0x0102030405060708)
+    assert list(swapped.byteswap()) == [1, 0x0102030405060708]
+
+
+def test_save_and_load_weights():
+    model = pf.Sequential(pf.DenseLayer(2, 3), pf.ReluLayer(), pf.Conv2d(1, 2, 2))
+    restored = pf.Sequential(pf.DenseLayer(2, 3), pf.ReluLayer(), pf.Conv2d(1, 2, 2))
+
+    with tempfile.TemporaryDirectory() as tmp:
+        path = os.path.join(tmp, "model.weights")
+        model.save_weights(path)
+        restored.load_weights(path)
+
+        for a, b in zip(model.parameters(), restored.parameters()):
+            assert a.value.shape == b.value.shape
+            assert list(a.value) == list(b.value)
+
+        dense = pf.DenseLayer(2, 3)
+        pf.save_weights(path, [dense])
+        with pytest.raises(ValueError):
+            pf.load_weights(path, pf.DenseLayer(3, 2))
+        with pytest.raises(ValueError):
+            restored.load_weights(path)
+
+        with pytest.raises(OSError):
+            pf.load_weights(os.path.join(tmp, "missing"), dense)
+
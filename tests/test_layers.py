@@ -1,3 +1,4 @@
+import pytest
 import pyfacet as pf
 
 
@@ -17,3 +18,221 @@ def test_dense_forward():
     layer.forward(X)
 
     assert layer.output.shape == [128, 8]
+
+
+def test_sequential_forward():
+    model = pf.Sequential(pf.DenseLayer(4, 8), pf.ReluLayer(), pf.DenseLayer(8, 3))
+
+    X = pf.array([[1, 2, 3, 4]] * 16)
+
+    out = model.forward(X)
+
+    assert out.shape == [16, 3]
+    assert len(model.layers) == 3
+    # weights and biases of both dense layers
+    assert [p.value.shape for p in model.parameters()] == [[4, 8], [8], [8, 3], [3]]
+
+
+def test_sequential_parameters_share_the_layers():
+    dense = pf.DenseLayer(2, 2)
+    conv = pf.Conv2d(1, 1, 1)
+    model = pf.Sequential(dense)
+    X = pf.array([[1, 2]])
+
+    weights, biases = model.parameters()
+    biases.value = pf.array([10, 20])
+    weights.value = pf.zeros([2, 2])
+
+    assert list(model.forward(X)) == [10, 20]
+    assert list(dense.biases) == [10, 20]
+
+    w, b = conv.parameters()
+    w.value = pf.ones([1, 1, 1, 1]) * pf.scalar(3.0)
+    b.value = pf.zeros([1])
+    conv.forward(pf.NdArrayD([1, 1, 1, 2], [1, 2]))
+    assert list(conv.output) == [3, 6]
+
+
+def test_sequential_backward():
+    first = pf.DenseLayer(4, 8)
+    model = pf.Sequential(first, pf.ReluLayer(), pf.DenseLayer(8, 3))
+
+    X = pf.array([[1, 2, 3, 4]] * 16)
+    out = model.forward(X)
+
+    dinputs = model.backward(pf.ones_like(out))
+
+    assert dinputs.shape == [16, 4]
+    assert first.dweights.shape == [4, 8]
+
+
+def test_parameter_zero_grad():
+    p = pf.Parameter(pf.array([[1, 2, 3], [4, 5, 6]]))
+
+    assert p.grad.shape == p.value.shape
+    assert list(p.grad) == [0] * 6
+
+    p.grad = pf.ones([2, 3])
+    p.zero_grad()
+
+    assert p.grad.shape == [2, 3]
+    assert list(p.grad) == [0] * 6
+
+
+def test_dense_parameters_track_gradients():
+    layer = pf.DenseLayer(4, 2)
+
+    layer.forward(pf.array([[1, 2, 3, 4]] * 8))
+    layer.backward(pf.ones([8, 2]))
+
+    weights, biases = layer.parameters()
+    assert weights.grad.shape == [4, 2]
+    assert biases.grad.shape == [2]
+    assert list(weights.grad) == list(layer.dweights)
+
+    layer.zero_grad()
+    assert list(layer.dweights) == [0] * 8
+
+
+def test_embedding_lookup():
+    layer = pf.Embedding(4, 3)
+    layer.weights = pf.array([[0, 1, 2], [3, 4, 5], [6, 7, 8], [9, 10, 11]])
+
+    out = layer.forward(pf.NdArrayI([2], [0, 2]))
+
+    assert out.shape == [2, 3]
+    assert list(out) == [0, 1, 2, 6, 7, 8]
+
+
+def test_embedding_backward():
+    layer = pf.Embedding(4, 2)
+
+    layer.forward(pf.NdArrayI([3], [3, 1, 3]))
+    layer.backward(pf.array([[1, 2], [3, 4], [5, 6]]))
+
+    assert layer.dweights.shape == [4, 2]
+    assert list(layer.dweights) == [0, 0, 3, 4, 0, 0, 6, 8]
+
+
+def test_embedding_normalize():
+    from pyfacet.layer import Embedding
+
+    layer = Embedding(2, 2, normalize=True)
+    layer.weights = pf.array([[3, 4], [0, 2]])
+
+    out = layer.forward([[1], [0]])
+
+    assert out.shape == [2, 1, 2]
+    assert list(out) == pytest.approx([0, 1, 0.6, 0.8])
+
+    # the gradient of a direction along the normalized row vanishes
+    layer.backward(pf.array([[[0, 1]], [[0.6, 0.8]]]))
+    assert max(abs(x) for x in layer.dweights) < 1e-6
+
+    with pytest.raises(IndexError):
+        layer.forward([2])
+
+
+def test_dense_fused_activation_matches_separate_relu():
+    fused = pf.DenseLayer(3, 4, activation="relu")
+    dense = pf.DenseLayer(3, 4)
+    relu = pf.ReluLayer()
+    dense.weights = fused.weights
+    dense.biases = fused.biases
+    assert fused.activation == "relu"
+    assert dense.activation is None
+
+    X = pf.array([[1, -2, 3], [-1, 0.5, 2]])
+    fused.forward(X)
+    dense.forward(X)
+    relu.forward(dense.output)
+    assert list(fused.output) == pytest.approx(list(relu.output))
+
+    dvalues = pf.array([[1, 2, 3, 4], [-1, 0.5, 2, 1]])
+    fused.backward(dvalues)
+    relu.backward(dvalues)
+    dense.backward(relu.dinputs)
+
+    assert list(fused.dweights) == pytest.approx(list(dense.dweights))
+    assert list(fused.dbiases) == pytest.approx(list(dense.dbiases))
+    assert list(fused.dinputs) == pytest.approx(list(dense.dinputs))
+
+    with pytest.raises(ValueError):
+        pf.DenseLayer(3, 4, activation="tanh")
+
+
+def test_upsample_nearest():
+    from pyfacet.layer import Upsample
+
+    layer = Upsample(2)
+    layer.forward(pf.NdArrayD([1, 1, 2, 2], [1, 2, 3, 4]))
+
+    assert layer.output.shape == [1, 1, 4, 4]
+    assert list(layer.output) == [1, 1, 2, 2, 1, 1, 2, 2, 3, 3, 4, 4, 3, 3, 4, 4]
+
+    layer.backward(pf.NdArrayD([1, 1, 4, 4], list(range(16))))
+
+    assert layer.dinputs.shape == [1, 1, 2, 2]
+    assert list(layer.dinputs) == [10, 18, 42, 50]
+
+
+def test_upsample_in_sequential():
+    model = pf.Sequential(pf.Upsample(3), pf.ReluLayer())
+
+    out = model.forward(pf.NdArrayD([2, 1, 1, 2], [1, -1, 2, 3]))
+
+    assert out.shape == [2, 1, 3, 6]
+    assert list(out)[:6] == [1, 1, 1, 0, 0, 0]
+
+    with pytest.raises(ValueError):
+        pf.Upsample(2, mode="bilinear")
+
+
+def test_conv2d():
+    layer = pf.Conv2d(2, 3, 3, stride=2, padding=1)
+
+    assert layer.weights.shape == [3, 2, 3, 3]
+    assert layer.biases.shape == [3]
+
+    layer.forward(pf.NdArrayD([4, 2, 5, 5], [1] * 200))
+    assert layer.output.shape == [4, 3, 3, 3]
+
+    layer.backward(pf.NdArrayD([4, 3, 3, 3], [1] * 108))
+    assert layer.dinputs.shape == [4, 2, 5, 5]
+    assert layer.dweights.shape == [3, 2, 3, 3]
+    assert list(layer.dbiases) == [36, 36, 36]
+
+    with pytest.raises(ValueError):
+        layer.forward(pf.NdArrayD([4, 1, 5, 5], [1] * 100))
+
+
+def test_conv2d_padding_modes():
+    same = pf.Conv2d(1, 1, 3, padding="same")
+    same.forward(pf.NdArrayD([1, 1, 5, 5], [1] * 25))
+    assert same.output.shape == [1, 1, 5, 5]
+    assert same.padding == "same"
+
+    # the 4x4 kernel needs 3 zeros per axis, more of them go after the inputs
+    even = pf.Conv2d(1, 1, 4, padding="same")
+    even.forward(pf.NdArrayD([1, 1, 5, 5], [1] * 25))
+    assert even.output.shape == [1, 1, 5, 5]
+
+    strided = pf.Conv2d(1, 1, 3, stride=2, padding="same")
+    strided.forward(pf.NdArrayD([1, 1, 5, 5], [1] * 25))
+    assert strided.output.shape == [1, 1, 3, 3]
+
+    valid = pf.Conv2d(1, 1, 3, padding="valid")
+    valid.forward(pf.NdArrayD([1, 1, 5, 5], [1] * 25))
+    assert valid.output.shape == [1, 1, 3, 3]
+    assert pf.Conv2d(1, 1, 3, padding=2).padding == 2
+
+    with pytest.raises(ValueError):
+        pf.Conv2d(1, 1, 3, padding="full")
+
+
+def test_conv2d_in_sequential():
+    model = pf.Sequential(pf.Conv2d(1, 2, 2), pf.ReluLayer())
+    out = model.forward(pf.NdArrayD([1, 1, 3, 3], list(range(9))))
+
+    assert out.shape == [1, 2, 2, 2]
+    assert len(model.parameters()) == 2
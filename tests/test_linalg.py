@@ -1,10 +1,12 @@
 """
 testing generic linalg functions
 """
-from pyfacet import NdArrayD, normalize_vectors
+import pytest
+import pyfacet
+from pyfacet import NdArrayD, NdArrayI, array, logsumexp, normalize, normalize_vectors
 
 import random
-from math import sqrt
+from math import inf, log, sqrt
 
 
 def test_normalize_vectors():
@@ -16,3 +18,312 @@ def test_normalize_vectors():
         l = sqrt(sum(x * x for x in vec))
         diff = abs(1.0 - l)
         assert diff < 0.02
+
+
+def test_normalize_rows():
+    a = array([[3, 4], [0, 0], [1, 1]])
+
+    res = normalize(a)
+
+    assert res.shape == [3, 2]
+    for row, expected in zip(res.iter_rows(), [1.0, 0.0, 1.0]):
+        assert abs(sqrt(sum(x * x for x in row)) - expected) < 1e-6
+    assert list(res.iter_rows())[1] == [0, 0]
+
+
+def test_normalize_columns_max_norm():
+    a = array([[1, -4], [2, 2]])
+
+    res = normalize(a, axis=0, ord=inf)
+
+    assert list(res) == [0.5, -1, 1, 0.5]
+
+
+def test_logsumexp():
+    assert abs(logsumexp([0, 0])[0] - log(2)) < 1e-6
+
+    res = logsumexp([1000, 1000])
+    assert abs(res[0] - (1000 + log(2))) < 1e-3
+
+
+def test_logsumexp_rows():
+    res = logsumexp(array([[0, 0], [1000, 1000]]))
+
+    assert res.shape == [2]
+    assert abs(res[0] - log(2)) < 1e-6
+
+
+def test_int_matmul():
+    a = NdArrayI([2, 3], [1, -2, 1, 2, 1, 3])
+    b = NdArrayI([3, 2], [2, 1, 3, 2, 1, 1])
+
+    c = a.matmul(b)
+
+    assert isinstance(c, NdArrayI)
+    assert (c == NdArrayI([2, 2], [-3, -2, 10, 7])).all()
+
+
+def test_int_inner_and_outer():
+    a = NdArrayI([3], [1, 2, 3])
+    b = NdArrayI([3], [4, -5, 6])
+
+    assert a.inner(b) == 12
+
+    res = a.outer(NdArrayI([2], [1, -1]))
+    assert isinstance(res, NdArrayI)
+    assert res.shape == [3, 2]
+    assert (res == NdArrayI([3, 2], [1, -1, 2, -2, 3, -3])).all()
+
+    with pytest.raises(ValueError):
+        a.inner(NdArrayI([2], [1, 2]))
+
+
+def test_int_reductions():
+    a = NdArrayI([2, 2], [1, 2, 3, 4])
+
+    res = pyfacet.sum(a)
+    assert isinstance(res, NdArrayI)
+    assert (res == NdArrayI([2], [3, 7])).all()
+
+    res = pyfacet.mean(a)
+    assert isinstance(res, NdArrayD)
+    assert list(res) == [1.5, 3.5]
+
+
+def test_bmm_matches_matmul_per_batch():
+    a0 = [1, 2, 3, 4, 5, 6]
+    a1 = [-1, 0, 2, 1, 3, -2]
+    b0 = [1, 0, 2, 1, 0, 3, 1, 1, 2, -1, 0, 1]
+    b1 = [2, -1, 0, 1, 1, 1, 3, 0, 0, 2, 1, -3]
+    a = NdArrayD([2, 2, 3], a0 + a1)
+    b = NdArrayD([2, 3, 4], b0 + b1)
+
+    res = pyfacet.bmm(a, b)
+
+    assert res.shape == [2, 2, 4]
+    c0 = NdArrayD([2, 3], a0).matmul(NdArrayD([3, 4], b0))
+    c1 = NdArrayD([2, 3], a1).matmul(NdArrayD([3, 4], b1))
+    assert list(res) == list(c0) + list(c1)
+
+
+def test_bmm_int():
+    a = NdArrayI([1, 2, 2], [1, 2, 3, 4])
+    b = NdArrayI([1, 2, 1], [1, -1])
+
+    res = pyfacet.bmm(a, b)
+
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [-1, -1]
+
+
+def test_bmm_shape_errors():
+    with pytest.raises(ValueError):
+        pyfacet.bmm(NdArrayD([2, 3], [0] * 6), NdArrayD([2, 3, 4], [0] * 24))
+    with pytest.raises(ValueError):
+        pyfacet.bmm(NdArrayD([2, 2, 3], [0] * 12), NdArrayD([3, 3, 4], [0] * 36))
+    with pytest.raises(ValueError):
+        pyfacet.bmm(NdArrayD([2, 2, 3], [0] * 12), NdArrayD([2, 2, 4], [0] * 16))
+
+
+def test_prod():
+    res = pyfacet.prod([1, 2, 3, 4])
+    assert isinstance(res, NdArrayD)
+    assert list(res) == [24]
+
+    a = NdArrayD([2, 3], [1, 2, 3, 4, 5, 6])
+    assert list(pyfacet.prod(a, axis=0)) == [4, 10, 18]
+    assert list(pyfacet.prod(a, axis=-1)) == [6, 120]
+    assert pyfacet.prod(a, axis=0, keepdims=True).shape == [1, 3]
+    assert pyfacet.prod(a, keepdims=True).shape == [1, 1]
+
+
+def test_prod_empty_is_one():
+    empty = NdArrayD([2, 0], [])
+
+    assert list(pyfacet.prod(empty)) == [1]
+    assert list(pyfacet.prod(empty, axis=1)) == [1, 1]
+
+    with pytest.raises(ValueError):
+        pyfacet.array([])
+
+
+def test_prod_int():
+    a = NdArrayI([4], [1, 2, 3, 4])
+
+    res = pyfacet.prod(a)
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [24]
+
+    with pytest.raises(OverflowError):
+        pyfacet.prod(NdArrayI([3], [2 ** 40, 2 ** 40, 1]))
+
+
+def test_tril_triu():
+    ones = pyfacet.ones([3, 3])
+
+    lower = pyfacet.tril(ones)
+    assert lower.shape == [3, 3]
+    assert list(lower) == [1, 0, 0, 1, 1, 0, 1, 1, 1]
+
+    upper = pyfacet.triu(ones)
+    assert list(upper) == [1, 1, 1, 0, 1, 1, 0, 0, 1]
+
+    assert list(pyfacet.tril(ones, k=-1)) == [0, 0, 0, 1, 0, 0, 1, 1, 0]
+    assert list(pyfacet.triu(ones, k=1)) == [0, 1, 1, 0, 0, 1, 0, 0, 0]
+
+    res = pyfacet.triu(NdArrayI([2, 2, 2], [1, 2, 3, 4, 5, 6, 7, 8]))
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [1, 2, 0, 4, 5, 6, 0, 8]
+
+    with pytest.raises(ValueError):
+        pyfacet.tril([1, 2, 3])
+
+
+def test_tri():
+    res = pyfacet.tri(2, 3)
+
+    assert res.shape == [2, 3]
+    assert list(res) == [1, 0, 0, 1, 1, 0]
+    assert list(pyfacet.tri(2, k=1)) == [1, 1, 1, 1]
+
+
+def test_pairwise_distances():
+    a = pyfacet.array([[0, 0], [3, 4], [1, 1]])
+
+    res = pyfacet.pairwise_distances(a)
+
+    assert res.shape == [3, 3]
+    for i in range(3):
+        assert abs(res.get([i, i])) < 1e-3
+        for j in range(3):
+            assert res.get([i, j]) == res.get([j, i])
+    assert res.get([0, 1]) == pytest.approx(5, abs=1e-4)
+    assert res.get([1, 2]) == pytest.approx(sqrt(13), abs=1e-4)
+
+    res = pyfacet.pairwise_distances(a, pyfacet.array([[1, 0]]), metric="manhattan")
+    assert res.shape == [3, 1]
+    assert list(res) == [1, 6, 1]
+
+    with pytest.raises(ValueError):
+        pyfacet.pairwise_distances(a, pyfacet.array([[1, 0, 0]]))
+    with pytest.raises(ValueError):
+        pyfacet.pairwise_distances(a, metric="cosine")
+
+
+def test_block():
+    one_by_one = [pyfacet.scalar(x).reshape([1, 1]) for x in [1, 2, 3, 4]]
+    a, b, c, d = one_by_one
+
+    res = pyfacet.block([[a, b], [c, d]])
+    assert res.shape == [2, 2]
+    assert list(res) == [1, 2, 3, 4]
+
+    m = NdArrayD([2, 2], [1, 2, 3, 4])
+    res = pyfacet.block([[m, pyfacet.zeros([2, 1])], [pyfacet.array([5, 6, 7])]])
+    assert res.shape == [3, 3]
+    assert list(res) == [1, 2, 0, 3, 4, 0, 5, 6, 7]
+
+    assert pyfacet.block([m, m]).shape == [2, 4]
+
+    ints = pyfacet.block([[NdArrayI([1], [1]), NdArrayI([1], [2])]])
+    assert isinstance(ints, NdArrayI)
+    assert list(ints) == [1, 2]
+
+    with pytest.raises(ValueError):
+        pyfacet.block([[m, pyfacet.zeros([1, 1])]])
+    with pytest.raises(ValueError):
+        pyfacet.block([[m], [pyfacet.zeros([1, 3])]])
+
+
+def test_block_diag():
+    a = NdArrayD([2, 2], [1, 2, 3, 4])
+    b = NdArrayD([2, 2], [5, 6, 7, 8])
+
+    res = pyfacet.block_diag(a, b)
+    assert res.shape == [4, 4]
+    # fmt: off
+    assert list(res) == [
+        1, 2, 0, 0,
+        3, 4, 0, 0,
+        0, 0, 5, 6,
+        0, 0, 7, 8,
+    ]
+    # fmt: on
+
+    res = pyfacet.block_diag(pyfacet.array([1, 2]), NdArrayD([2, 1], [3, 4]))
+    assert res.shape == [3, 3]
+    assert list(res) == [1, 2, 0, 0, 0, 3, 0, 0, 4]
+
+    with pytest.raises(ValueError):
+        pyfacet.block_diag()
+
+
+def test_vander():
+    res = pyfacet.vander([1, 2, 3], 3)
+    assert res.shape == [3, 3]
+    assert list(res) == [1, 1, 1, 4, 2, 1, 9, 3, 1]
+
+    assert pyfacet.vander(pyfacet.array([2, 3])).shape == [2, 2]
+    assert list(pyfacet.vander([2], 4)) == [8, 4, 2, 1]
+
+    with pytest.raises(ValueError):
+        pyfacet.vander(NdArrayD([2, 2], [1, 2, 3, 4]))
+
+
+def test_polyval():
+    assert pyfacet.polyval([1, 0, -1], 2) == 3
+
+    res = pyfacet.polyval([2, 1], NdArrayD([2, 2], [0, 1, 2, 3]))
+    assert res.shape == [2, 2]
+    assert list(res) == [1, 3, 5, 7]
+
+    # vander(x) @ coeffs evaluates the polynomial at each x
+    x = [1, 2, 3]
+    coeffs = pyfacet.array([[1], [0], [-1]])
+    assert list(pyfacet.vander(x).matmul(coeffs)) == list(pyfacet.polyval([1, 0, -1], x))
+
+
+def test_flatten_batch():
+    a = NdArrayD([2, 3, 4], list(range(24)))
+    w = NdArrayD([4, 5], [1] * 20)
+
+    flat, leading = pyfacet.flatten_batch(a)
+    assert flat.shape == [6, 4]
+    assert leading == [2, 3]
+    assert a.shape == [2, 3, 4]
+
+    res = pyfacet.unflatten_batch(flat.matmul(w), leading)
+    assert res.shape == [2, 3, 5]
+    # every output column holds the sum of an input row
+    assert list(res)[:5] == [0 + 1 + 2 + 3] * 5
+    assert list(res)[-5:] == [20 + 21 + 22 + 23] * 5
+
+    flat, leading = pyfacet.flatten_batch(pyfacet.array([1, 2]))
+    assert flat.shape == [1, 2]
+    assert pyfacet.unflatten_batch(flat, leading).shape == [2]
+
+    with pytest.raises(ValueError):
+        pyfacet.unflatten_batch(flat, [2, 3])
+    with pytest.raises(ValueError):
+        pyfacet.flatten_batch(pyfacet.scalar(1))
+
+
+def test_clamp_norm():
+    a = array([[3, 4], [0.3, 0.4], [2, 2], [0, 0]])
+
+    assert pyfacet.clamp_norm(a, 1.0) is None
+
+    rows = list(a.iter_rows())
+    for row, expected in zip(rows, [1.0, 0.5, 1.0, 0.0]):
+        assert abs(sqrt(sum(x * x for x in row)) - expected) < 1e-6
+    assert rows[0] == list(array([0.6, 0.8]))
+    assert rows[1] == list(array([0.3, 0.4]))
+    assert rows[3] == [0, 0]
+
+
+def test_clamp_norm_axis():
+    a = array([[3, 0.1], [4, 0.1]])
+
+    pyfacet.clamp_norm(a, 2.0, axis=0)
+
+    assert list(a) == list(array([1.2, 0.1, 1.6, 0.1]))
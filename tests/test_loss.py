@@ -1,5 +1,7 @@
+import math
+
 from pyfacet import categorical_cross_entropy, array
-from pyfacet.loss import MeanSquaredError
+from pyfacet.loss import BinaryCrossentropy, MeanSquaredError
 
 
 def test_cce_simple():
@@ -31,3 +33,20 @@ def test_mse_simple():
     # just make sure dinputs is defined
     print(loss.dinputs)
     assert loss.dinputs.shape == [2, 3]
+
+
+def test_bce_simple():
+    x = array([[0.5, 0.5], [0.9, 0.1]])
+    y = array([[1, 0], [1, 0]])
+
+    loss = BinaryCrossentropy()
+    res = loss.forward(x, y)
+
+    assert len(res) == 2
+    assert abs(res[0] - math.log(2)) < 1e-5
+    assert abs(res[1] + math.log(0.9)) < 1e-5
+
+    loss.backward(x, y)
+    # -(y / x - (1 - y) / (1 - x)) / outputs / samples
+    assert abs(loss.dinputs[[0, 0]] + 0.5) < 1e-5
+    assert abs(loss.dinputs[[0, 1]] - 0.5) < 1e-5
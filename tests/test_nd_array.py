@@ -1,6 +1,6 @@
 import pytest
 import pyfacet
-from pyfacet import NdArrayD, NdArrayI, array
+from pyfacet import NdArrayC, NdArrayD, NdArrayI, array
 
 
 def test_factory():
@@ -42,6 +42,135 @@ def test_sum_scalar():
     assert (res == pyfacet.scalar(69)).all()
 
 
+def test_sum_keepdims():
+    a = NdArrayD([2, 3], [1, 2, 3, 4, 5, 6])
+
+    res = pyfacet.sum(a, keepdims=True)
+
+    assert res.shape == [2, 1]
+    assert (res == NdArrayD([2, 1], [6, 15])).all()
+    assert pyfacet.sum([1, 2, 3], keepdims=True).shape == [1]
+
+
+def test_reductions_keepdims():
+    a = NdArrayD([2, 2, 3], [i for i in range(12)])
+
+    assert pyfacet.mean(a, keepdims=True).shape == [2, 2, 1]
+    assert pyfacet.std(a, keepdims=True).shape == [2, 2, 1]
+    assert pyfacet.std_squared(a, keepdims=True).shape == [2, 2, 1]
+    assert pyfacet.argmax(a, keepdims=True).shape == [2, 2, 1]
+    assert pyfacet.argmin(a, keepdims=True).shape == [2, 2, 1]
+    assert pyfacet.veclen(a, keepdims=True).shape == [2, 2, 1]
+    assert pyfacet.logsumexp(a, axis=1, keepdims=True).shape == [2, 1, 3]
+    assert pyfacet.sum(a).shape == [2, 2]
+
+
+def test_reductions_along_axis():
+    a = NdArrayD([2, 3], [1, 2, 3, 4, 5, 9])
+
+    assert list(pyfacet.sum(a, axis=0)) == [5, 7, 12]
+    assert pyfacet.sum(a, axis=0).shape == [3]
+    assert list(pyfacet.mean(a, axis=0)) == [2.5, 3.5, 6]
+    assert pyfacet.mean(a, axis=0, keepdims=True).shape == [1, 3]
+    assert list(pyfacet.var(a, axis=0)) == [2.25, 2.25, 9]
+    assert list(pyfacet.std(a, axis=0)) == [1.5, 1.5, 3]
+    assert pyfacet.std_squared(a, axis=-2, keepdims=True).shape == [1, 3]
+    assert list(pyfacet.amax(a, axis=0)) == [4, 5, 9]
+    assert list(pyfacet.amin(a)) == [1, 4]
+    assert pyfacet.amax(a, keepdims=True).shape == [2, 1]
+
+    b = NdArrayI([2, 2], [1, -5, 3, 2])
+    assert isinstance(pyfacet.sum(b, axis=0), NdArrayI)
+    assert list(pyfacet.sum(b, axis=0)) == [4, -3]
+    assert list(pyfacet.amin(b, axis=0)) == [1, -5]
+
+    with pytest.raises(ValueError):
+        pyfacet.sum(a, axis=2)
+    with pytest.raises(ValueError):
+        pyfacet.std(a, mean=pyfacet.mean(a), axis=0)
+    # axis is keyword-only across the family, so positional calls can not mix it up with keepdims
+    for f in [pyfacet.sum, pyfacet.mean, pyfacet.var, pyfacet.amax, pyfacet.amin]:
+        with pytest.raises(TypeError):
+            f(a, False, 0)
+
+
+def test_int_sum_raises_on_overflow():
+    a = NdArrayI([2], [2 ** 63 - 1, 1])
+
+    with pytest.raises(OverflowError):
+        pyfacet.sum(a)
+    with pytest.raises(OverflowError):
+        pyfacet.sum(NdArrayI([2, 1], [2 ** 63 - 1, 1]), axis=0)
+    assert list(pyfacet.sum(NdArrayI([2], [2 ** 63 - 1, -1]))) == [2 ** 63 - 2]
+
+
+def test_int_moments_are_exact():
+    # 2 ** 24 + 1 is not representable in f32, converting first gives a variance of 1
+    a = NdArrayI([2], [2 ** 24 + 1, 2 ** 24 + 2])
+
+    assert list(pyfacet.var(a)) == [0.25]
+    assert list(pyfacet.std(a)) == [0.5]
+    assert list(pyfacet.var(NdArrayI([2, 1], [2 ** 24 + 1, 2 ** 24 + 2]), axis=0)) == [0.25]
+    assert pyfacet.mean(a).shape == pyfacet.mean(NdArrayD([2], [1, 2])).shape
+
+
+def test_amax_propagates_nan():
+    a = NdArrayD([2, 2], [1, float("nan"), 3, 4])
+
+    res = list(pyfacet.amax(a, axis=0))
+
+    assert res[0] == 3
+    assert res[1] != res[1]
+
+
+def test_broadcasting_binary_ops():
+    col = NdArrayD([2, 1], [1, 2])
+    row = NdArrayD([3], [10, 20, 30])
+
+    res = col + row
+    assert res.shape == [2, 3]
+    assert list(res) == [11, 21, 31, 12, 22, 32]
+    assert list(row - col) == [9, 19, 29, 8, 18, 28]
+
+    mask = col < NdArrayD([1, 2], [1.5, 0])
+    assert mask.shape == [2, 2]
+    assert list(mask) == [True, False, False, False]
+
+    with pytest.raises(ValueError):
+        NdArrayD([2, 3], [0] * 6) * NdArrayD([2], [1, 2])
+    with pytest.raises(ValueError):
+        NdArrayI([3], [1, 2, 3]) == NdArrayI([2], [1, 2])
+
+
+def test_slicing_returns_view():
+    a = NdArrayD([2, 3, 4], list(range(24)))
+
+    v = a[1, ::-1, 2]
+    assert isinstance(v, pyfacet.NdArrayView)
+    assert v.shape == [3]
+    assert list(v) == [22, 18, 14]
+    assert v[1:][0] == 18
+
+    w = a[:, 1:3]
+    assert w.shape == [2, 2, 4]
+    assert len(w) == 16
+    assert w[1, 0, 3] == 19
+
+    # the view keeps the values it was created from
+    a.set([1, 2, 2], -1)
+    assert list(v) == [22, 18, 14]
+
+    res = v.copy()
+    assert isinstance(res, NdArrayD)
+    assert list(pyfacet.sum(v)) == [54]
+
+    assert list(NdArrayI([4], [1, 2, 3, 4])[::2].copy()) == [1, 3]
+    with pytest.raises(IndexError):
+        a[2, :]
+    with pytest.raises(IndexError):
+        a[0, :, 0, 0]
+
+
 def test_ctor():
     """
     smoke test
@@ -194,4 +323,792 @@ def test_rotate_cw():
 
     res = arr.rotate_cw()
     assert arr.shape == res.shape
-    assert (res == pyfacet.array([[[3] * 4, [4] * 4, [1] * 4, [2] * 4] * 4])).all()
+    assert (res == pyfacet.array([[[3] * 4, [4] * 4, [1] * 4, [2] * 4]] * 4)).all()
+
+
+def test_gather_scatter():
+    a = pyfacet.array([[1, 2, 3], [4, 5, 6]])
+    index = NdArrayI([2, 2], [2, 0, 1, 1])
+
+    res = pyfacet.gather(a, 1, index)
+
+    assert res.shape == [2, 2]
+    assert (res == pyfacet.array([[3, 1], [5, 5]])).all()
+
+    res = pyfacet.scatter(pyfacet.zeros([2, 3]), 1, index, res)
+
+    assert (res == pyfacet.array([[1, 0, 3], [0, 5, 0]])).all()
+
+
+def test_gather_out_of_bounds_raises():
+    a = pyfacet.array([[1, 2, 3], [4, 5, 6]])
+    with pytest.raises(IndexError):
+        pyfacet.gather(a, 1, [[0], [3]])
+
+
+def test_take_along_axis_argsort():
+    a = pyfacet.array([[3, 1, 2], [6, 5, 4]])
+
+    index = pyfacet.argsort(a, axis=1)
+    assert isinstance(index, NdArrayI)
+    assert list(index) == [1, 2, 0, 2, 1, 0]
+
+    res = pyfacet.take_along_axis(a, index, 1)
+    assert res.shape == [2, 3]
+    assert list(res) == [1, 2, 3, 4, 5, 6]
+
+    res = pyfacet.take_along_axis(a, pyfacet.argsort(a, axis=0), 0)
+    assert list(res) == [3, 1, 2, 6, 5, 4]
+
+
+def test_take_along_axis_negative_indices():
+    a = NdArrayI([2, 3], [1, 2, 3, 4, 5, 6])
+
+    res = pyfacet.take_along_axis(a, NdArrayI([2, 1], [-1, 0]), -1)
+
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [3, 4]
+    with pytest.raises(IndexError):
+        pyfacet.take_along_axis(a, NdArrayI([2, 1], [-4, 0]), 1)
+
+
+def test_argmax_with_2_threads():
+    pyfacet.set_num_threads(2)
+    assert pyfacet.get_num_threads() == 2
+
+    a = array([[1, 2, 3], [4, 2, 3], [1, 5, 3]] * 100)
+
+    res = pyfacet.argmax(a)
+
+    assert res.shape == [300]
+    assert (res == NdArrayI([300], [2, 0, 1] * 100)).all()
+
+    pyfacet.set_num_threads(0)
+
+
+def test_delete_column():
+    a = pyfacet.array([[1, 2, 3], [4, 5, 6]])
+
+    res = pyfacet.delete(a, 1, axis=1)
+
+    assert res.shape == [2, 2]
+    assert (res == pyfacet.array([[1, 3], [4, 6]])).all()
+
+
+def test_insert_row():
+    a = pyfacet.array([[1, 2, 3], [4, 5, 6]])
+
+    res = pyfacet.insert(a, 0, [7, 8, 9], axis=0)
+
+    assert res.shape == [3, 3]
+    assert (res == pyfacet.array([[7, 8, 9], [1, 2, 3], [4, 5, 6]])).all()
+
+
+def test_delete_flattened():
+    a = pyfacet.array([[1, 2, 3], [4, 5, 6]])
+
+    res = pyfacet.delete(a, [0, 5])
+
+    assert res.shape == [4]
+    assert list(res) == [2, 3, 4, 5]
+
+
+def test_split_at():
+    a = NdArrayD([10, 4], [i for i in range(40)])
+
+    train, validation = pyfacet.split_at(a, 8)
+
+    assert train.shape == [8, 4]
+    assert validation.shape == [2, 4]
+    assert list(train) == [i for i in range(32)]
+    assert list(validation) == [i for i in range(32, 40)]
+
+    left, right = pyfacet.split_at(a, 1, axis=1)
+    assert left.shape == [10, 1]
+    assert right.shape == [10, 3]
+
+    with pytest.raises(IndexError):
+        pyfacet.split_at(a, 11)
+
+
+def test_saturating_add():
+    i64_max = 2 ** 63 - 1
+    a = NdArrayI([2], [i64_max, -(2 ** 63)])
+    one = NdArrayI([2], [1, -1])
+
+    assert list(a.add_sat(one)) == [i64_max, -(2 ** 63)]
+    assert list(a.sub_sat(one)) == [i64_max - 1, -(2 ** 63) + 1]
+    assert list(a.mul_sat(NdArrayI([2], [2, 2]))) == [i64_max, -(2 ** 63)]
+
+
+def test_operators_wrap_on_overflow():
+    i64_max = 2 ** 63 - 1
+    a = NdArrayI([2], [i64_max, -(2 ** 63)])
+    one = NdArrayI([2], [1, -1])
+
+    assert list(a + one) == [-(2 ** 63), i64_max]
+    assert list(a - one) == [i64_max - 1, -(2 ** 63) + 1]
+    assert list(a * NdArrayI([2], [2, 2])) == [-2, 0]
+
+
+def test_cov_corrcoef():
+    a = [[0, 1, 2], [2, 1, 0]]
+
+    c = pyfacet.cov(a)
+    assert c.shape == [2, 2]
+    assert list(c) == [1.0, -1.0, -1.0, 1.0]
+
+    r = pyfacet.corrcoef([[1, 2, 3, 4], [2, 4, 6, 9]])
+    assert r.shape == [2, 2]
+    assert abs(r[0, 0] - 1.0) < 1e-6
+    assert abs(r[1, 1] - 1.0) < 1e-6
+
+
+def test_unique():
+    res = pyfacet.unique([3, 1, 2, 1, 3])
+
+    assert list(res) == [1, 2, 3]
+
+
+def test_unique_return_counts():
+    values, counts = pyfacet.unique([[3, 1, 2], [3, 3, 1]], return_counts=True)
+
+    assert list(values) == [1, 2, 3]
+    assert list(counts) == [2, 1, 3]
+
+
+def test_array_from():
+    a = pyfacet.array_from([1, 2, 3, 4, 5, 6], [2, 3])
+
+    assert a.shape == [2, 3]
+    assert list(a) == [1, 2, 3, 4, 5, 6]
+
+
+def test_array_from_length_mismatch_raises():
+    with pytest.raises(ValueError):
+        pyfacet.array_from([1, 2, 3, 4, 5], [2, 3])
+
+
+def test_diff():
+    a = [1, 2, 4, 7]
+
+    assert list(pyfacet.diff(a)) == [1, 2, 3]
+    assert list(pyfacet.diff(a, 2)) == [1, 1]
+
+
+def test_diff_along_axis():
+    a = pyfacet.array([[1, 2, 4], [2, 5, 9]])
+
+    res = pyfacet.diff(a, axis=0)
+
+    assert res.shape == [1, 3]
+    assert list(res) == [1, 3, 5]
+
+
+def test_astype_i64_rounding_modes():
+    a = pyfacet.array([-1.5, 1.5, 2.4])
+
+    res = a.astype("i64")
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [-1, 1, 2]
+
+    assert list(a.astype("i64", mode="trunc")) == [-1, 1, 2]
+    assert list(a.astype("i64", mode="floor")) == [-2, 1, 2]
+    assert list(a.astype("i64", mode="ceil")) == [-1, 2, 3]
+    assert list(a.astype("i64", mode="round")) == [-2, 2, 2]
+
+
+def test_astype_unknown_mode_raises():
+    a = pyfacet.array([-1.5, 1.5, 2.4])
+
+    with pytest.raises(ValueError):
+        a.astype("i64", mode="nearest")
+
+
+def test_astype_small_int_types():
+    a = pyfacet.array([-1.5, 0.5, 300.7])
+
+    b = a.astype("u8")
+    assert isinstance(b, pyfacet.NdArrayU8)
+    assert list(b) == [0, 0, 255]
+    assert list(a.astype("u8", mode="ceil")) == [0, 1, 255]
+
+    c = a.astype("i32", mode="round")
+    assert isinstance(c, pyfacet.NdArrayI32)
+    assert list(c) == [-2, 1, 301]
+    assert c.shape == [3]
+
+    assert list(c.astype("u8")) == [0, 1, 255]
+    assert list(c.astype("bool")) == [True, True, True]
+    assert list(b.astype("f32")) == [0.0, 0.0, 255.0]
+    assert list(pyfacet.NdArrayI([2], [2 ** 40, -1]).astype("i32")) == [2 ** 31 - 1, -1]
+
+
+def test_small_int_arrays_convert_to_float_inputs():
+    a = pyfacet.NdArrayU8([2, 2], [1, 2, 3, 4])
+
+    assert list(pyfacet.mean(a)) == [1.5, 3.5]
+
+    with pytest.raises(ValueError):
+        a.astype("f64")
+
+
+def test_zeros_like_keeps_int_type():
+    a = NdArrayI([2, 3], [1, 2, 3, 4, 5, 6])
+
+    res = pyfacet.zeros_like(a)
+
+    assert isinstance(res, NdArrayI)
+    assert res.shape == [2, 3]
+    assert list(res) == [0] * 6
+
+
+def test_ones_and_full_like():
+    a = pyfacet.array([[1.5, 2], [3, 4]])
+
+    res = pyfacet.ones_like(a)
+    assert isinstance(res, NdArrayD)
+    assert res.shape == [2, 2]
+    assert list(res) == [1] * 4
+
+    res = pyfacet.full_like(a, 0.5)
+    assert list(res) == [0.5] * 4
+
+
+def test_overflowing_shape_raises():
+    shape = [2 ** 32 - 1] * 4
+
+    for make in [pyfacet.zeros, pyfacet.ones, NdArrayD, NdArrayI]:
+        with pytest.raises(ValueError):
+            make(shape)
+
+
+def test_reciprocal():
+    assert list(pyfacet.reciprocal([2, 4])) == [0.5, 0.25]
+    assert list(pyfacet.reciprocal([0])) == [float("inf")]
+
+
+def test_negative():
+    assert list(pyfacet.negative([1, -2])) == [-1, 2]
+
+    res = pyfacet.negative(NdArrayI([2], [1, -2]))
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [-1, 2]
+
+
+def test_mod():
+    assert list(pyfacet.mod_([-1, 5], 3)) == [2, 2]
+    assert list(pyfacet.mod_([1.5, -1.5], -1)) == [-0.5, -0.5]
+
+    res = pyfacet.mod_(NdArrayI([3], [-1, 5, -7]), 3)
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [2, 2, 2]
+    assert list(pyfacet.mod_(NdArrayI([2], [7, -7]), -3)) == [-2, -1]
+
+
+def test_floor_divide():
+    assert list(pyfacet.floor_divide([7, -7], 2)) == [3, -4]
+
+    res = pyfacet.floor_divide(NdArrayI([2], [7, -7]), 2)
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [3, -4]
+
+    with pytest.raises(ZeroDivisionError):
+        pyfacet.floor_divide(NdArrayI([2], [7, -7]), 0)
+
+
+def test_copysign():
+    assert list(pyfacet.copysign(3, -1)) == [-3]
+    assert list(pyfacet.copysign([-1, 2, 3], [1, -1, 0])) == [1, -2, 3]
+
+
+def test_hypot():
+    assert list(pyfacet.hypot(3, 4)) == [5]
+    # the squares overflow f32
+    assert list(pyfacet.hypot([3e30, 0], [4e30, 2])) == pytest.approx([5e30, 2])
+
+
+def test_ndenumerate():
+    a = pyfacet.array([[1, 2], [3, 4]])
+
+    res = list(pyfacet.ndenumerate(a))
+
+    assert res == [((0, 0), 1), ((0, 1), 2), ((1, 0), 3), ((1, 1), 4)]
+
+
+def test_sum_wide():
+    i64_max = 2 ** 63 - 1
+    a = NdArrayI([2, 3], [i64_max, i64_max, -i64_max, 1, 2, 3])
+
+    assert list(a.sum_wide()) == [i64_max, 6]
+
+    with pytest.raises(OverflowError):
+        NdArrayI([2], [i64_max, 1]).sum_wide()
+
+
+def test_bench():
+    calls = []
+    a = pyfacet.ones([16, 16])
+
+    res = pyfacet.bench(lambda: calls.append(a.matmul(a)), repeats=10)
+
+    assert len(calls) == 10
+    assert 0 < res["min"] <= res["mean"] <= res["max"]
+
+
+def test_digitize():
+    a = [0.2, 6.4, 3.0]
+    bins = [0, 1, 2.5, 4, 10]
+
+    res = pyfacet.digitize(a, bins)
+
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [1, 4, 3]
+
+
+def test_digitize_right():
+    a = [-1, 1, 2.5, 11]
+    bins = [0, 1, 2.5, 4, 10]
+
+    assert list(pyfacet.digitize(a, bins)) == [0, 2, 3, 5]
+    assert list(pyfacet.digitize(a, bins, right=True)) == [0, 1, 2, 5]
+    assert list(pyfacet.digitize(a, list(reversed(bins)))) == [5, 3, 2, 0]
+
+
+def test_searchsorted():
+    res = pyfacet.searchsorted([1, 3, 5], [2, 4])
+
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [1, 2]
+    assert list(pyfacet.searchsorted([1, 3, 5], [0, 6])) == [0, 3]
+
+
+def test_searchsorted_side():
+    a = [1, 2, 2, 3]
+
+    assert list(pyfacet.searchsorted(a, [2, 3])) == [1, 3]
+    assert list(pyfacet.searchsorted(a, [2, 3], side="right")) == [3, 4]
+
+    with pytest.raises(ValueError):
+        pyfacet.searchsorted(a, [2], side="middle")
+    with pytest.raises(ValueError):
+        pyfacet.searchsorted([3, 1], [2])
+
+
+def test_rot90():
+    a = NdArrayD([2, 3], [1, 2, 3, 4, 5, 6])
+
+    res = pyfacet.rot90(a)
+
+    assert res.shape == [3, 2]
+    assert list(res) == [3, 6, 2, 5, 1, 4]
+
+    res = pyfacet.rot90(a, k=4)
+    assert res.shape == [2, 3]
+    assert (res == a).all()
+
+    res = pyfacet.rot90(pyfacet.rot90(a), k=-1)
+    assert (res == a).all()
+
+
+def test_rot90_axes():
+    a = NdArrayI([2, 1, 2], [1, 2, 3, 4])
+
+    res = pyfacet.rot90(a, 1, (0, 2))
+
+    assert isinstance(res, NdArrayI)
+    assert res.shape == [2, 1, 2]
+    assert list(res) == [2, 4, 1, 3]
+    assert list(pyfacet.rot90(a, 1, (2, 0))) == [3, 1, 4, 2]
+
+    with pytest.raises(ValueError):
+        pyfacet.rot90(a, 1, (1, 1))
+    with pytest.raises(ValueError):
+        pyfacet.rot90(NdArrayD([3], [1, 2, 3]))
+
+
+def test_interp():
+    res = pyfacet.interp([0.5, 1.5], [0, 1, 2], [0, 10, 20])
+
+    assert isinstance(res, NdArrayD)
+    assert list(res) == [5, 15]
+
+
+def test_interp_clamps_outside_of_xp():
+    xp = [1, 2, 4]
+    fp = [3, 2, 0]
+
+    res = pyfacet.interp(NdArrayD([2, 3], [-1, 1, 3, 4, 5, 2]), xp, fp)
+
+    assert res.shape == [2, 3]
+    assert list(res) == [3, 3, 1, 0, 0, 2]
+
+    with pytest.raises(ValueError):
+        pyfacet.interp([1], [2, 1], [0, 1])
+    with pytest.raises(ValueError):
+        pyfacet.interp([1], [1, 2], [0])
+
+
+def test_matmul_incompatible_shapes_raises():
+    a = pyfacet.ones([2, 3])
+    b = pyfacet.ones([2, 2])
+
+    with pytest.raises(ValueError) as err:
+        a.matmul(b)
+
+    assert "[2, 3]" in str(err.value)
+    assert "[2, 2]" in str(err.value)
+
+
+def test_matmul_transposed_operand():
+    a = NdArrayD([3, 2], [1, 2, 3, 4, 5, 6])
+    b = NdArrayD([3, 2], [1, 0, 0, 1, 1, 1])
+
+    c = a.T.matmul(b)
+
+    assert c.shape == [2, 2]
+    assert (c == NdArrayD([2, 2], [6, 8, 8, 10])).all()
+
+
+def test_complex_abs():
+    c = NdArrayC(array([3.0, 0.0]), array([4.0, -2.0]))
+
+    assert c.shape == [2]
+    assert (c.abs() == array([5.0, 2.0])).all()
+    assert (c.conj().imag() == array([-4.0, 2.0])).all()
+    assert (c.real() == array([3.0, 0.0])).all()
+
+
+def test_complex_arithmetic():
+    a = NdArrayC(array([1.0, 2.0]), array([1.0, 0.0]))
+    b = NdArrayC(array([0.0, 1.0]), array([1.0, 1.0]))
+
+    assert (a + b).get([0]) == (1.0, 2.0)
+    assert (a * b).get([0]) == (-1.0, 1.0)
+    assert (a - b).get([1]) == (1.0, -1.0)
+    assert (a / b).get([1]) == (1.0, -1.0)
+
+
+def test_mixed_int_float_arithmetic_promotes():
+    i = NdArrayI([2], [1, 2])
+    d = NdArrayD([2], [0.5, 0.5])
+
+    res = i + d
+    assert isinstance(res, NdArrayD)
+    assert (res == NdArrayD([2], [1.5, 2.5])).all()
+
+    res = d - i
+    assert isinstance(res, NdArrayD)
+    assert (res == NdArrayD([2], [-0.5, -1.5])).all()
+
+    assert (i * d == NdArrayD([2], [0.5, 1.0])).all()
+    assert (i / d == NdArrayD([2], [2.0, 4.0])).all()
+
+
+def test_int_arithmetic_stays_int():
+    res = NdArrayI([2], [1, 2]) + NdArrayI([2], [3, 4])
+    assert isinstance(res, NdArrayI)
+
+
+def test_apply_along_axis():
+    def cumsum(lane):
+        res = []
+        acc = 0
+        for x in lane:
+            acc += x
+            res.append(acc)
+        return res
+
+    a = NdArrayD([2, 3], [1, 2, 3, 4, 5, 6])
+
+    res = pyfacet.apply_along_axis(cumsum, 1, a)
+
+    assert res.shape == [2, 3]
+    assert list(res) == [1, 3, 6, 4, 9, 15]
+    assert list(pyfacet.apply_along_axis(cumsum, 0, a)) == [1, 2, 3, 5, 7, 9]
+
+
+def test_apply_along_axis_changes_shape():
+    a = NdArrayD([2, 3], [1, 2, 3, 4, 5, 6])
+
+    res = pyfacet.apply_along_axis(lambda lane: [min(lane), max(lane)], -1, a)
+    assert res.shape == [2, 2]
+    assert list(res) == [1, 3, 4, 6]
+
+    res = pyfacet.apply_along_axis(lambda lane: sum(lane), 0, a)
+    assert res.shape == [3]
+    assert list(res) == [5, 7, 9]
+
+    with pytest.raises(ValueError):
+        pyfacet.apply_along_axis(lambda lane: list(lane)[: int(lane[0])], 1, a)
+
+    def fail(lane):
+        raise KeyError("nope")
+
+    with pytest.raises(KeyError):
+        pyfacet.apply_along_axis(fail, 0, a)
+
+
+def test_scalar_inputs():
+    s = pyfacet.scalar(5.0)
+
+    for f in [pyfacet.argmax, pyfacet.argmin]:
+        res = f(s)
+        assert res.shape == []
+        assert list(res) == [0]
+        assert f(s, keepdims=True).shape == []
+
+    for f in [pyfacet.sum, pyfacet.mean, pyfacet.veclen]:
+        res = f(s)
+        assert res.shape == []
+        assert list(res) == [5]
+
+    assert list(pyfacet.veclen_squared(s)) == [25]
+    assert s.transpose().shape == []
+    assert list(s.T) == [5]
+
+    res = pyfacet.scalar(5.0).reshape([1, 1])
+    assert res.shape == [1, 1]
+    assert res.reshape([]).shape == []
+    assert list(res) == [5]
+
+    with pytest.raises(TypeError):
+        pyfacet.zeros(s)
+
+
+def test_debug_nan():
+    assert not pyfacet.get_debug_nan()
+
+    res = pyfacet.log(pyfacet.scalar(-1.0))
+    assert list(res)[0] != list(res)[0]
+
+    pyfacet.set_debug_nan(True)
+    try:
+        assert pyfacet.get_debug_nan()
+        with pytest.raises(ValueError):
+            pyfacet.log(pyfacet.scalar(-1.0))
+        with pytest.raises(ValueError):
+            NdArrayD([2], [1, 0]) / NdArrayD([2], [1, 0])
+        with pytest.raises(ValueError):
+            pyfacet.sum(NdArrayD([2], [3e38, 3e38]))
+
+        assert list(pyfacet.log(pyfacet.scalar(1.0))) == [0]
+    finally:
+        pyfacet.set_debug_nan(False)
+
+
+def test_flatten_order():
+    a = NdArrayD([2, 3], [1, 2, 3, 4, 5, 6])
+
+    res = a.flatten()
+    assert res.shape == [6]
+    assert list(res) == [1, 2, 3, 4, 5, 6]
+
+    res = a.flatten(order="F")
+    assert res.shape == [6]
+    assert list(res) == [1, 4, 2, 5, 3, 6]
+    assert a.shape == [2, 3]
+
+    t = NdArrayI([2, 2, 2], list(range(8)))
+    assert list(t.flatten("F")) == [0, 4, 2, 6, 1, 5, 3, 7]
+
+    with pytest.raises(ValueError):
+        a.flatten(order="K")
+
+
+def test_reshape_order():
+    a = NdArrayD([2, 3], [1, 2, 3, 4, 5, 6])
+
+    a.reshape([3, 2], order="F")
+
+    assert a.shape == [3, 2]
+    assert list(a) == [1, 5, 4, 3, 2, 6]
+    assert list(a.flatten("F")) == [1, 4, 2, 5, 3, 6]
+
+
+def test_atleast_nd():
+    s = pyfacet.scalar(5)
+
+    assert pyfacet.atleast_1d(s).shape == [1]
+    assert pyfacet.atleast_2d(s).shape == [1, 1]
+    assert list(pyfacet.atleast_2d(s)) == [5]
+
+    res = pyfacet.atleast_2d([1, 2, 3])
+    assert res.shape == [1, 3]
+    assert list(res) == [1, 2, 3]
+    assert pyfacet.atleast_1d([1, 2, 3]).shape == [3]
+
+    a = NdArrayI([2, 2], [1, 2, 3, 4])
+    res = pyfacet.atleast_2d(a)
+    assert isinstance(res, NdArrayI)
+    assert res.shape == [2, 2]
+    assert pyfacet.atleast_2d(NdArrayD([2, 1, 2])).shape == [2, 1, 2]
+
+
+def test_masked_select():
+    a = pyfacet.array([-1, 2, -3, 4])
+
+    res = pyfacet.masked_select(a, a > pyfacet.zeros(4))
+    assert res.shape == [2]
+    assert list(res) == [2, 4]
+
+    m = NdArrayD([2, 3], [1, 2, 3, 4, 5, 6])
+    res = pyfacet.masked_select(m, [1, 0, 1])
+    assert list(res) == [1, 3, 4, 6]
+
+    res = pyfacet.masked_select(NdArrayI([2, 2], [1, 2, 3, 4]), [[0, 1], [1, 0]])
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [2, 3]
+
+    assert pyfacet.masked_select(m, pyfacet.scalar(0)).shape == [0]
+
+    with pytest.raises(ValueError):
+        pyfacet.masked_select(m, [1, 0])
+
+
+def test_choose():
+    choices = [pyfacet.full_like(pyfacet.zeros([2, 3]), v) for v in [10, 20, 30]]
+    indices = NdArrayI([2, 3], [0, 1, 2, 2, 1, 0])
+
+    res = pyfacet.choose(indices, choices)
+    assert res.shape == [2, 3]
+    assert list(res) == [10, 20, 30, 30, 20, 10]
+
+    ints = [NdArrayI([2], [1, 2]), NdArrayI([2], [3, 4])]
+    res = pyfacet.choose(NdArrayI([2], [1, 0]), ints)
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [3, 2]
+
+    with pytest.raises(IndexError):
+        pyfacet.choose(NdArrayI([2], [0, 3]), ints)
+    with pytest.raises(IndexError):
+        pyfacet.choose(NdArrayI([2], [0, -1]), ints)
+    with pytest.raises(ValueError):
+        pyfacet.choose(indices, ints)
+
+
+def test_unravel_index():
+    # same decomposition as the strides of `get_index`: 61 = 1 * 32 + 3 * 8 + 5
+    assert pyfacet.unravel_index(61, (2, 4, 8)) == (1, 3, 5)
+    assert pyfacet.ravel_multi_index((1, 3, 5), (2, 4, 8)) == 61
+
+    index = pyfacet.unravel_index(5, (2, 4, 8))
+    assert index == (0, 0, 5)
+    assert pyfacet.ravel_multi_index(index, (2, 4, 8)) == 5
+
+    a = NdArrayD([2, 3], [1, 9, 3, 4, 5, 6])
+    assert pyfacet.unravel_index(1, a.shape) == (0, 1)
+
+    with pytest.raises(ValueError):
+        pyfacet.unravel_index(64, (2, 4, 8))
+    with pytest.raises(ValueError):
+        pyfacet.ravel_multi_index((1, 4, 0), (2, 4, 8))
+    with pytest.raises(ValueError):
+        pyfacet.ravel_multi_index((1, 3), (2, 4, 8))
+
+
+def test_gradient():
+    assert list(pyfacet.gradient([1, 2, 4, 7, 11])) == [1, 1.5, 2.5, 3.5, 4]
+    assert list(pyfacet.gradient([1, 2, 4, 7, 11], spacing=2)) == [0.5, 0.75, 1.25, 1.75, 2]
+
+    a = pyfacet.array([[1, 2, 6], [3, 4, 5]])
+    res = pyfacet.gradient(a, axis=0)
+    assert res.shape == [2, 3]
+    assert list(res) == [2, 2, -1, 2, 2, -1]
+
+    with pytest.raises(ValueError):
+        pyfacet.gradient(pyfacet.array([[1, 2, 3]]), axis=0)
+
+
+def test_float_predicates():
+    a = pyfacet.array([float("nan"), float("inf"), 1.5, float("-inf")])
+
+    nan = pyfacet.isnan(a)
+    assert isinstance(nan, NdArrayI)
+    assert nan.shape == [4]
+    assert list(nan) == [1, 0, 0, 0]
+    assert list(pyfacet.isinf(a)) == [0, 1, 0, 1]
+    assert list(pyfacet.isfinite(a)) == [0, 0, 1, 0]
+
+
+def test_partition():
+    values = [7, 2, 9, 1, 5, 8, 3]
+    res = list(pyfacet.partition(values, 2))
+    assert res[2] == 3
+    assert all(x < 3 for x in res[:2])
+    assert all(x > 3 for x in res[3:])
+    assert sorted(res) == sorted(values)
+
+    index = pyfacet.argpartition(values, 2)
+    assert isinstance(index, NdArrayI)
+    assert values[list(index)[2]] == 3
+
+    a = pyfacet.array([[3, 1, 2], [6, 5, 4]])
+    res = pyfacet.partition(a, 0, axis=0)
+    assert list(res) == [3, 1, 2, 6, 5, 4]
+    res = pyfacet.take_along_axis(a, pyfacet.argpartition(a, -1), 1)
+    assert list(res)[2::3] == [3, 6]
+
+    with pytest.raises(IndexError):
+        pyfacet.partition(values, 7)
+
+
+def test_moveaxis():
+    a = NdArrayD([2, 3, 4], list(range(24)))
+
+    res = pyfacet.moveaxis(a, 1, 2)
+    assert res.shape == [2, 4, 3]
+    assert list(res)[:6] == [0, 4, 8, 1, 5, 9]
+
+    nchw = NdArrayI([1, 2, 1, 3], [1, 2, 3, 4, 5, 6])
+    nhwc = pyfacet.moveaxis(nchw, 1, -1)
+    assert isinstance(nhwc, NdArrayI)
+    assert nhwc.shape == [1, 1, 3, 2]
+    assert list(nhwc) == [1, 4, 2, 5, 3, 6]
+
+    assert pyfacet.moveaxis(a, [0, 1], [2, 0]).shape == [3, 4, 2]
+
+    with pytest.raises(ValueError):
+        pyfacet.moveaxis(a, [0, 1], [2])
+    with pytest.raises(ValueError):
+        pyfacet.moveaxis(a, 3, 0)
+
+
+def test_cummax_cummin():
+    assert list(pyfacet.cummax([1, 3, 2, 5, 4])) == [1, 3, 3, 5, 5]
+    assert list(pyfacet.cummin([4, 5, 2, 3, 1])) == [4, 4, 2, 2, 1]
+
+    a = pyfacet.array([[1, 5], [3, 2], [2, 6]])
+    res = pyfacet.cummax(a, axis=0)
+    assert res.shape == [3, 2]
+    assert list(res) == [1, 5, 3, 5, 3, 6]
+    assert list(pyfacet.cummin(a, axis=0)) == [1, 5, 1, 2, 1, 2]
+
+    res = list(pyfacet.cummax([1, float("nan"), 3]))
+    assert res[0] == 1
+    assert all(x != x for x in res[1:])
+
+
+def test_top_k():
+    a = pyfacet.array([[3, 9, 1, 7], [4, 2, 8, 6]])
+
+    values, indices = pyfacet.top_k(a, 2)
+    assert isinstance(indices, NdArrayI)
+    assert values.shape == [2, 2]
+    assert list(values) == [9, 7, 8, 6]
+    assert list(indices) == [1, 3, 2, 3]
+
+    values, indices = pyfacet.top_k(a, 2, largest=False)
+    assert list(values) == [1, 3, 2, 4]
+    assert list(indices) == [2, 0, 1, 0]
+
+    values, indices = pyfacet.top_k(a, 1, axis=0)
+    assert values.shape == [1, 4]
+    assert list(values) == [4, 9, 8, 7]
+    assert list(indices) == [1, 0, 1, 0]
+
+    values, _ = pyfacet.top_k([5, 1, 4, 2], 3, sorted=False)
+    assert sorted(values) == [2, 4, 5]
+
+    with pytest.raises(ValueError):
+        pyfacet.top_k(a, 5)
@@ -0,0 +1,63 @@
+import pyfacet as pf
+from pyfacet.optimizer import SGD, Adam
+
+
+class FakeLayer:
+    def __init__(self, id):
+        self.id = id
+        self.weights = pf.Parameter(pf.array([[1.0, 2.0], [3.0, 4.0]]))
+        self.weights.grad = pf.array([[0.1, -0.2], [0.3, 0.4]])
+        self.biases = pf.Parameter(pf.array([0.5, -0.5]))
+        self.biases.grad = pf.array([0.2, -0.1])
+
+    def parameters(self):
+        return [self.weights, self.biases]
+
+
+def assert_close(a, b):
+    tolerance = pf.zeros(a.shape) + pf.scalar(1e-6)
+    assert (pf.abs(a - b) < tolerance).all()
+
+
+def step(optim, layer):
+    optim.pre_update()
+    optim.update_params(layer)
+
+
+def assert_same_update(make_optim):
+    accumulated = FakeLayer(0)
+    optim = make_optim(accumulation_steps=2)
+    step(optim, accumulated)
+    # the first step only accumulates
+    assert (accumulated.weights.value == FakeLayer(0).weights.value).all()
+    step(optim, accumulated)
+
+    doubled = FakeLayer(0)
+    doubled.weights.grad = doubled.weights.grad * pf.scalar(2.0)
+    doubled.biases.grad = doubled.biases.grad * pf.scalar(2.0)
+    step(make_optim(), doubled)
+
+    assert_close(accumulated.weights.value, doubled.weights.value)
+    assert_close(accumulated.biases.value, doubled.biases.value)
+    assert optim.iters == 1
+
+
+def test_sgd_accumulation_steps():
+    assert_same_update(lambda **kw: SGD(learning_rate=0.1, **kw))
+    assert_same_update(lambda **kw: SGD(learning_rate=0.1, momentum=0.5, **kw))
+
+
+def test_adam_accumulation_steps():
+    assert_same_update(lambda **kw: Adam(learning_rate=0.1, **kw))
+
+
+def test_optimizers_update_the_layer():
+    for optim in [SGD(learning_rate=0.1, momentum=0.5), Adam(learning_rate=0.1)]:
+        layer = pf.DenseLayer(2, 2)
+        before = layer.weights
+        layer.forward(pf.array([[1, 2]]))
+        layer.backward(pf.ones([1, 2]))
+
+        step(optim, layer)
+
+        assert not (layer.weights == before).all()
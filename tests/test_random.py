@@ -0,0 +1,58 @@
+import pytest
+import pyfacet
+from pyfacet import NdArrayD
+
+
+def test_seeded_permutation_is_deterministic():
+    pyfacet.seed(42)
+    a = list(pyfacet.permutation(5))
+    pyfacet.seed(42)
+    b = list(pyfacet.permutation(5))
+
+    assert a == b
+    assert sorted(a) == [0, 1, 2, 3, 4]
+
+
+def test_shuffle_keeps_rows():
+    a = NdArrayD([5, 2], [i // 2 for i in range(10)])
+
+    pyfacet.seed(1)
+    pyfacet.shuffle(a)
+
+    rows = [tuple(row) for row in a.iter_rows()]
+    assert all(x == y for x, y in rows)
+    assert sorted(x for x, _ in rows) == [0, 1, 2, 3, 4]
+
+
+def test_seeded_shuffle_matches_permutation_order():
+    x = NdArrayD([4, 1], [0, 1, 2, 3])
+    y = NdArrayD([4], [0, 1, 2, 3])
+
+    pyfacet.seed(7)
+    pyfacet.shuffle(x)
+    pyfacet.seed(7)
+    pyfacet.shuffle(y)
+
+    assert list(x) == list(y)
+
+
+def test_seeded_dropout():
+    a = pyfacet.array([float(x) for x in range(1, 101)])
+
+    out, mask = pyfacet.dropout(a, 0.5, seed=7)
+    out2, mask2 = pyfacet.dropout(a, 0.5, seed=7)
+    assert list(mask) == list(mask2)
+    assert list(out) == list(out2)
+
+    assert set(mask) == {0, 2}
+    for x, y, m in zip(a, out, mask):
+        assert y == (2 * x if m else 0)
+
+    out, mask = pyfacet.dropout(a, 0.5, training=False)
+    assert list(out) == list(a)
+    assert set(mask) == {1}
+
+
+def test_dropout_invalid_probability():
+    with pytest.raises(ValueError):
+        pyfacet.dropout([1.0, 2.0], 1.5)
@@ -0,0 +1,39 @@
+import pytest
+import pyfacet
+from pyfacet import NdArrayD, NdArrayI, from_coo, to_coo
+
+
+def test_coo_round_trip():
+    dense = NdArrayD([4, 4], [0, 0, 3, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, -2])
+
+    indices, values = to_coo(dense)
+
+    assert isinstance(indices, NdArrayI)
+    assert indices.shape == [2, 3]
+    assert list(indices) == [0, 2, 3, 2, 0, 3]
+    assert list(values) == [3, 1, -2]
+
+    res = from_coo(indices, values, dense.shape)
+    assert res.shape == [4, 4]
+    assert (res == dense).all()
+
+
+def test_coo_all_zeros():
+    indices, values = to_coo(NdArrayD([2, 3], [0] * 6))
+
+    assert indices.shape == [2, 0]
+    assert values.shape == [0]
+    assert list(from_coo(indices, values, [2, 3])) == [0] * 6
+
+
+def test_from_coo_sums_repeated_indices():
+    res = from_coo(NdArrayI([1, 3], [1, 1, 2]), [1, 2, 4], [3])
+
+    assert list(res) == [0, 3, 4]
+
+
+def test_from_coo_errors():
+    with pytest.raises(IndexError):
+        from_coo(NdArrayI([1, 1], [3]), [1], [3])
+    with pytest.raises(ValueError):
+        from_coo(NdArrayI([2, 1], [0, 0]), [1], [3])
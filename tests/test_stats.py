@@ -0,0 +1,59 @@
+import pytest
+import pyfacet
+from pyfacet import NdArrayD, RunningMean, RunningVar
+
+
+def assert_close(a, b):
+    assert list(a) == pytest.approx(list(b), abs=1e-4)
+
+
+def test_running_stats_match_concatenation():
+    first = [[1, 2], [3, 5], [4, 0]]
+    second = [[10, -1], [6, 2]]
+    full = pyfacet.array(first + second)
+
+    mean = RunningMean()
+    var = RunningVar()
+    for batch in [first, second]:
+        mean.update(pyfacet.array(batch))
+        var.update(pyfacet.array(batch))
+
+    assert mean.count == 5
+    assert mean.result().shape == [2]
+    assert_close(mean.result(), pyfacet.mean(full.T))
+    assert_close(var.mean(), pyfacet.mean(full.T))
+    assert_close(var.result(), pyfacet.std_squared(full.T))
+
+
+def test_running_stats_vector_batches():
+    var = RunningVar()
+    var.update(NdArrayD([3], [1, 2, 3]))
+    var.update(NdArrayD([1], [6]))
+
+    assert var.result().shape == []
+    assert list(var.result()) == pytest.approx([3.5])
+    assert list(var.mean()) == pytest.approx([3])
+
+
+def test_running_stats_errors():
+    mean = RunningMean()
+    with pytest.raises(ValueError):
+        mean.result()
+
+    mean.update(NdArrayD([2, 2], [1, 2, 3, 4]))
+    with pytest.raises(ValueError):
+        mean.update(NdArrayD([2, 3], [1, 2, 3, 4, 5, 6]))
+
+
+def test_mean_var():
+    a = NdArrayD([3, 2], [1, 2, 3, 2, 5, 2])
+
+    mean, var = pyfacet.mean_var(a)
+    assert mean.shape == [2]
+    assert list(mean) == [3, 2]
+    assert abs(list(var)[0] - 8 / 3) < 1e-6
+    assert list(var)[1] == 0
+
+    mean, var = pyfacet.mean_var(a, axis=-1)
+    assert list(mean) == [1.5, 2.5, 3.5]
+    assert list(var) == [0.25, 0.25, 2.25]
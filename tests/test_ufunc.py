@@ -0,0 +1,70 @@
+import pytest
+import pyfacet
+from pyfacet import NdArrayD, NdArrayI
+
+
+def ufunc(name):
+    """
+    stand-in for a numpy ufunc, the protocol only looks at the name
+    """
+
+    def f(*args):
+        raise AssertionError("the ufunc should be dispatched to pyfacet")
+
+    f.__name__ = name
+    return f
+
+
+def test_array_ufunc_dispatch():
+    a = NdArrayD([3], [1, 2, 3])
+    b = NdArrayD([3], [10, 20, 30])
+
+    res = a.__array_ufunc__(ufunc("add"), "__call__", a, b)
+    assert isinstance(res, NdArrayD)
+    assert list(res) == [11, 22, 33]
+
+    res = a.__array_ufunc__(ufunc("maximum"), "__call__", a, 2)
+    assert list(res) == [2, 2, 3]
+
+    res = a.__array_ufunc__(ufunc("negative"), "__call__", a)
+    assert list(res) == [-1, -2, -3]
+
+
+def test_array_ufunc_int():
+    a = NdArrayI([3], [1, 2, 3])
+
+    res = a.__array_ufunc__(ufunc("multiply"), "__call__", a, a)
+    assert isinstance(res, NdArrayI)
+    assert list(res) == [1, 4, 9]
+
+
+def test_array_ufunc_not_implemented():
+    a = NdArrayD([3], [1, 2, 3])
+
+    assert a.__array_ufunc__(ufunc("arctan2"), "__call__", a, a) is NotImplemented
+    assert a.__array_ufunc__(ufunc("add"), "reduce", a) is NotImplemented
+    assert a.__array_ufunc__(ufunc("add"), "__call__", a, a, out=(a,)) is NotImplemented
+    assert a.__array_ufunc__(ufunc("add"), "__call__", a, [1, 2, 3]) is NotImplemented
+
+
+def test_numpy_add():
+    np = pytest.importorskip("numpy")
+
+    a = NdArrayD([2, 2], [1, 2, 3, 4])
+    b = NdArrayD([2, 2], [4, 3, 2, 1])
+
+    res = np.add(a, b)
+
+    assert isinstance(res, NdArrayD)
+    assert list(res) == [5, 5, 5, 5]
+
+
+def test_maximum_minimum():
+    a = NdArrayD([3], [1, float("nan"), 3])
+    b = NdArrayD([3], [2, 0, 1])
+
+    res = pyfacet.maximum(a, b)
+    assert list(res)[0] == 2 and list(res)[2] == 3
+    assert list(res)[1] != list(res)[1]
+
+    assert list(pyfacet.minimum([1, 5], [3, 2])) == [1, 2]